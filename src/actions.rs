@@ -0,0 +1,2075 @@
+//! Code generation for the per-field actions and the lookups they share.
+
+use super::*;
+
+/// Expression for the error of `element` (a `&str`) not being found by `selector` (a `By`)
+pub(crate) fn not_found_error(
+    options: &StructOptions,
+    element: TokenStream2,
+    selector: TokenStream2,
+) -> TokenStream2 {
+    if options.structured_errors {
+        let runtime = runtime_path();
+        let anyhow = &options.crate_paths.anyhow;
+        quote! {
+            #anyhow::Error::new(#runtime::ThirtyfourActionsError::NotFound {
+                element: #element.to_string(),
+                selector: format!("{:?}", #selector),
+            })
+        }
+    } else {
+        let (_, message_error) = options.error_tokens();
+        quote!(#message_error(format!("Element {} not found", #element)))
+    }
+}
+
+/// Expression for the error of waiting `waited` on `element` (a `&str`) in vain.
+///
+/// `message` is the format string and arguments of the error message.
+pub(crate) fn timeout_error(
+    options: &StructOptions,
+    element: TokenStream2,
+    waited: TokenStream2,
+    message: TokenStream2,
+) -> TokenStream2 {
+    if options.structured_errors {
+        let runtime = runtime_path();
+        let anyhow = &options.crate_paths.anyhow;
+        quote! {
+            #anyhow::Error::new(#runtime::ThirtyfourActionsError::Timeout {
+                element: #element.to_string(),
+                waited: #waited,
+            })
+            .context(format!(#message))
+        }
+    } else {
+        let (_, message_error) = options.error_tokens();
+        quote!(#message_error(format!(#message)))
+    }
+}
+
+/// Body polling a string read from a field's element until `check` (over `value`) holds,
+/// returning the value.
+///
+/// `read` reads the value from `element` as a `WebDriverResult<Option<String>>`. `subject` and
+/// `expectation` are format strings and arguments naming the value and completing "to ..." in the
+/// timeout error.
+pub(crate) fn value_poll_tokens(
+    field: &FieldContext,
+    read: TokenStream2,
+    subject: TokenStream2,
+    check: TokenStream2,
+    expectation: TokenStream2,
+) -> TokenStream2 {
+    let FieldContext {
+        field_ident,
+        field_name_str,
+        query_fn_ident,
+        options,
+        struct_options,
+        lookups,
+    } = *field;
+    let tokio = &struct_options.crate_paths.tokio;
+    let poll_ms = struct_options.poll_ms();
+    let keyed_locator = keyed_locator_binding(field_ident, options, &lookups.naming, None);
+    // Only the query knows which match an `nth` field picks, and how to resolve a `resolver` or
+    // check a stored element
+    let lookup = if options.nth.is_some() || options.selectorless() {
+        quote!(self.#query_fn_ident(driver).await)
+    } else {
+        let lookup = scoped_query(
+            lookups,
+            field_ident,
+            field_locator(field_ident, options, lookups),
+            quote!(.nowait().first_opt().await),
+            |_| quote!(Ok(None)),
+        );
+        quote!(#lookup.ok().flatten())
+    };
+    let timeout = timeout_error(
+        struct_options,
+        quote!(#field_name_str),
+        quote!(Duration::from_secs(timeout_secs)),
+        quote! {
+            "Timed out waiting for {} to {} (last value: {:?})",
+            format!(#subject),
+            format!(#expectation),
+            last_value
+        },
+    );
+    quote! {
+        use std::time::{Duration, Instant};
+        #keyed_locator
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let mut last_value = None;
+        loop {
+            if let Some(element) = #lookup {
+                if let Ok(Some(value)) = #read {
+                    if #check {
+                        return Ok(value);
+                    }
+                    last_value = Some(value);
+                }
+            }
+            if Instant::now() >= deadline {
+                return Err(#timeout);
+            }
+            #tokio::time::sleep(Duration::from_millis(#poll_ms)).await;
+        }
+    }
+}
+
+/// Element a field is searched within instead of the whole document
+#[derive(Clone)]
+pub(crate) struct Scope {
+    /// Field whose element scopes the search
+    pub(crate) field: Ident,
+    /// Search the shadow root of that element rather than its children
+    pub(crate) shadow: bool,
+}
+
+/// How fields are looked up beyond their own selector, by field name
+#[derive(Default)]
+pub(crate) struct Lookups {
+    /// Element each scoped field is searched within
+    pub(crate) scopes: HashMap<String, Scope>,
+    /// Selectors tried, in order, when a field's own selector matches nothing
+    pub(crate) fallbacks: HashMap<String, Vec<Selector>>,
+    /// `ElementQuery` filter calls every match of a field must pass
+    pub(crate) filters: HashMap<String, TokenStream2>,
+    /// `locator_builder` of each field holding a locator string
+    pub(crate) builders: HashMap<String, syn::Path>,
+    /// How the names of the methods generated for each field are formed, from `naming`
+    pub(crate) naming: MethodNaming,
+    /// Paths generated code reaches its dependencies by, from the struct's overrides
+    pub(crate) crate_paths: CratePaths,
+}
+
+/// Expression for the `By` of another locator field, e.g. a marker, built from its locator
+/// string when it holds one.
+pub(crate) fn stored_locator(lookups: &Lookups, field: &Ident) -> TokenStream2 {
+    let thirtyfour = &lookups.crate_paths.thirtyfour;
+    match lookups.builders.get(&field.to_string()) {
+        Some(builder) => quote!(#builder(&self.#field)),
+        None => quote!(#thirtyfour::By::clone(&self.#field)),
+    }
+}
+
+/// Expression running `query` (the chain following `.query(locator)`) for `field` with its
+/// fallback selectors, from the element or shadow root that scopes it, or from `driver` for
+/// fields searched across the whole document.
+///
+/// `missing` gives the expression's value, from the scope field's name, when the scope element
+/// itself is not found.
+pub(crate) fn scoped_query(
+    lookups: &Lookups,
+    field: &Ident,
+    locator: TokenStream2,
+    query: TokenStream2,
+    missing: impl Fn(&str) -> TokenStream2,
+) -> TokenStream2 {
+    let thirtyfour = &lookups.crate_paths.thirtyfour;
+    let name = field.to_string();
+    // Filters apply to the selector before them, so each fallback repeats them
+    let filters = lookups.filters.get(&name).cloned().unwrap_or_default();
+    let fallbacks = lookups.fallbacks.get(&name).into_iter().flatten().map(
+        |Selector { constructor, value }| quote!(.or(#thirtyfour::By::#constructor(#value)) #filters),
+    );
+    scope_query(
+        lookups.scopes.get(&name),
+        &lookups.naming,
+        locator,
+        quote!(#filters #(#fallbacks)* #query),
+        missing,
+    )
+}
+
+/// Expression running `query` on `locator` from `scope`, or from `driver` without one.
+pub(crate) fn scope_query(
+    scope: Option<&Scope>,
+    naming: &MethodNaming,
+    locator: TokenStream2,
+    query: TokenStream2,
+    missing: impl Fn(&str) -> TokenStream2,
+) -> TokenStream2 {
+    match scope {
+        Some(Scope {
+            field: scope,
+            shadow: false,
+        }) => {
+            let scope_query_fn_ident = naming.method_ident("query", scope, "");
+            let missing = missing(&scope.to_string());
+            quote! {
+                match self.#scope_query_fn_ident(driver).await {
+                    Some(scope) => scope.query(#locator)#query,
+                    None => #missing,
+                }
+            }
+        }
+        Some(Scope {
+            field: host,
+            shadow: true,
+        }) => {
+            let host_query_fn_ident = naming.method_ident("query", host, "");
+            let missing = missing(&host.to_string());
+            quote! {
+                match self.#host_query_fn_ident(driver).await {
+                    Some(host) => match host.get_shadow_root().await {
+                        Ok(scope) => scope.query(#locator)#query,
+                        Err(e) => Err(e),
+                    },
+                    None => #missing,
+                }
+            }
+        }
+        None => quote!(driver.query(#locator)#query),
+    }
+}
+
+/// Query result reporting that the scope element `scope_name` of a waited-for field is absent
+pub(crate) fn scope_missing(scope_name: &str, thirtyfour: &syn::Path) -> TokenStream2 {
+    quote! {
+        Err(#thirtyfour::error::WebDriverError::CustomError(format!("{} not found", #scope_name)))
+    }
+}
+
+/// Statements waiting up to `timeout_secs` for a field to be visible, binding it to `element`.
+///
+/// Expects `std::time::Duration` in scope.
+pub(crate) fn visible_element_tokens(field: &FieldContext) -> TokenStream2 {
+    let FieldContext {
+        field_ident,
+        field_name_str,
+        query_fn_ident,
+        options,
+        struct_options,
+        lookups,
+    } = *field;
+    let tokio = &struct_options.crate_paths.tokio;
+    let poll_ms = struct_options.poll_ms();
+    if options.nth.is_some() || options.selectorless() {
+        // Only the query knows which match to pick or how to resolve the field, so poll it
+        let message = match options.nth {
+            Some(nth) => quote!(
+                "Timed out waiting for {} (match {}) to be visible",
+                #field_name_str,
+                #nth
+            ),
+            None => quote!("Timed out waiting for {} to be visible", #field_name_str),
+        };
+        let timeout = timeout_error(
+            struct_options,
+            quote!(#field_name_str),
+            quote!(Duration::from_secs(timeout_secs)),
+            message,
+        );
+        quote! {
+            let deadline = std::time::Instant::now() + Duration::from_secs(timeout_secs);
+            let element = loop {
+                if let Some(element) = self.#query_fn_ident(driver).await {
+                    if element.is_displayed().await.unwrap_or(false) {
+                        break element;
+                    }
+                }
+                if std::time::Instant::now() >= deadline {
+                    return Err(#timeout);
+                }
+                #tokio::time::sleep(Duration::from_millis(#poll_ms)).await;
+            };
+        }
+    } else {
+        let timeout = timeout_error(
+            struct_options,
+            quote!(#field_name_str),
+            quote!(Duration::from_secs(timeout_secs)),
+            quote!("Timed out waiting for {} to be visible: {}", #field_name_str, e),
+        );
+        let lookup = scoped_query(
+            lookups,
+            field_ident,
+            field_locator(field_ident, options, lookups),
+            quote! {
+                .wait(Duration::from_secs(timeout_secs), Duration::from_millis(#poll_ms))
+                .visible()
+                .first()
+                .await
+            },
+            |scope_name| scope_missing(scope_name, &lookups.crate_paths.thirtyfour),
+        );
+        let keyed_locator = keyed_locator_binding(field_ident, options, &lookups.naming, None);
+        quote! {
+            #keyed_locator
+            let element = #lookup.map_err(|e| #timeout)?;
+        }
+    }
+}
+
+/// Expression storing the `By` built by `by` in a locator field of type `ty`.
+///
+/// Besides `By` itself, fields may hold a shared `Arc<By>`, `Rc<By>` or `Cow<'static, By>`
+/// (or a `Box<By>`); generated code reads them through `thirtyfour::By::clone(&self.field)`.
+pub(crate) fn locator_init(ty: &syn::Type, by: TokenStream2) -> TokenStream2 {
+    let wrapper = match ty {
+        syn::Type::Path(path) => path.path.segments.last().map(|seg| seg.ident.to_string()),
+        _ => None,
+    };
+    match wrapper.as_deref() {
+        Some("Cow") => quote!(std::borrow::Cow::Owned(#by)),
+        Some("Arc" | "Rc" | "Box") => quote!(std::convert::From::from(#by)),
+        _ => by,
+    }
+}
+
+/// Whether a field of type `ty` holds an already located element, a `WebElement` or an
+/// `Arc<WebElement>`
+pub(crate) fn is_stored_element(ty: &syn::Type) -> bool {
+    let syn::Type::Path(path) = ty else {
+        return false;
+    };
+    match path.path.segments.last() {
+        Some(segment) if segment.ident == "WebElement" => true,
+        Some(segment) if segment.ident == "Arc" => result_ok_type(ty).is_some_and(
+            |inner| matches!(inner, syn::Type::Path(inner) if inner.path.segments.last().is_some_and(|seg| seg.ident == "WebElement")),
+        ),
+        _ => false,
+    }
+}
+
+/// Whether a field of type `ty` holds a locator string, a `String` or `&str`, for
+/// `locator_builder` to build into a `By`
+pub(crate) fn is_locator_string(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|seg| seg.ident == "String"),
+        syn::Type::Reference(reference) => {
+            matches!(&*reference.elem, syn::Type::Path(path) if path.path.is_ident("str"))
+        }
+        _ => false,
+    }
+}
+
+/// Whether a locator field of type `ty` is a map of selectors by key, such as
+/// `HashMap<String, By>` or a `BTreeMap`
+pub(crate) fn is_selector_map(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|seg| seg.ident == "HashMap" || seg.ident == "BTreeMap"),
+        _ => false,
+    }
+}
+
+/// Statement binding `keyed_locator`, the selector a keyed field's `key` argument picks, for
+/// [`field_locator`]; a missing key runs `on_missing` with the error bound to `e`, or returns the
+/// error without one. Empty for other fields.
+pub(crate) fn keyed_locator_binding(
+    field_ident: &Ident,
+    options: &FieldOptions,
+    naming: &MethodNaming,
+    on_missing: Option<TokenStream2>,
+) -> TokenStream2 {
+    if !options.keyed {
+        return quote!();
+    }
+    let locator_fn_ident = naming.method_ident("locator", field_ident, "");
+    match on_missing {
+        Some(on_missing) => quote! {
+            let keyed_locator = match self.#locator_fn_ident(key) {
+                Ok(locator) => locator,
+                Err(e) => #on_missing,
+            };
+        },
+        None => quote!(let keyed_locator = self.#locator_fn_ident(key)?;),
+    }
+}
+
+/// Expression building the `By` a field is queried with.
+///
+/// A selector with `{placeholder}`s is filled in by `locator_<field>` from the method
+/// parameters of the same names, a keyed field reads the `keyed_locator` bound by
+/// [`keyed_locator_binding`], and a locator string goes through the struct's `locator_builder`.
+pub(crate) fn field_locator(
+    field_ident: &Ident,
+    options: &FieldOptions,
+    lookups: &Lookups,
+) -> TokenStream2 {
+    let thirtyfour = &lookups.crate_paths.thirtyfour;
+    if options.keyed {
+        return quote!(#thirtyfour::By::clone(&keyed_locator));
+    }
+    if let Some(builder) = &options.built_with {
+        return quote!(#builder(&self.#field_ident));
+    }
+    match &options.selector {
+        Some(selector) if !selector.placeholders().is_empty() => {
+            let locator_fn_ident = lookups.naming.method_ident("locator", field_ident, "");
+            let placeholders = selector.placeholders();
+            quote!(self.#locator_fn_ident(#(#placeholders),*))
+        }
+        _ => quote!(#thirtyfour::By::clone(&self.#field_ident)),
+    }
+}
+
+/// `Debug` expression describing how a field is located, for errors and logs
+pub(crate) fn selector_debug(field_ident: &Ident, options: &FieldOptions) -> TokenStream2 {
+    if options.resolver {
+        quote!("ElementResolver")
+    } else if options.stored_element {
+        quote!("stored WebElement")
+    } else {
+        quote!(self.#field_ident)
+    }
+}
+
+/// Statements logging the outcome of a field query that found no element.
+///
+/// `error` is a `Display` expression describing the failure, or `None` when the selector simply
+/// matched nothing. Events go through `tracing` with the `tracing` feature, `log` otherwise.
+pub(crate) fn query_event(
+    selector: &TokenStream2,
+    field_name_str: &str,
+    error: Option<TokenStream2>,
+    log: &syn::Path,
+) -> TokenStream2 {
+    match (error, cfg!(feature = "tracing")) {
+        (Some(error), true) => quote! {
+            tracing::error!(element = #field_name_str, selector = ?#selector, action = "query", error = %#error, "Error querying element");
+        },
+        (Some(error), false) => quote! {
+            #log::error!("Error querying element {}: {}", #field_name_str, #error);
+        },
+        (None, true) => quote! {
+            tracing::debug!(element = #field_name_str, selector = ?#selector, action = "query", "Element not found");
+        },
+        (None, false) => quote! {
+            #log::debug!("Element {} not found ({:?})", #field_name_str, #selector);
+        },
+    }
+}
+
+/// Generate `<action>_<field>_once` for the field's `click` and `submit` actions, which skip the
+/// action when its `done_when`/`done_when_url` effect is already visible.
+///
+/// Lets retry policies repeat a mutating action without submitting twice.
+pub(crate) fn idempotent_methods(field: &FieldContext, actions: &[String]) -> Vec<TokenStream2> {
+    let FieldContext {
+        field_ident,
+        field_name_str,
+        options,
+        struct_options,
+        lookups,
+        ..
+    } = *field;
+    let thirtyfour = &struct_options.crate_paths.thirtyfour;
+    if options.done_when.is_none() && options.done_when_url.is_none() {
+        return Vec::new();
+    }
+    let (error_type, _) = struct_options.error_tokens();
+    let (driver_error, driver_source) = struct_options.driver_error_tokens();
+
+    let mut checks = Vec::new();
+    if let Some(marker) = &options.done_when {
+        let lookup = scoped_query(
+            lookups,
+            marker,
+            stored_locator(lookups, marker),
+            quote!(.nowait().first_opt().await),
+            |_| quote!(Ok(None)),
+        );
+        checks.push(quote! {
+            match #lookup {
+                Ok(Some(element)) => element.is_displayed().await.unwrap_or(false),
+                _ => false,
+            }
+        });
+    }
+    if let Some(url) = &options.done_when_url {
+        checks.push(quote! {
+            driver.current_url().await
+                .map_err(|e| #driver_error(format!("Failed to get current URL before acting on {}: {}", #field_name_str, e) #driver_source))?
+                .as_str()
+                .contains(#url)
+        });
+    }
+
+    actions
+        .iter()
+        .filter(|action| *action == "click" || *action == "submit")
+        .map(|action| {
+            let action_fn_ident =
+                lookups.naming.method_ident(action, field_ident, "");
+            let once_fn_ident =
+                lookups.naming.method_ident(action, field_ident, "_once");
+            let doc = format!(
+                " Like [`Self::{}`], but skipped when the action already took effect, so retries cannot repeat it.",
+                action_fn_ident
+            );
+            quote! {
+                #[doc = #doc]
+                ///
+                /// Returns whether the action was performed.
+                pub async fn #once_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<bool, #error_type> {
+                    if #(#checks)||* {
+                        return Ok(false);
+                    }
+                    self.#action_fn_ident(driver).await?;
+                    Ok(true)
+                }
+            }
+        })
+        .collect()
+}
+
+/// Per-field data shared by the action generators
+pub(crate) struct FieldContext<'a> {
+    pub(crate) field_ident: &'a Ident,
+    pub(crate) field_name_str: &'a str,
+    pub(crate) query_fn_ident: &'a Ident,
+    pub(crate) options: &'a FieldOptions,
+    pub(crate) struct_options: &'a StructOptions,
+    /// Scopes and fallback selectors of every field
+    pub(crate) lookups: &'a Lookups,
+}
+
+/// Generate the method implementing `method_name` for one field
+pub(crate) fn field_action(field: &FieldContext, method_name: &str) -> syn::Result<TokenStream2> {
+    let FieldContext {
+        field_ident,
+        field_name_str,
+        query_fn_ident,
+        options: field_options,
+        struct_options,
+        lookups,
+    } = *field;
+    let CratePaths {
+        thirtyfour,
+        log,
+        tokio,
+        serde_json,
+        ..
+    } = &struct_options.crate_paths;
+
+    let (error_type, message_error) = struct_options.error_tokens();
+    let (driver_error, driver_source) = struct_options.driver_error_tokens();
+    let not_found = not_found_error(
+        struct_options,
+        quote!(#field_name_str),
+        selector_debug(field_ident, field_options),
+    );
+    // Unique fields query through `try_query_<field>` so ambiguous matches fail with their count,
+    // and keyed fields check their key first so a missing one is reported as such
+    let query = if field_options.unique {
+        let try_query_fn_ident = lookups.naming.method_ident("try_query", field_ident, "");
+        quote!(self.#try_query_fn_ident(driver).await?)
+    } else if field_options.keyed {
+        let locator_fn_ident = lookups.naming.method_ident("locator", field_ident, "");
+        quote!({
+            self.#locator_fn_ident(key)?;
+            self.#query_fn_ident(driver).await
+        })
+    } else {
+        quote!(self.#query_fn_ident(driver).await)
+    };
+    // Binds `reason`, the `disabled_reason` attribute of a disabled `element` as ": <value>"
+    let disabled_reason = match &field_options.disabled_reason {
+        Some(attribute) => quote! {
+            let reason = match element.attr(#attribute).await {
+                Ok(Some(reason)) => format!(": {}", reason),
+                _ => String::new(),
+            };
+        },
+        None => quote!(let reason = "";),
+    };
+
+    // Interactions return `()`, or the element acted upon with `return_element`
+    let (interaction_output, interaction_ok) = if struct_options.return_element {
+        (quote!(#thirtyfour::WebElement), quote!(Ok(element)))
+    } else {
+        (quote!(()), quote!(Ok(())))
+    };
+    // In dry-run mode, interactions stop once their element is found, visible and enabled,
+    // recording the action and its `detail` (a format string and its argument) and returning
+    // `ok` instead
+    let dry_run_returning = |action: &str,
+                             detail: Option<(&str, &TokenStream2)>,
+                             ok: &TokenStream2| {
+        if struct_options.dry_run.is_none() {
+            return quote!();
+        }
+        let record = match detail {
+            Some((format, argument)) => {
+                let format = format!("{} {{}} {}", action, format);
+                quote!(format!(#format, #field_name_str, #argument))
+            }
+            None => quote!(format!("{} {}", #action, #field_name_str)),
+        };
+        quote! {
+            if Self::dry_run().is_enabled() {
+                if !element.is_displayed().await
+                    .map_err(|e| #driver_error(format!("Failed to check if {} is displayed: {}", #field_name_str, e) #driver_source))? {
+                    return Err(#message_error(format!("Dry run: {} {} is not visible", #action, #field_name_str)));
+                }
+                if !element.is_enabled().await
+                    .map_err(|e| #driver_error(format!("Failed to check if {} is enabled: {}", #field_name_str, e) #driver_source))? {
+                    return Err(#message_error(format!("Dry run: {} {} is disabled", #action, #field_name_str)));
+                }
+                Self::dry_run().record(#record);
+                return #ok;
+            }
+        }
+    };
+    let dry_run = |action: &str, detail: Option<(&str, &TokenStream2)>| {
+        dry_run_returning(action, detail, &interaction_ok)
+    };
+
+    match method_name {
+        // Basic element interactions
+        "click" => {
+            let click_fn_ident = lookups.naming.method_ident("click", field_ident, "");
+            let dry_run = dry_run("click", None);
+            let method = quote! {
+                /// Click on the web element.
+                pub async fn #click_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            element.click().await
+                                .map_err(|e| #driver_error(format!("Failed to click {}: {}", #field_name_str, e) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "double_click" => {
+            let double_click_fn_ident =
+                lookups.naming.method_ident("double_click", field_ident, "");
+            let dry_run = dry_run("double_click", None);
+            let method = quote! {
+                /// Double-click on the web element.
+                pub async fn #double_click_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            let actions = driver.action_chain();
+                            actions.double_click(&element).perform().await
+                                .map_err(|e| #driver_error(format!("Failed to double-click {}: {}", #field_name_str, e) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "right_click" => {
+            let right_click_fn_ident = lookups.naming.method_ident("right_click", field_ident, "");
+            let dry_run = dry_run("right_click", None);
+            let method = quote! {
+                /// Right-click (context click) on the web element.
+                pub async fn #right_click_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            let actions = driver.action_chain();
+                            actions.context_click(&element).perform().await
+                                .map_err(|e| #driver_error(format!("Failed to right-click {}: {}", #field_name_str, e) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "enter_keys" => {
+            let shown = shown_value(field_options.sensitive, quote!(keys));
+            let detail = error_detail(field_options.sensitive, quote!(keys));
+            let enter_fn_ident = lookups.naming.method_ident("enter_keys", field_ident, "");
+            let dry_run = dry_run("enter_keys", Some(("{:?}", &shown)));
+            let method = quote! {
+                /// Enter text into the web element.
+                pub async fn #enter_fn_ident(&self, driver: &#thirtyfour::WebDriver, keys: &str) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            element.send_keys(keys).await
+                                .map_err(|e| #driver_error(format!("Failed to send keys to {}: {}", #field_name_str, #detail) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "send_key" => {
+            // Sensitive keys are compared as text so they can be masked in driver errors
+            let typed = if field_options.sensitive {
+                quote!(let typed = keys.to_string();)
+            } else {
+                quote!()
+            };
+            let shown = shown_value(field_options.sensitive, quote!(keys));
+            let detail = error_detail(field_options.sensitive, quote!(typed.as_str()));
+            let send_key_fn_ident = lookups.naming.method_ident("send_key", field_ident, "");
+            let dry_run = dry_run("send_key", Some(("{:?}", &shown)));
+            let method = quote! {
+                /// Send special keys or key combinations to the web element.
+                ///
+                /// Accepts a `thirtyfour::Key`, a sequence such as `Key::Control + "a"`, or plain text.
+                pub async fn #send_key_fn_ident(&self, driver: &#thirtyfour::WebDriver, keys: impl Into<#thirtyfour::TypingData> + std::fmt::Debug) -> std::result::Result<#interaction_output, #error_type> {
+                    let keys: #thirtyfour::TypingData = keys.into();
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            #typed
+                            element.send_keys(keys).await
+                                .map_err(|e| #driver_error(format!("Failed to send keys to {}: {}", #field_name_str, #detail) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "clear" => {
+            let clear_fn_ident = lookups.naming.method_ident("clear", field_ident, "");
+            let dry_run = dry_run("clear", None);
+            let method = quote! {
+                /// Clear input field content.
+                pub async fn #clear_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            element.clear().await
+                                .map_err(|e| #driver_error(format!("Failed to clear {}: {}", #field_name_str, e) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "submit" => {
+            let submit_fn_ident = lookups.naming.method_ident("submit", field_ident, "");
+            let dry_run = dry_run("submit", None);
+            let method = quote! {
+                /// Submit a form element.
+                pub async fn #submit_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            element.submit().await
+                                .map_err(|e| #driver_error(format!("Failed to submit form {}: {}", #field_name_str, e) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "hover" => {
+            let hover_fn_ident = lookups.naming.method_ident("hover", field_ident, "");
+            // Lingers on the element with `hover_dwell_ms`, unless the page is headless
+            let dwell = match struct_options.hover_dwell_ms {
+                Some(dwell_ms) => {
+                    let sleep = quote!(#tokio::time::sleep(std::time::Duration::from_millis(#dwell_ms)).await;);
+                    if struct_options.headless_aware.is_some() {
+                        quote! {
+                            if !Self::headless().is_enabled() {
+                                #sleep
+                            }
+                        }
+                    } else {
+                        sleep
+                    }
+                }
+                None => quote!(),
+            };
+            let dry_run = dry_run("hover", None);
+            let method = quote! {
+                /// Hover over the web element (move mouse to it).
+                pub async fn #hover_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            let actions = driver.action_chain();
+                            actions.move_to_element(&element).perform().await
+                                .map_err(|e| #driver_error(format!("Failed to hover over {}: {}", #field_name_str, e) #driver_source))?;
+                            #dwell
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "drag_to" => {
+            let drag_to_fn_ident = lookups.naming.method_ident("drag", field_ident, "_to");
+            let dry_run = dry_run("drag_to", None);
+            let method = quote! {
+                /// Drag this element to another target element.
+                pub async fn #drag_to_fn_ident(&self, driver: &#thirtyfour::WebDriver, target_element: &#thirtyfour::WebElement) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            let actions = driver.action_chain();
+                            actions.drag_and_drop(&element, target_element).perform().await
+                                .map_err(|e| #driver_error(format!("Failed to drag {} to target: {}", #field_name_str, e) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "click_and_answer_prompt" => {
+            let shown = shown_value(field_options.sensitive, quote!(text));
+            let detail = error_detail(field_options.sensitive, quote!(text));
+            let click_prompt_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("click_and_answer_prompt", field_ident, "");
+            let dry_run = dry_run("click_and_answer_prompt", Some(("{:?}", &shown)));
+            let method = quote! {
+                /// Click on the web element, then type `text` into the `window.prompt` it opens and accept it.
+                pub async fn #click_prompt_fn_ident(&self, driver: &#thirtyfour::WebDriver, text: &str) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            element.click().await
+                                .map_err(|e| #driver_error(format!("Failed to click {}: {}", #field_name_str, e) #driver_source))?;
+                            driver.send_alert_text(text).await
+                                .map_err(|e| #driver_error(format!("Failed to answer prompt opened by {}: {}", #field_name_str, #detail) #driver_source))?;
+                            driver.accept_alert().await
+                                .map_err(|e| #driver_error(format!("Failed to accept prompt opened by {}: {}", #field_name_str, e) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        #[cfg(feature = "reqwest")]
+        "check_link" => {
+            let check_link_fn_ident = lookups.naming.method_ident("check_link", field_ident, "");
+            let method = quote! {
+                /// Request the `href` of this anchor and return the HTTP status code.
+                ///
+                /// Issues a HEAD request, falling back to GET for servers that reject HEAD.
+                pub async fn #check_link_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<u16, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            let href = element.attr("href").await
+                                .map_err(|e| #driver_error(format!("Failed to get href from {}: {}", #field_name_str, e) #driver_source))?
+                                .ok_or_else(|| #message_error(format!("Element {} has no href", #field_name_str)))?;
+                            let client = reqwest::Client::new();
+                            let mut response = client.head(&href).send().await
+                                .map_err(|e| #driver_error(format!("Failed to request {} ({}): {}", href, #field_name_str, e) #driver_source))?;
+                            if response.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+                                response = client.get(&href).send().await
+                                    .map_err(|e| #driver_error(format!("Failed to request {} ({}): {}", href, #field_name_str, e) #driver_source))?;
+                            }
+                            Ok(response.status().as_u16())
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        #[cfg(not(feature = "reqwest"))]
+        "check_link" => Err(syn::Error::new(
+            field_ident.span(),
+            "thirtyfour_actions method 'check_link' requires the `reqwest` feature",
+        )),
+        "draw_path" => {
+            let draw_path_fn_ident = lookups.naming.method_ident("draw_path", field_ident, "");
+            let dry_run = dry_run(
+                "draw_path",
+                Some(("through {} points", &quote!(points.len()))),
+            );
+            let method = quote! {
+                /// Press at the first point, move through the rest and release, drawing over the element.
+                ///
+                /// Points are `(x, y)` offsets from the element's center.
+                pub async fn #draw_path_fn_ident(&self, driver: &#thirtyfour::WebDriver, points: &[(i64, i64)]) -> std::result::Result<#interaction_output, #error_type> {
+                    let (start, rest) = points.split_first()
+                        .ok_or_else(|| #message_error(format!("Cannot draw an empty path on {}", #field_name_str)))?;
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            let mut actions = driver.action_chain()
+                                .move_to_element_with_offset(&element, start.0, start.1)
+                                .click_and_hold();
+                            for (x, y) in rest {
+                                actions = actions.move_to_element_with_offset(&element, *x, *y);
+                            }
+                            actions.release().perform().await
+                                .map_err(|e| #driver_error(format!("Failed to draw path on {}: {}", #field_name_str, e) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+
+        // Element properties and state
+        "get_text" => {
+            let get_text_fn_ident = lookups.naming.method_ident("get_text", field_ident, "");
+            let method = quote! {
+                /// Get the text content of the web element.
+                pub async fn #get_text_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<String, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            element.text().await
+                                .map_err(|e| #driver_error(format!("Failed to get text from {}: {}", #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "get_attribute" => {
+            let get_attr_fn_ident = lookups
+                .naming
+                .method_ident("get_attribute", field_ident, "");
+            let method = quote! {
+                /// Get a specific attribute value from the web element.
+                pub async fn #get_attr_fn_ident(&self, driver: &#thirtyfour::WebDriver, attribute: &str) -> std::result::Result<Option<String>, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            element.attr(attribute).await
+                                .map_err(|e| #driver_error(format!("Failed to get attribute '{}' from {}: {}",
+                                    attribute, #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "get_attributes_all" => {
+            let get_attrs_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("get_attributes_all", field_ident, "");
+            // A resolver or stored element is a single element
+            let elements = if field_options.selectorless() {
+                quote!(self.#query_fn_ident(driver).await.into_iter().collect::<Vec<_>>())
+            } else {
+                let lookup = scoped_query(
+                    lookups,
+                    field_ident,
+                    field_locator(field_ident, field_options, lookups),
+                    quote!(.nowait().all_from_selector().await),
+                    |_| quote!(Ok(Vec::new())),
+                );
+                quote! {
+                    #lookup.map_err(|e| #driver_error(format!("Failed to find elements for {}: {}", #field_name_str, e) #driver_source))?
+                }
+            };
+            let keyed_locator =
+                keyed_locator_binding(field_ident, field_options, &lookups.naming, None);
+            let method = quote! {
+                /// Get `attribute` of every element matching the selector, in document order, reading
+                /// them all in a single script call (e.g. every `href` of a list of links).
+                ///
+                /// Elements without the attribute give `None`; no matches give an empty list.
+                pub async fn #get_attrs_fn_ident(&self, driver: &#thirtyfour::WebDriver, attribute: &str) -> std::result::Result<Vec<Option<String>>, #error_type> {
+                    #keyed_locator
+                    let elements = #elements;
+                    if elements.is_empty() {
+                        return Ok(Vec::new());
+                    }
+                    let ret = driver.execute(
+                        r#"return Array.from(arguments[0], element => element.getAttribute(arguments[1]));"#,
+                        vec![
+                            #serde_json::Value::Array(elements.into_iter().map(Into::into).collect()),
+                            attribute.into(),
+                        ],
+                    ).await
+                        .map_err(|e| #driver_error(format!("Failed to get attribute '{}' from {}: {}", attribute, #field_name_str, e) #driver_source))?;
+                    let values = ret.json().as_array()
+                        .ok_or_else(|| #message_error(format!("Unexpected attribute result for {}", #field_name_str)))?
+                        .iter()
+                        .map(|value| value.as_str().map(|value| value.to_string()))
+                        .collect();
+                    Ok(values)
+                }
+            };
+            Ok(method)
+        }
+        "get_value" => {
+            let get_value_fn_ident = lookups.naming.method_ident("get_value", field_ident, "");
+            let method = quote! {
+                /// Get the value attribute of a form control element.
+                pub async fn #get_value_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<Option<String>, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            element.attr("value").await
+                                .map_err(|e| #driver_error(format!("Failed to get value from {}: {}", #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "get_css_value" => {
+            let get_css_fn_ident = lookups
+                .naming
+                .method_ident("get_css_value", field_ident, "");
+            let method = quote! {
+                /// Get a CSS property value of the web element.
+                pub async fn #get_css_fn_ident(&self, driver: &#thirtyfour::WebDriver, property: &str) -> std::result::Result<String, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            element.css_value(property).await
+                                .map_err(|e| #driver_error(format!("Failed to get CSS property '{}' from {}: {}",
+                                    property, #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "has_class" => {
+            let has_class_fn_ident = lookups.naming.method_ident("has_class", field_ident, "");
+            let method = quote! {
+                /// Check if the element has a specific CSS class.
+                pub async fn #has_class_fn_ident(&self, driver: &#thirtyfour::WebDriver, class_name: &str) -> std::result::Result<bool, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            let class_attr = element.attr("class").await
+                                .map_err(|e| #driver_error(format!("Failed to get class attribute from {}: {}", #field_name_str, e) #driver_source))?;
+
+                            match class_attr {
+                                Some(classes) => {
+                                    let class_list: Vec<&str> = classes.split_whitespace().collect();
+                                    Ok(class_list.contains(&class_name))
+                                },
+                                None => Ok(false)
+                            }
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "get_class_list" => {
+            let get_class_list_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("get_class_list", field_ident, "");
+            let method = quote! {
+                /// Get the CSS classes of the element, in document order.
+                pub async fn #get_class_list_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<Vec<String>, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            let class_attr = element.attr("class").await
+                                .map_err(|e| #driver_error(format!("Failed to get class attribute from {}: {}", #field_name_str, e) #driver_source))?;
+                            Ok(class_attr
+                                .map(|classes| classes.split_whitespace().map(String::from).collect())
+                                .unwrap_or_default())
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "add_class" | "remove_class" => {
+            let (doc, script, verb) = if method_name == "add_class" {
+                (
+                    " Add a CSS class to the element through its `classList`.",
+                    "arguments[0].classList.add(arguments[1]);",
+                    "add class",
+                )
+            } else {
+                (
+                    " Remove a CSS class from the element through its `classList`.",
+                    "arguments[0].classList.remove(arguments[1]);",
+                    "remove class",
+                )
+            };
+            let class_fn_ident = lookups.naming.method_ident(method_name, field_ident, "");
+            let dry_run = dry_run(method_name, Some(("{:?}", &quote!(class_name))));
+            let method = quote! {
+                #[doc = #doc]
+                pub async fn #class_fn_ident(&self, driver: &#thirtyfour::WebDriver, class_name: &str) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            driver.execute(#script, vec![element.clone().into(), class_name.into()]).await
+                                .map_err(|e| #driver_error(format!("Failed to {} '{}' on {}: {}", #verb, class_name, #field_name_str, e) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "toggle_class" => {
+            let toggle_class_fn_ident =
+                lookups.naming.method_ident("toggle_class", field_ident, "");
+            // A dry run reports whether the class would be present afterwards
+            let dry_run = dry_run_returning(
+                "toggle_class",
+                Some(("{:?}", &quote!(class_name))),
+                &quote!({
+                    let present = element.class_name().await
+                        .map_err(|e| #driver_error(format!("Failed to read classes of {}: {}", #field_name_str, e) #driver_source))?
+                        .is_some_and(|classes| classes.split_whitespace().any(|class| class == class_name));
+                    Ok(!present)
+                }),
+            );
+            let method = quote! {
+                /// Toggle a CSS class on the element through its `classList`.
+                ///
+                /// Returns whether the class is present afterwards.
+                pub async fn #toggle_class_fn_ident(&self, driver: &#thirtyfour::WebDriver, class_name: &str) -> std::result::Result<bool, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            let ret = driver.execute(
+                                r#"return arguments[0].classList.toggle(arguments[1]);"#,
+                                vec![element.clone().into(), class_name.into()],
+                            ).await
+                                .map_err(|e| #driver_error(format!("Failed to toggle class '{}' on {}: {}", class_name, #field_name_str, e) #driver_source))?;
+                            ret.json().as_bool()
+                                .ok_or_else(|| #message_error(format!("Unexpected result toggling class '{}' on {}", class_name, #field_name_str)))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "get_validity" => {
+            let get_validity_fn_ident =
+                lookups.naming.method_ident("get_validity", field_ident, "");
+            let method = quote! {
+                /// Get the HTML5 constraint-validation state of a form control.
+                ///
+                /// Returns `(validity.valid, validationMessage)`.
+                pub async fn #get_validity_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<(bool, String), #error_type> {
+                    match #query {
+                        Some(element) => {
+                            let ret = driver.execute(
+                                r#"return [arguments[0].validity.valid, arguments[0].validationMessage];"#,
+                                vec![element.clone().into()],
+                            ).await
+                                .map_err(|e| #driver_error(format!("Failed to get validity of {}: {}", #field_name_str, e) #driver_source))?;
+                            let state = ret.json();
+                            match (state[0].as_bool(), state[1].as_str()) {
+                                (Some(valid), Some(message)) => Ok((valid, message.to_string())),
+                                _ => Err(#message_error(format!("Element {} does not support constraint validation", #field_name_str)))
+                            }
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "get_canvas_data_url" => {
+            let data_url_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("get_canvas_data_url", field_ident, "");
+            let method = quote! {
+                /// Get the contents of a canvas element as a PNG data URL.
+                pub async fn #data_url_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<String, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            let ret = driver.execute(r#"return arguments[0].toDataURL('image/png');"#, vec![element.clone().into()]).await
+                                .map_err(|e| #driver_error(format!("Failed to read canvas {}: {}", #field_name_str, e) #driver_source))?;
+                            ret.json().as_str()
+                                .map(|data_url| data_url.to_string())
+                                .ok_or_else(|| #message_error(format!("Element {} is not a canvas", #field_name_str)))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+
+        // Element state checks
+        "is_displayed" => {
+            let is_displayed_fn_ident =
+                lookups.naming.method_ident("is_displayed", field_ident, "");
+            let method = quote! {
+                /// Check if the web element is displayed.
+                pub async fn #is_displayed_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<bool, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            element.is_displayed().await
+                                .map_err(|e| #driver_error(format!("Failed to check if {} is displayed: {}", #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "is_selected" => {
+            let is_selected_fn_ident = lookups.naming.method_ident("is_selected", field_ident, "");
+            let method = quote! {
+                /// Check if the web element is selected.
+                pub async fn #is_selected_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<bool, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            element.is_selected().await
+                                .map_err(|e| #driver_error(format!("Failed to check if {} is selected: {}", #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "is_enabled" => {
+            let is_enabled_fn_ident = lookups.naming.method_ident("is_enabled", field_ident, "");
+            let method = quote! {
+                /// Check if the web element is enabled.
+                pub async fn #is_enabled_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<bool, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            element.is_enabled().await
+                                .map_err(|e| #driver_error(format!("Failed to check if {} is enabled: {}", #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "is_focused" => {
+            let is_focused_fn_ident = lookups.naming.method_ident("is_focused", field_ident, "");
+            let method = quote! {
+                /// Check if the web element currently has keyboard focus.
+                pub async fn #is_focused_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<bool, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            let active = driver.active_element().await
+                                .map_err(|e| #driver_error(format!("Failed to get active element while checking {}: {}", #field_name_str, e) #driver_source))?;
+                            Ok(active.element_id() == element.element_id())
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "check_focus_trap" => {
+            let focus_trap_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("check_focus_trap", field_ident, "");
+            let method = quote! {
+                /// Press Tab `tabs` times and check that focus stays inside this element, e.g. a modal dialog.
+                ///
+                /// Returns `None` if focus was trapped, or a description of the element focus escaped to.
+                pub async fn #focus_trap_fn_ident(&self, driver: &#thirtyfour::WebDriver, tabs: usize) -> std::result::Result<Option<String>, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            for _ in 0..tabs {
+                                driver.action_chain().send_keys(#thirtyfour::Key::Tab).perform().await
+                                    .map_err(|e| #driver_error(format!("Failed to press Tab in {}: {}", #field_name_str, e) #driver_source))?;
+                                let ret = driver.execute(
+                                    r#"const active = document.activeElement;
+                                       if (!active || arguments[0].contains(active)) return null;
+                                       let desc = active.tagName.toLowerCase();
+                                       if (active.id) desc += '#' + active.id;
+                                       if (typeof active.className === 'string' && active.className.trim())
+                                           desc += '.' + active.className.trim().split(/\s+/).join('.');
+                                       return desc;"#,
+                                    vec![element.clone().into()],
+                                ).await
+                                    .map_err(|e| #driver_error(format!("Failed to check focus in {}: {}", #field_name_str, e) #driver_source))?;
+                                if let Some(escaped_to) = ret.json().as_str() {
+                                    return Ok(Some(escaped_to.to_string()));
+                                }
+                            }
+                            Ok(None)
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "assert_above_fold" => {
+            let above_fold_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("assert_above_fold", field_ident, "");
+            let method = quote! {
+                /// Check that the top of the element lies within the initial viewport, i.e. it is
+                /// visible on page load without scrolling.
+                pub async fn #above_fold_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<(), #error_type> {
+                    match #query {
+                        Some(element) => {
+                            let ret = driver.execute(
+                                r#"return [arguments[0].getBoundingClientRect().top + window.scrollY, window.innerHeight];"#,
+                                vec![element.clone().into()],
+                            ).await
+                                .map_err(|e| #driver_error(format!("Failed to get position of {}: {}", #field_name_str, e) #driver_source))?;
+                            let position = ret.json();
+                            match (position[0].as_f64(), position[1].as_f64()) {
+                                (Some(top), Some(viewport_height)) if top < viewport_height => Ok(()),
+                                (Some(top), Some(viewport_height)) => Err(#message_error(format!(
+                                    "Element {} is below the fold (top at {}px, viewport height {}px)",
+                                    #field_name_str, top, viewport_height
+                                ))),
+                                _ => Err(#message_error(format!("Unexpected position result for {}", #field_name_str)))
+                            }
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "exists" => {
+            let exists_fn_ident = lookups.naming.method_ident("exists", field_ident, "");
+            // A keyed field's query logs a missing key
+            let exists = if field_options.nth.is_some()
+                || field_options.selectorless()
+                || field_options.keyed
+            {
+                quote!(self.#query_fn_ident(driver).await.is_some())
+            } else {
+                let lookup = scoped_query(
+                    lookups,
+                    field_ident,
+                    field_locator(field_ident, field_options, lookups),
+                    quote!(.exists().await),
+                    |_| quote!(Ok(false)),
+                );
+                quote! {
+                    match #lookup {
+                        Ok(exists) => exists,
+                        Err(_) => false
+                    }
+                }
+            };
+            let method = quote! {
+                /// Check if the element exists in the DOM without throwing an error.
+                pub async fn #exists_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> bool {
+                    #exists
+                }
+            };
+            Ok(method)
+        }
+        "get_element_state" => {
+            let runtime = runtime_path();
+            let state_fn_ident = lookups
+                .naming
+                .method_ident("get_element_state", field_ident, "");
+            let method = quote! {
+                /// Get whether the element exists, is displayed, enabled and selected, along with its
+                /// rect and the start of its text, reading everything but visibility in a single
+                /// script call instead of one call per property.
+                ///
+                /// A missing element gives a state with `exists: false` rather than an error.
+                pub async fn #state_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<#runtime::ElementState, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            let displayed = element.is_displayed().await
+                                .map_err(|e| #driver_error(format!("Failed to check if {} is displayed: {}", #field_name_str, e) #driver_source))?;
+                            let ret = driver.execute(
+                                r#"
+                                const el = arguments[0];
+                                const rect = el.getBoundingClientRect();
+                                let text = (el.innerText || el.textContent || '').replace(/\s+/g, ' ').trim();
+                                if (text.length > 80) {
+                                    text = text.slice(0, 80) + '…';
+                                }
+                                return [
+                                    !el.matches(':disabled'),
+                                    !!(el.checked || el.selected),
+                                    [rect.left + window.scrollX, rect.top + window.scrollY, rect.width, rect.height],
+                                    text,
+                                ];
+                                "#,
+                                vec![element.clone().into()],
+                            ).await
+                                .map_err(|e| #driver_error(format!("Failed to get state of {}: {}", #field_name_str, e) #driver_source))?;
+                            let state = ret.json();
+                            let rect = match state[2].as_array().map(|rect| rect.as_slice()) {
+                                Some([x, y, width, height]) => Some(#runtime::ElementBounds {
+                                    x: x.as_f64().unwrap_or_default(),
+                                    y: y.as_f64().unwrap_or_default(),
+                                    width: width.as_f64().unwrap_or_default(),
+                                    height: height.as_f64().unwrap_or_default(),
+                                }),
+                                _ => None,
+                            };
+                            Ok(#runtime::ElementState {
+                                exists: true,
+                                displayed,
+                                enabled: state[0].as_bool().unwrap_or(false),
+                                selected: state[1].as_bool().unwrap_or(false),
+                                rect,
+                                text: state[3].as_str().unwrap_or_default().to_string(),
+                            })
+                        },
+                        None => Ok(#runtime::ElementState::default())
+                    }
+                }
+            };
+            Ok(method)
+        }
+
+        // Select element methods
+        "select_by_text" => {
+            let shown = shown_value(field_options.sensitive, quote!(text));
+            let detail = error_detail(field_options.sensitive, quote!(text));
+            let select_text_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("select_by_text", field_ident, "");
+            let dry_run = dry_run("select_by_text", Some(("{:?}", &shown)));
+            let method = quote! {
+                /// Select an option from a dropdown by its visible text.
+                pub async fn #select_text_fn_ident(&self, driver: &#thirtyfour::WebDriver, text: &str) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            let select = #thirtyfour::components::select::SelectElement::new(&element);
+                            select.select_by_visible_text(text).await
+                                .map_err(|e| #driver_error(format!("Failed to select text '{}' in {}: {}", #shown, #field_name_str, #detail) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "select_by_value" => {
+            let shown = shown_value(field_options.sensitive, quote!(value));
+            let detail = error_detail(field_options.sensitive, quote!(value));
+            let select_value_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("select_by_value", field_ident, "");
+            let dry_run = dry_run("select_by_value", Some(("{:?}", &shown)));
+            let method = quote! {
+                /// Select an option from a dropdown by its value attribute.
+                pub async fn #select_value_fn_ident(&self, driver: &#thirtyfour::WebDriver, value: &str) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            let select = #thirtyfour::components::select::SelectElement::new(&element);
+                            select.select_by_value(value).await
+                                .map_err(|e| #driver_error(format!("Failed to select value '{}' in {}: {}", #shown, #field_name_str, #detail) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "select_by_index" => {
+            let select_index_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("select_by_index", field_ident, "");
+            let dry_run = dry_run("select_by_index", Some(("{}", &quote!(index))));
+            let method = quote! {
+                /// Select an option from a dropdown by its index.
+                pub async fn #select_index_fn_ident(&self, driver: &#thirtyfour::WebDriver, index: usize) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            let select = #thirtyfour::components::select::SelectElement::new(&element);
+                            select.select_by_index(index).await
+                                .map_err(|e| #driver_error(format!("Failed to select index {} in {}: {}", index, #field_name_str, e) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "get_selected_text" => {
+            let get_selected_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("get_selected_text", field_ident, "");
+            let method = quote! {
+                /// Get the text of the currently selected option in a dropdown.
+                pub async fn #get_selected_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<String, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            let select = #thirtyfour::components::select::SelectElement::new(&element);
+                            select.first_selected_option().await
+                                .map_err(|e| #driver_error(format!("Failed to get selected option in {}: {}", #field_name_str, e) #driver_source))?
+                                .text().await
+                                .map_err(|e| #driver_error(format!("Failed to get text of selected option in {}: {}", #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "custom_dropdown" => {
+            let shown = shown_value(field_options.sensitive, quote!(text));
+            let detail = error_detail(field_options.sensitive, quote!(text));
+            // Defaults cover select2 and ARIA combobox widgets
+            let search = field_options.dropdown_search.as_ref().map_or_else(
+                || {
+                    quote!(
+                        "input.select2-search__field, input[role='combobox'], input[type='search']"
+                    )
+                },
+                |selector| quote!(#selector),
+            );
+            let option = field_options.dropdown_option.as_ref().map_or_else(
+                || quote!(".select2-results__option, [role='option']"),
+                |selector| quote!(#selector),
+            );
+            let pick_fn_ident = lookups.naming.method_ident("pick", field_ident, "");
+            let dry_run = dry_run("pick", Some(("{:?}", &shown)));
+            let method = quote! {
+                /// Pick an option of a JavaScript dropdown widget: open it, type `text` into its
+                /// search box and click the option with that text.
+                pub async fn #pick_fn_ident(&self, driver: &#thirtyfour::WebDriver, text: &str) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            element.click().await
+                                .map_err(|e| #driver_error(format!("Failed to open dropdown {}: {}", #field_name_str, e) #driver_source))?;
+                            // Widgets usually render the open list at the end of the body
+                            let search = driver.query(#thirtyfour::By::Css(#search)).and_displayed().first().await
+                                .map_err(|e| #driver_error(format!("Search box of dropdown {} not found: {}", #field_name_str, e) #driver_source))?;
+                            search.send_keys(text).await
+                                .map_err(|e| #driver_error(format!("Failed to search '{}' in dropdown {}: {}", #shown, #field_name_str, #detail) #driver_source))?;
+                            let option = driver.query(#thirtyfour::By::Css(#option)).with_text(text.to_string()).and_displayed().first().await
+                                .map_err(|e| #driver_error(format!("Option '{}' not found in dropdown {}: {}", #shown, #field_name_str, #detail) #driver_source))?;
+                            option.click().await
+                                .map_err(|e| #driver_error(format!("Failed to pick '{}' in dropdown {}: {}", #shown, #field_name_str, #detail) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+
+        // Element traversal
+        "find_within" => {
+            let find_in_fn_ident = lookups.naming.method_ident("find_in", field_ident, "");
+            let method = quote! {
+                /// Find all elements matching `by` inside this element.
+                pub async fn #find_in_fn_ident(&self, driver: &#thirtyfour::WebDriver, by: #thirtyfour::By) -> std::result::Result<Vec<#thirtyfour::WebElement>, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            element.find_all(by).await
+                                .map_err(|e| #driver_error(format!("Failed to search within {}: {}", #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "get_children" => {
+            let get_children_fn_ident =
+                lookups.naming.method_ident("get_children", field_ident, "");
+            let method = quote! {
+                /// Get the direct child elements of this element.
+                pub async fn #get_children_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<Vec<#thirtyfour::WebElement>, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            element.find_all(#thirtyfour::By::XPath("./*")).await
+                                .map_err(|e| #driver_error(format!("Failed to get children of {}: {}", #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "get_parent" => {
+            let get_parent_fn_ident = lookups.naming.method_ident("get_parent", field_ident, "");
+            let method = quote! {
+                /// Get the parent element of this element.
+                pub async fn #get_parent_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<#thirtyfour::WebElement, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            element.find(#thirtyfour::By::XPath("..")).await
+                                .map_err(|e| #driver_error(format!("Failed to get parent of {}: {}", #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "get_shadow_root" => {
+            let get_shadow_root_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("get_shadow_root", field_ident, "");
+            let method = quote! {
+                /// Get the shadow root of this element, to continue querying inside a web component.
+                pub async fn #get_shadow_root_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<#thirtyfour::WebElement, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            element.get_shadow_root().await
+                                .map_err(|e| #driver_error(format!("Failed to get shadow root of {}: {}", #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "dump_subtree" => {
+            let dump_subtree_fn_ident =
+                lookups.naming.method_ident("dump_subtree", field_ident, "");
+            let method = quote! {
+                /// Describe the element and its descendants up to `max_depth` levels down as an
+                /// indented tree of tags with their id, classes and the start of their own text,
+                /// gathered in a single script call.
+                ///
+                /// Meant for understanding unfamiliar DOM structure while debugging a failing test.
+                pub async fn #dump_subtree_fn_ident(&self, driver: &#thirtyfour::WebDriver, max_depth: usize) -> std::result::Result<String, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            let ret = driver.execute(
+                                r#"
+                                const maxDepth = arguments[1];
+                                const rows = [];
+                                const walk = (el, depth) => {
+                                    let text = Array.from(el.childNodes)
+                                        .filter(node => node.nodeType === Node.TEXT_NODE)
+                                        .map(node => node.textContent)
+                                        .join(' ')
+                                        .replace(/\s+/g, ' ')
+                                        .trim();
+                                    if (text.length > 40) {
+                                        text = text.slice(0, 40) + '…';
+                                    }
+                                    rows.push([depth, el.tagName.toLowerCase(), el.id, Array.from(el.classList), text, el.children.length]);
+                                    if (depth < maxDepth) {
+                                        for (const child of el.children) {
+                                            walk(child, depth + 1);
+                                        }
+                                    }
+                                };
+                                walk(arguments[0], 0);
+                                return rows;
+                                "#,
+                                vec![element.clone().into(), max_depth.into()],
+                            ).await
+                                .map_err(|e| #driver_error(format!("Failed to dump subtree of {}: {}", #field_name_str, e) #driver_source))?;
+                            let rows = ret.json().as_array().cloned()
+                                .ok_or_else(|| #message_error(format!("Unexpected subtree result for {}", #field_name_str)))?;
+                            let mut tree = String::new();
+                            for row in rows {
+                                let depth = row[0].as_u64().unwrap_or(0) as usize;
+                                tree.push_str(&"  ".repeat(depth));
+                                tree.push('<');
+                                tree.push_str(row[1].as_str().unwrap_or("?"));
+                                if let Some(id) = row[2].as_str().filter(|id| !id.is_empty()) {
+                                    tree.push_str(&format!(" id=\"{}\"", id));
+                                }
+                                let classes: Vec<&str> = row[3].as_array()
+                                    .map(|classes| classes.iter().filter_map(|class| class.as_str()).collect())
+                                    .unwrap_or_default();
+                                if !classes.is_empty() {
+                                    tree.push_str(&format!(" class=\"{}\"", classes.join(" ")));
+                                }
+                                tree.push('>');
+                                if let Some(text) = row[4].as_str().filter(|text| !text.is_empty()) {
+                                    tree.push_str(&format!(" {:?}", text));
+                                }
+                                let children = row[5].as_u64().unwrap_or(0);
+                                if depth == max_depth && children > 0 {
+                                    tree.push_str(&format!(" (+{} children)", children));
+                                }
+                                tree.push('\n');
+                            }
+                            Ok(tree)
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+
+        // Visibility and waiting methods
+        "scroll_to" => {
+            let scroll_fn_ident = lookups.naming.method_ident("scroll_to", field_ident, "");
+            let dry_run = dry_run("scroll_to", None);
+            let method = quote! {
+                /// Scroll the element into view.
+                pub async fn #scroll_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<#interaction_output, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #dry_run
+                            driver.execute(r#"arguments[0].scrollIntoView();"#, vec![element.clone().into()]).await
+                                .map_err(|e| #driver_error(format!("Failed to scroll to {}: {}", #field_name_str, e) #driver_source))?;
+                            #interaction_ok
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "wait_for" => {
+            let wait_fn_ident = lookups.naming.method_ident("wait_for", field_ident, "");
+            let visible_element = visible_element_tokens(field);
+            let method = quote! {
+                /// Wait for the element to be present and visible with timeout.
+                pub async fn #wait_fn_ident(&self, driver: &#thirtyfour::WebDriver, timeout_secs: u64) -> std::result::Result<#thirtyfour::WebElement, #error_type> {
+                    use std::time::Duration;
+                    #visible_element
+                    Ok(element)
+                }
+            };
+            Ok(method)
+        }
+        "wait_until_clickable" => {
+            let wait_clickable_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("wait_until_clickable", field_ident, "");
+            let visible_element = visible_element_tokens(field);
+            let method = quote! {
+                /// Wait until the element is clickable (visible and enabled).
+                pub async fn #wait_clickable_fn_ident(&self, driver: &#thirtyfour::WebDriver, timeout_secs: u64) -> std::result::Result<#thirtyfour::WebElement, #error_type> {
+                    use std::time::Duration;
+                    #visible_element
+
+                    // Check if enabled
+                    if !element.is_enabled().await
+                        .map_err(|e| #driver_error(format!("Failed to check if {} is enabled: {}", #field_name_str, e) #driver_source))? {
+                        #disabled_reason
+                        return Err(#message_error(format!("Element {} is not clickable (disabled{})", #field_name_str, reason)));
+                    }
+
+                    Ok(element)
+                }
+            };
+            Ok(method)
+        }
+        "wait_for_enabled_then_click" => {
+            let timeout = timeout_error(
+                struct_options,
+                quote!(#field_name_str),
+                quote!(Duration::from_secs(timeout_secs)),
+                quote!(
+                    "Timed out waiting for {} to be enabled (disabled{})",
+                    #field_name_str,
+                    reason
+                ),
+            );
+            let poll_ms = struct_options.poll_ms();
+            let fn_ident =
+                lookups
+                    .naming
+                    .method_ident("wait_for_enabled_then_click", field_ident, "");
+            let visible_element = visible_element_tokens(field);
+            let dry_run = dry_run("wait_for_enabled_then_click", None);
+            let method = quote! {
+                /// Wait until the element is visible and enabled, then click it.
+                pub async fn #fn_ident(&self, driver: &#thirtyfour::WebDriver, timeout_secs: u64) -> std::result::Result<#interaction_output, #error_type> {
+                    use std::time::{Duration, Instant};
+                    let enabled_deadline = Instant::now() + Duration::from_secs(timeout_secs);
+                    #visible_element
+                    while !element.is_enabled().await
+                        .map_err(|e| #driver_error(format!("Failed to check if {} is enabled: {}", #field_name_str, e) #driver_source))? {
+                        if Instant::now() >= enabled_deadline {
+                            #disabled_reason
+                            return Err(#timeout);
+                        }
+                        #tokio::time::sleep(Duration::from_millis(#poll_ms)).await;
+                    }
+                    #dry_run
+                    element.click().await
+                        .map_err(|e| #driver_error(format!("Failed to click {}: {}", #field_name_str, e) #driver_source))?;
+                    #interaction_ok
+                }
+            };
+            Ok(method)
+        }
+        "wait_for_no_overlap" => {
+            let timeout = timeout_error(
+                struct_options,
+                quote!(#field_name_str),
+                quote!(Duration::from_secs(timeout_secs)),
+                quote!(
+                    "Timed out waiting for {} to be uncovered (covered by {})",
+                    #field_name_str,
+                    covering
+                ),
+            );
+            let poll_ms = struct_options.poll_ms();
+            let fn_ident = lookups
+                .naming
+                .method_ident("wait_for_no_overlap", field_ident, "");
+            let visible_element = visible_element_tokens(field);
+            let method = quote! {
+                /// Wait until the element is visible and no other element covers its center point,
+                /// e.g. a sticky header, toast or chat widget that would intercept a click.
+                pub async fn #fn_ident(&self, driver: &#thirtyfour::WebDriver, timeout_secs: u64) -> std::result::Result<#thirtyfour::WebElement, #error_type> {
+                    use std::time::{Duration, Instant};
+                    let uncovered_deadline = Instant::now() + Duration::from_secs(timeout_secs);
+                    #visible_element
+                    loop {
+                        // The topmost element at the center, described when it is not the target.
+                        // `elementFromPoint` only sees the viewport, so the element is centered in it
+                        // first, and a center still outside it counts as covered.
+                        let ret = driver.execute(
+                            r#"
+                            const element = arguments[0];
+                            element.scrollIntoView({ block: "center", inline: "center" });
+                            const rect = element.getBoundingClientRect();
+                            const hit = document.elementFromPoint(rect.left + rect.width / 2, rect.top + rect.height / 2);
+                            if (hit === null) {
+                                return "nothing (center outside the viewport)";
+                            }
+                            if (hit === element || element.contains(hit)) {
+                                return null;
+                            }
+                            return hit.outerHTML.slice(0, 120);
+                            "#,
+                            vec![element.clone().into()],
+                        ).await
+                            .map_err(|e| #driver_error(format!("Failed to check what covers {}: {}", #field_name_str, e) #driver_source))?;
+                        let covering = match ret.json().as_str() {
+                            Some(covering) => covering.to_string(),
+                            None => return Ok(element),
+                        };
+                        if Instant::now() >= uncovered_deadline {
+                            return Err(#timeout);
+                        }
+                        #tokio::time::sleep(Duration::from_millis(#poll_ms)).await;
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "wait_for_attribute_to_contain" => {
+            let wait_contain_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("wait_for_attribute_to_contain", field_ident, "");
+            let poll = value_poll_tokens(
+                field,
+                quote!(element.attr(attribute).await),
+                quote!("attribute '{}' of {}", attribute, #field_name_str),
+                quote!(value.contains(needle)),
+                quote!("contain '{}'", needle),
+            );
+            let method = quote! {
+                /// Wait until `attribute` of the element contains `needle`, returning the attribute value.
+                pub async fn #wait_contain_fn_ident(&self, driver: &#thirtyfour::WebDriver, attribute: &str, needle: &str, timeout_secs: u64) -> std::result::Result<String, #error_type> {
+                    #poll
+                }
+            };
+            Ok(method)
+        }
+        "wait_for_value" => {
+            let wait_value_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("wait_for_value", field_ident, "");
+            let wait_value_contain_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("wait_for_value_to_contain", field_ident, "");
+            let read = quote!(element.prop("value").await);
+            let subject = quote!("value of {}", #field_name_str);
+            let equals_poll = value_poll_tokens(
+                field,
+                read.clone(),
+                subject.clone(),
+                quote!(value == expected),
+                quote!("equal '{}'", expected),
+            );
+            let contains_poll = value_poll_tokens(
+                field,
+                read,
+                subject,
+                quote!(value.contains(needle)),
+                quote!("contain '{}'", needle),
+            );
+            let method = quote! {
+                /// Wait until the `value` property of the element equals `expected`, e.g. once an
+                /// input has been reformatted or filled in by the page.
+                pub async fn #wait_value_fn_ident(&self, driver: &#thirtyfour::WebDriver, expected: &str, timeout_secs: u64) -> std::result::Result<String, #error_type> {
+                    #equals_poll
+                }
+
+                /// Wait until the `value` property of the element contains `needle`, returning the value.
+                pub async fn #wait_value_contain_fn_ident(&self, driver: &#thirtyfour::WebDriver, needle: &str, timeout_secs: u64) -> std::result::Result<String, #error_type> {
+                    #contains_poll
+                }
+            };
+            Ok(method)
+        }
+        #[cfg(feature = "regex")]
+        "wait_for_attribute_to_match" => {
+            let wait_match_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("wait_for_attribute_to_match", field_ident, "");
+            let poll = value_poll_tokens(
+                field,
+                quote!(element.attr(attribute).await),
+                quote!("attribute '{}' of {}", attribute, #field_name_str),
+                quote!(regex.is_match(&value)),
+                quote!("match {}", pattern),
+            );
+            let method = quote! {
+                /// Wait until `attribute` of the element matches the regex `pattern`, returning the attribute value.
+                pub async fn #wait_match_fn_ident(&self, driver: &#thirtyfour::WebDriver, attribute: &str, pattern: &str, timeout_secs: u64) -> std::result::Result<String, #error_type> {
+                    let regex = regex::Regex::new(pattern)
+                        .map_err(|e| #driver_error(format!("Invalid pattern {} for {}: {}", pattern, #field_name_str, e) #driver_source))?;
+                    #poll
+                }
+            };
+            Ok(method)
+        }
+        #[cfg(not(feature = "regex"))]
+        "wait_for_attribute_to_match" => Err(syn::Error::new(
+            field_ident.span(),
+            "thirtyfour_actions method 'wait_for_attribute_to_match' requires the `regex` feature",
+        )),
+        "take_screenshot" => {
+            let screenshot = screenshot_tokens(
+                struct_options,
+                quote!(element.screenshot_as_png()),
+                quote!(element.screenshot_as_base64()),
+                field_name_str,
+            );
+            let screenshot_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("take_screenshot", field_ident, "");
+            let method = quote! {
+                /// Take a screenshot of just this element and return the PNG image data as base64.
+                pub async fn #screenshot_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<String, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            #screenshot
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        "screenshot_with_highlight" => {
+            let screenshot = screenshot_tokens(
+                struct_options,
+                quote!(driver.screenshot_as_png()),
+                quote!(driver.screenshot_as_png_base64()),
+                field_name_str,
+            );
+            let highlight_fn_ident =
+                lookups
+                    .naming
+                    .method_ident("screenshot_with_highlight", field_ident, "");
+            let method = quote! {
+                /// Take a screenshot of the whole page with this element outlined and return the PNG image data as base64.
+                pub async fn #highlight_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<String, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            driver.execute(
+                                r#"arguments[0].dataset.thirtyfourOutline = arguments[0].style.outline;
+                                   arguments[0].style.outline = '3px solid red';"#,
+                                vec![element.clone().into()],
+                            ).await
+                                .map_err(|e| #driver_error(format!("Failed to highlight {}: {}", #field_name_str, e) #driver_source))?;
+                            let screenshot = #screenshot;
+                            // The screenshot is taken; a leftover outline is not worth losing it
+                            if let Err(e) = driver.execute(
+                                r#"arguments[0].style.outline = arguments[0].dataset.thirtyfourOutline || '';
+                                   delete arguments[0].dataset.thirtyfourOutline;"#,
+                                vec![element.clone().into()],
+                            ).await {
+                                #log::warn!("Failed to remove highlight from {}: {}", #field_name_str, e);
+                            }
+                            screenshot
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        #[cfg(feature = "ocr")]
+        "read_text_ocr" => {
+            let ocr_fn_ident = lookups
+                .naming
+                .method_ident("read_text_ocr", field_ident, "");
+            let runtime = runtime_path();
+            let method = quote! {
+                /// Screenshot the web element and extract its rendered text with `engine`.
+                ///
+                /// Reads text drawn in canvases and images that `get_text` cannot see.
+                pub async fn #ocr_fn_ident(&self, driver: &#thirtyfour::WebDriver, engine: &dyn #runtime::OcrEngine) -> std::result::Result<String, #error_type> {
+                    match #query {
+                        Some(element) => {
+                            let png = element.screenshot_as_png().await
+                                .map_err(|e| #driver_error(format!("Failed to take screenshot of {}: {}", #field_name_str, e) #driver_source))?;
+                            engine.recognize(&png)
+                                .map_err(|e| #driver_error(format!("Failed to read text of {}: {}", #field_name_str, e) #driver_source))
+                        },
+                        None => Err(#not_found)
+                    }
+                }
+            };
+            Ok(method)
+        }
+        #[cfg(not(feature = "ocr"))]
+        "read_text_ocr" => Err(syn::Error::new(
+            field_ident.span(),
+            "thirtyfour_actions method 'read_text_ocr' requires the `ocr` feature",
+        )),
+
+        // If the method isn't supported, generate a compile-time error
+        _ => Err(syn::Error::new(
+            field_ident.span(),
+            format!(
+                "Unsupported thirtyfour_actions method: '{}' for field {}",
+                method_name, field_name_str
+            ),
+        )),
+    }
+}
@@ -1,704 +1,4994 @@
 extern crate proc_macro;
 
+mod actions;
+mod rewrite;
+mod runtime;
+mod selector_syntax;
+mod selectors_file;
+mod wrappers;
+
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
+use std::collections::HashMap;
 use syn::Ident;
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::token::Comma;
 use syn::{DeriveInput, parse_macro_input, spanned::Spanned};
 
-/// Parse method lists for field-specific attributes
-struct ElementMethods {
-    methods: Vec<String>,
+use actions::*;
+use rewrite::*;
+use runtime::runtime_module;
+use wrappers::*;
+
+/// Parse a parenthesized, comma-separated list of method names, expanding `all`
+fn parse_method_list(input: ParseStream) -> syn::Result<Vec<String>> {
+    // Parse the parenthesized content
+    let content;
+    syn::parenthesized!(content in input);
+
+    // Parse comma-separated identifiers
+    let method_names = Punctuated::<Ident, Comma>::parse_terminated(&content)?;
+    let mut methods = Vec::new();
+    for id in method_names {
+        if id == "all" {
+            methods.extend(available_actions().map(String::from));
+        } else {
+            methods.push(id.to_string());
+        }
+    }
+    Ok(methods)
+}
+
+/// Parse `all_except(...)`: every available action except the listed ones
+fn parse_all_except(input: ParseStream) -> syn::Result<Vec<String>> {
+    let content;
+    syn::parenthesized!(content in input);
+
+    let excluded = Punctuated::<Ident, Comma>::parse_terminated(&content)?;
+    for id in &excluded {
+        if !ACTIONS.contains(&id.to_string().as_str()) {
+            return Err(syn::Error::new(
+                id.span(),
+                format!("Unsupported thirtyfour_actions method: '{}'", id),
+            ));
+        }
+    }
+    Ok(available_actions()
+        .filter(|action| !excluded.iter().any(|id| id == action))
+        .map(String::from)
+        .collect())
+}
+
+/// Parse an integer or float literal, optionally negative, as `f64`
+fn parse_number(input: ParseStream) -> syn::Result<f64> {
+    let negative = input.parse::<Option<syn::Token![-]>>()?.is_some();
+    let value = match input.parse::<syn::Lit>()? {
+        syn::Lit::Int(lit) => lit.base10_parse::<f64>()?,
+        syn::Lit::Float(lit) => lit.base10_parse::<f64>()?,
+        lit => return Err(syn::Error::new(lit.span(), "expected a number")),
+    };
+    Ok(if negative { -value } else { value })
+}
+
+/// Parse `= "..."` following an option name
+fn parse_str_value(input: ParseStream) -> syn::Result<syn::LitStr> {
+    input.parse::<syn::Token![=]>()?;
+    input.parse()
+}
+
+/// Names of the `{placeholder}`s in a struct-level `url`, in order of first appearance
+fn url_placeholders(url: &syn::LitStr) -> syn::Result<Vec<String>> {
+    let value = url.value();
+    let mut placeholders = Vec::new();
+    let mut rest = value.as_str();
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            return Err(syn::Error::new(url.span(), "unmatched '}' in url"));
+        }
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| syn::Error::new(url.span(), "unclosed '{' in url"))?;
+        let name = &rest[start + 1..start + end];
+        if syn::parse_str::<Ident>(name).is_err() {
+            return Err(syn::Error::new(
+                url.span(),
+                format!(
+                    "invalid url placeholder '{{{}}}', expected an identifier",
+                    name
+                ),
+            ));
+        }
+        if !placeholders.iter().any(|placeholder| placeholder == name) {
+            placeholders.push(name.to_string());
+        }
+        rest = &rest[start + end + 1..];
+    }
+    Ok(placeholders)
+}
+
+/// `geolocation(lat = ..., lon = ..., accuracy = ...)` override
+#[derive(Clone, Copy)]
+struct Geolocation {
+    latitude: f64,
+    longitude: f64,
+    accuracy: f64,
 }
 
-impl Parse for ElementMethods {
+impl Parse for Geolocation {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        // Expect the keyword "methods"
-        let ident: Ident = input.parse()?;
-        if ident != "methods" {
-            return Err(syn::Error::new(ident.span(), "expected 'methods'"));
+        let content;
+        let paren = syn::parenthesized!(content in input);
+        let (mut latitude, mut longitude, mut accuracy) = (None, None, 100.0);
+        while !content.is_empty() {
+            let key: Ident = content.parse()?;
+            content.parse::<syn::Token![=]>()?;
+            match key.to_string().as_str() {
+                "lat" => latitude = Some(parse_number(&content)?),
+                "lon" => longitude = Some(parse_number(&content)?),
+                "accuracy" => accuracy = parse_number(&content)?,
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("unknown geolocation option '{}'", key),
+                    ));
+                }
+            }
+            if !content.is_empty() {
+                content.parse::<Comma>()?;
+            }
+        }
+
+        match (latitude, longitude) {
+            (Some(latitude), Some(longitude)) => Ok(Geolocation {
+                latitude,
+                longitude,
+                accuracy,
+            }),
+            _ => Err(syn::Error::new(
+                paren.span.join(),
+                "geolocation(...) requires both lat and lon",
+            )),
         }
+    }
+}
+
+/// `wait_between_actions(ms = ..., field = "...")` pacing policy
+struct ActionPacing {
+    interval_ms: u64,
+    /// Field holding the `ActionPacer` remembering when the last action finished
+    field: Ident,
+}
 
-        // Parse the parenthesized content
+impl Parse for ActionPacing {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
         let content;
-        syn::parenthesized!(content in input);
+        let paren = syn::parenthesized!(content in input);
+        let (mut interval_ms, mut field) = (None, None);
+        while !content.is_empty() {
+            let key: Ident = content.parse()?;
+            content.parse::<syn::Token![=]>()?;
+            match key.to_string().as_str() {
+                "ms" => interval_ms = Some(content.parse::<syn::LitInt>()?.base10_parse()?),
+                "field" => field = Some(content.parse::<syn::LitStr>()?.parse()?),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("unknown wait_between_actions option '{}'", key),
+                    ));
+                }
+            }
+            if !content.is_empty() {
+                content.parse::<Comma>()?;
+            }
+        }
+
+        match (interval_ms, field) {
+            (Some(interval_ms), Some(field)) => Ok(ActionPacing { interval_ms, field }),
+            _ => Err(syn::Error::new(
+                paren.span.join(),
+                "wait_between_actions(...) requires both ms and field",
+            )),
+        }
+    }
+}
+
+/// `naming = "..."`: how the methods generated for a field combine its name with their verb
+#[derive(Clone)]
+struct MethodNaming {
+    /// Name template with one `{verb}` and one `{field}`, e.g. `{field}_{verb}`
+    template: String,
+}
 
-        // Parse comma-separated identifiers
-        let method_names = Punctuated::<Ident, Comma>::parse_terminated(&content)?;
-        let methods = method_names.into_iter().map(|id| id.to_string()).collect();
+impl Parse for MethodNaming {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lit = parse_str_value(input)?;
+        let template = match lit.value().as_str() {
+            "verb_field" => "{verb}_{field}".to_string(),
+            "field_verb" => "{field}_{verb}".to_string(),
+            template => template.to_string(),
+        };
+        let rest = template
+            .replacen("{verb}", "", 1)
+            .replacen("{field}", "", 1);
+        if rest.contains('{') || template.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(syn::Error::new(
+                lit.span(),
+                "naming must be \"verb_field\", \"field_verb\" or a template with one `{verb}` \
+                 and one `{field}`, e.g. \"ui_{field}_{verb}\"",
+            ));
+        }
+        if let Some(c) = rest
+            .chars()
+            .find(|&c| !(c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'))
+        {
+            return Err(syn::Error::new(
+                lit.span(),
+                format!("'{}' cannot be part of a method name", c),
+            ));
+        }
+        Ok(MethodNaming { template })
+    }
+}
 
-        Ok(ElementMethods { methods })
+impl Default for MethodNaming {
+    fn default() -> Self {
+        MethodNaming {
+            template: "{verb}_{field}".to_string(),
+        }
     }
 }
 
-/// Parse method lists for global attributes
-struct GlobalMethods {
-    methods: Vec<String>,
+impl MethodNaming {
+    /// Name of the method for `verb` on `field`, keeping the variant `suffix` last
+    fn method_name(&self, verb: &str, field: &str, suffix: &str) -> String {
+        let name = self
+            .template
+            .replace("{verb}", verb)
+            .replace("{field}", field);
+        format!("{}{}", name, suffix)
+    }
+
+    /// Identifier of the method for `verb` on the field `field`, spanning the field
+    fn method_ident(&self, verb: &str, field: &Ident, suffix: &str) -> Ident {
+        Ident::new(
+            &self.method_name(verb, &field.unraw().to_string(), suffix),
+            field.span(),
+        )
+    }
+
+    /// Split the name of a method generated for the field `field_name`, optionally followed by
+    /// the `_once`/`_default`/`_with_timeout` suffixes, after the `_to` of `drag_<field>_to` too,
+    /// into its verb and suffix.
+    fn split<'a>(&self, name: &'a str, field_name: &str) -> Option<(&'a str, &'a str)> {
+        let template = self.template.replace("{field}", field_name);
+        let (before, after) = template.split_once("{verb}")?;
+        let rest = name.strip_prefix(before)?;
+        [
+            "",
+            "_once",
+            "_default",
+            "_with_timeout",
+            "_to",
+            "_to_default",
+            "_to_with_timeout",
+        ]
+        .into_iter()
+        .find_map(|suffix| {
+            let verb = rest.strip_suffix(suffix)?.strip_suffix(after)?;
+            (!verb.is_empty()).then(|| (verb, &rest[verb.len() + after.len()..]))
+        })
+    }
+}
+
+/// `expect_count = ...` on a field: the inclusive range of matches its selector should have
+#[derive(Clone, Copy)]
+struct ExpectedCount {
+    min: usize,
+    /// `None` for an open range such as `1..`
+    max: Option<usize>,
 }
 
-impl Parse for GlobalMethods {
+impl Parse for ExpectedCount {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        // Expect the keyword "global"
-        let ident: Ident = input.parse()?;
-        if ident != "global" {
-            return Err(syn::Error::new(ident.span(), "expected 'global'"));
+        let start: syn::LitInt = input.parse()?;
+        let min = start.base10_parse()?;
+        let max = if input.peek(syn::Token![..=]) {
+            input.parse::<syn::Token![..=]>()?;
+            Some(input.parse::<syn::LitInt>()?.base10_parse()?)
+        } else if input.peek(syn::Token![..]) {
+            input.parse::<syn::Token![..]>()?;
+            if input.peek(syn::LitInt) {
+                let end: syn::LitInt = input.parse()?;
+                let end: usize = end.base10_parse()?;
+                if end == 0 {
+                    return Err(syn::Error::new(end.span(), "expect_count range is empty"));
+                }
+                Some(end - 1)
+            } else {
+                None
+            }
+        } else {
+            Some(min)
+        };
+        if max.is_some_and(|max| max < min) {
+            return Err(syn::Error::new(start.span(), "expect_count range is empty"));
         }
+        Ok(ExpectedCount { min, max })
+    }
+}
 
-        // Parse the parenthesized content
-        let content;
-        syn::parenthesized!(content in input);
+impl ExpectedCount {
+    /// Describe the expectation for errors, e.g. "exactly 1" or "at least 2"
+    fn describe(&self) -> String {
+        match self.max {
+            Some(max) if max == self.min => format!("exactly {}", max),
+            Some(max) => format!("{} to {}", self.min, max),
+            None => format!("at least {}", self.min),
+        }
+    }
+}
+
+/// Every per-field action, in the order they are generated
+const ACTIONS: &[&str] = &[
+    "click",
+    "double_click",
+    "right_click",
+    "enter_keys",
+    "send_key",
+    "clear",
+    "submit",
+    "hover",
+    "drag_to",
+    "click_and_answer_prompt",
+    "check_link",
+    "draw_path",
+    "get_text",
+    "get_attribute",
+    "get_attributes_all",
+    "get_value",
+    "get_css_value",
+    "has_class",
+    "get_class_list",
+    "add_class",
+    "remove_class",
+    "toggle_class",
+    "get_validity",
+    "get_canvas_data_url",
+    "is_displayed",
+    "is_selected",
+    "is_enabled",
+    "is_focused",
+    "check_focus_trap",
+    "assert_above_fold",
+    "exists",
+    "get_element_state",
+    "select_by_text",
+    "select_by_value",
+    "select_by_index",
+    "get_selected_text",
+    "custom_dropdown",
+    "find_within",
+    "get_children",
+    "get_parent",
+    "get_shadow_root",
+    "dump_subtree",
+    "scroll_to",
+    "wait_for",
+    "wait_until_clickable",
+    "wait_for_enabled_then_click",
+    "wait_for_no_overlap",
+    "wait_for_attribute_to_contain",
+    "wait_for_value",
+    "wait_for_attribute_to_match",
+    "take_screenshot",
+    "screenshot_with_highlight",
+    "read_text_ocr",
+];
+
+/// Actions usable with the enabled cargo features, as expanded by `methods(all)`
+fn available_actions() -> impl Iterator<Item = &'static str> {
+    ACTIONS.iter().copied().filter(|action| {
+        (*action != "check_link" || cfg!(feature = "reqwest"))
+            && (*action != "read_text_ocr" || cfg!(feature = "ocr"))
+            && (*action != "wait_for_attribute_to_match" || cfg!(feature = "regex"))
+    })
+}
 
-        // Parse comma-separated identifiers
-        let method_names = Punctuated::<Ident, Comma>::parse_terminated(&content)?;
-        let methods = method_names.into_iter().map(|id| id.to_string()).collect();
+/// Actions that take only the driver, which `debug_menu` can trigger by name
+const DEBUG_MENU_ACTIONS: &[&str] = &[
+    "click",
+    "double_click",
+    "right_click",
+    "clear",
+    "submit",
+    "hover",
+    "get_text",
+    "get_value",
+    "get_validity",
+    "get_class_list",
+    "is_displayed",
+    "is_selected",
+    "is_enabled",
+    "is_focused",
+    "exists",
+    "get_element_state",
+    "get_selected_text",
+    "get_children",
+    "get_parent",
+    "get_shadow_root",
+    "scroll_to",
+];
 
-        Ok(GlobalMethods { methods })
+/// Semantic role of a field, used by struct-level helpers
+#[derive(Clone, Copy, PartialEq)]
+enum Role {
+    /// Error text shown after a failed submit
+    ErrorMessage,
+    /// The control that submits the form
+    Submit,
+    /// Element that is visible once the page has finished loading
+    LoadMarker,
+    /// Dismissable element covering the page (cookie banners, promos)
+    Overlay,
+}
+
+impl Parse for Role {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lit: syn::LitStr = input.parse()?;
+        match lit.value().as_str() {
+            "error_message" => Ok(Role::ErrorMessage),
+            "submit" => Ok(Role::Submit),
+            "load_marker" => Ok(Role::LoadMarker),
+            "overlay" => Ok(Role::Overlay),
+            other => Err(syn::Error::new(
+                lit.span(),
+                format!(
+                    "unknown role '{}', expected \"error_message\", \"submit\", \"load_marker\" or \"overlay\"",
+                    other
+                ),
+            )),
+        }
     }
 }
 
-/// The custom derive macro automatically generates asynchronous helper methods for web elements.
-///
-/// For every field in the struct, it always generates a base query method named:
-///     query_<field>(&self, driver: &thirtyfour::WebDriver)
-///
-/// Global methods can be specified at the struct level:
-///     #[thirtyfour_actions(global(click, is_displayed))]
-///
-/// Field-specific methods can be added:
-///     #[thirtyfour_actions(methods(enter_keys, clear))]
+/// Selector declared inline on a field with `css`, `xpath`, `id` or `name`
+#[derive(Clone)]
+struct Selector {
+    /// Name of the `thirtyfour::By` constructor
+    constructor: Ident,
+    value: syn::LitStr,
+}
+
+impl Selector {
+    /// Render the selector like its constructor call, e.g. `By::Css("#login")`
+    fn describe(&self) -> String {
+        format!("By::{}({:?})", self.constructor, self.value.value())
+    }
+
+    /// The selector's `{placeholder}`s, in order of first appearance
+    fn placeholders(&self) -> Vec<Ident> {
+        let value = self.value.value();
+        let mut names: Vec<&str> = Vec::new();
+        for (_, name) in placeholder_spans(&value) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+            .into_iter()
+            .map(|name| placeholder_ident(name, self.value.span()))
+            .collect()
+    }
+}
+
+/// Parameter name for a `{placeholder}`, made raw when it is a keyword such as `{type}`
+fn placeholder_ident(name: &str, span: proc_macro2::Span) -> Ident {
+    if syn::parse_str::<Ident>(name).is_ok() {
+        Ident::new(name, span)
+    } else {
+        Ident::new_raw(name, span)
+    }
+}
+
+/// Byte ranges and names of the `{placeholder}`s in a selector
+fn placeholder_spans(value: &str) -> Vec<(std::ops::Range<usize>, &str)> {
+    let mut spans = Vec::new();
+    let mut rest = 0;
+    while let Some(open) = value[rest..].find('{').map(|offset| rest + offset) {
+        let Some(close) = value[open..].find('}').map(|offset| open + offset) else {
+            break;
+        };
+        let name = &value[open + 1..close];
+        let is_ident = name
+            .chars()
+            .next()
+            .is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+            && name.chars().all(|c| c == '_' || c.is_ascii_alphanumeric());
+        if is_ident {
+            spans.push((open..close + 1, name));
+            rest = close + 1;
+        } else {
+            rest = open + 1;
+        }
+    }
+    spans
+}
+
+/// The `thirtyfour::By` constructor for a selector option (`css`, `xpath`, `id` or `name`)
+fn selector_constructor(kind: &str) -> Option<&'static str> {
+    match kind {
+        "css" => Some("Css"),
+        "xpath" => Some("XPath"),
+        "id" => Some("Id"),
+        "name" => Some("Name"),
+        _ => None,
+    }
+}
+
+/// Check the syntax of a CSS or XPath selector for the `By` constructor `constructor`.
 ///
-/// Global methods are applied to ALL fields, and can be combined with field-specific methods.
-#[proc_macro_derive(ImplThirtyfourActions, attributes(thirtyfour_actions))]
-pub fn impl_thirtyfour_actions(input: TokenStream) -> TokenStream {
-    let input_parsed = parse_macro_input!(input as DeriveInput);
-    let input_span = input_parsed.span();
-    let struct_name = input_parsed.ident;
+/// `{placeholder}`s are checked as if filled with a plain word.
+fn check_selector(constructor: &str, value: &str) -> Result<(), String> {
+    let mut filled = value.to_string();
+    for (range, _) in placeholder_spans(value).into_iter().rev() {
+        filled.replace_range(range, "x");
+    }
+    let value = filled.as_str();
+    match constructor {
+        "Css" => {
+            selector_syntax::validate_css(value).map_err(|e| format!("invalid CSS selector: {}", e))
+        }
+        "XPath" => selector_syntax::validate_xpath(value)
+            .map_err(|e| format!("invalid XPath selector: {}", e)),
+        _ => Ok(()),
+    }
+}
 
-    let mut methods = Vec::new();
+/// A checked `selectors_file`
+struct SelectorsFile {
+    /// Resolved path, for rebuilds when the file changes
+    path: String,
+    /// Selector of each field named in the file, with the line it was declared on
+    selectors: HashMap<String, (Selector, usize)>,
+}
 
-    // Extract global methods from struct attributes
-    let mut global_methods = Vec::new();
-    for attr in &input_parsed.attrs {
-        if attr.path().is_ident("thirtyfour_actions") {
-            match attr.parse_args::<GlobalMethods>() {
-                Ok(parsed) => {
-                    global_methods.extend(parsed.methods);
-                }
-                Err(_) => {
-                    // It's not a global attribute, might be something else
-                    continue;
+/// Read and check a `selectors_file`, resolved against the consuming crate's manifest directory
+fn read_selectors_file(file: &syn::LitStr) -> syn::Result<SelectorsFile> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path = std::path::Path::new(&manifest_dir).join(file.value());
+    let source = std::fs::read_to_string(&path).map_err(|e| {
+        syn::Error::new(
+            file.span(),
+            format!("failed to read selectors file {}: {}", path.display(), e),
+        )
+    })?;
+    let selectors = selectors_file::parse(&source).map_err(|e| {
+        syn::Error::new(
+            file.span(),
+            format!("in selectors file {}: {}", file.value(), e),
+        )
+    })?;
+
+    let mut by_field = HashMap::new();
+    for selector in selectors {
+        let error = |message: String| {
+            syn::Error::new(
+                file.span(),
+                format!(
+                    "in selectors file {}: line {}: {}",
+                    file.value(),
+                    selector.line,
+                    message
+                ),
+            )
+        };
+        let constructor = selector_constructor(&selector.kind).ok_or_else(|| {
+            error(format!(
+                "unknown selector kind '{}' for '{}', expected css, xpath, id or name",
+                selector.kind, selector.field
+            ))
+        })?;
+        check_selector(constructor, &selector.value)
+            .map_err(|e| error(format!("selector for '{}': {}", selector.field, e)))?;
+        by_field.insert(
+            selector.field,
+            (
+                Selector {
+                    constructor: Ident::new(constructor, file.span()),
+                    value: syn::LitStr::new(&selector.value, file.span()),
+                },
+                selector.line,
+            ),
+        );
+    }
+    Ok(SelectorsFile {
+        path: path.display().to_string(),
+        selectors: by_field,
+    })
+}
+
+/// A single item of a field-level `thirtyfour_actions(...)` attribute
+enum FieldItem {
+    /// `methods(...)` or `all_except(...)`: methods generated for this field
+    Methods(Vec<String>),
+    /// `role = "..."`: semantic role of this field
+    Role(Role),
+    /// `unique`: fail the query if the selector matches more than one element
+    Unique,
+    /// `sensitive`: never expose values passed to this field's actions
+    Sensitive,
+    /// `no_query`: keep the base `query_<field>` method private
+    NoQuery,
+    /// `vis = "pub(crate)"`: visibility of the field's public generated methods
+    Vis(syn::Visibility),
+    /// `optional`: actions return `Ok(None)`/`Ok(false)` when the element is absent
+    Optional,
+    /// `skip`: not a locator, ignore the field entirely
+    Skip,
+    /// `component`: a nested page object deriving `ImplThirtyfourActions`, not a locator
+    Component,
+    /// `resolver`: a thirtyfour `ElementResolver`, resolved instead of queried by selector
+    Resolver,
+    /// `root`: the element every other field of the struct is searched within
+    Root,
+    /// `lazy`: left out of `resolve_all` unless lazy fields are asked for
+    Lazy,
+    /// `within = "field"`: search for this field inside the element of a sibling field
+    Within(Ident),
+    /// `alias = "name"`: also generate this field's methods under `name`
+    Alias(Ident),
+    /// `rename = "name"` or `field_name = "name"`: name the field differently in its generated
+    /// methods, or name an unnamed field of a tuple struct
+    FieldName(Ident),
+    /// `shadow_root = "field"`: search for this field inside the shadow root of a host field
+    ShadowRoot(Ident),
+    /// `cfg_feature = "..."`: only generate this field's actions when the consumer enables the feature
+    CfgFeature(syn::LitStr),
+    /// `fallbacks(css = "...", ...)`: selectors tried in order when the field's own matches nothing
+    Fallbacks(Vec<Selector>),
+    /// `nth = N`: use the match at zero-based index `N` instead of the first one
+    Nth(usize),
+    /// `disabled_reason = "attr"`: attribute explaining why the element is disabled, for errors
+    DisabledReason(syn::LitStr),
+    /// `with_text = "..."`: only match elements with this text
+    WithText(syn::LitStr),
+    /// `with_attribute("name", "value")`: only match elements with this attribute value
+    WithAttribute(syn::LitStr, syn::LitStr),
+    /// `dropdown_search = "css"`: search box of a `custom_dropdown` once it is open
+    DropdownSearch(syn::LitStr),
+    /// `dropdown_option = "css"`: options of a `custom_dropdown` once it is open
+    DropdownOption(syn::LitStr),
+    /// `no_defaults`: ignore the struct's `global`/`default_methods` for this field
+    NoDefaults,
+    /// `css = "..."`, `xpath = "..."`, `id = "..."` or `name = "..."`: inline selector
+    Selector(Selector),
+    /// `timeout_secs = N`: how long this field's queries and `_default` waits wait for it
+    TimeoutSecs(u64),
+    /// `expect_count = N` or a range such as `1..=3`: how many elements the selector should match,
+    /// checked by `validate_counts`
+    ExpectCount(ExpectedCount),
+    /// `done_when = "field"`: a displayed `field` means this field's click/submit took effect
+    DoneWhen(Ident),
+    /// `done_when_url = "..."`: a current URL containing the text means the click/submit took effect
+    DoneWhenUrl(syn::LitStr),
+    /// `locator = "..."`: initial value of a string field built into a `By` by `locator_builder`
+    Locator(syn::LitStr),
+    /// `group = "..."`: label of the reduced page object the field is also part of
+    Group(syn::LitStr),
+}
+
+impl Parse for FieldItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident: Ident = input.parse()?;
+        match ident.to_string().as_str() {
+            "methods" => Ok(FieldItem::Methods(parse_method_list(input)?)),
+            "all_except" => Ok(FieldItem::Methods(parse_all_except(input)?)),
+            "role" => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(FieldItem::Role(input.parse()?))
+            }
+            "unique" | "strict" => Ok(FieldItem::Unique),
+            "sensitive" => Ok(FieldItem::Sensitive),
+            "no_query" => Ok(FieldItem::NoQuery),
+            "vis" => Ok(FieldItem::Vis(parse_str_value(input)?.parse()?)),
+            "optional" => Ok(FieldItem::Optional),
+            "no_defaults" => Ok(FieldItem::NoDefaults),
+            "skip" => Ok(FieldItem::Skip),
+            "component" => Ok(FieldItem::Component),
+            "resolver" => Ok(FieldItem::Resolver),
+            "root" => Ok(FieldItem::Root),
+            "lazy" => Ok(FieldItem::Lazy),
+            "within" => Ok(FieldItem::Within(parse_str_value(input)?.parse()?)),
+            "alias" => Ok(FieldItem::Alias(parse_str_value(input)?.parse()?)),
+            "rename" | "field_name" => Ok(FieldItem::FieldName(parse_str_value(input)?.parse()?)),
+            "shadow_root" => Ok(FieldItem::ShadowRoot(parse_str_value(input)?.parse()?)),
+            "cfg_feature" => Ok(FieldItem::CfgFeature(parse_str_value(input)?)),
+            "nth" => {
+                input.parse::<syn::Token![=]>()?;
+                let lit: syn::LitInt = input.parse()?;
+                Ok(FieldItem::Nth(lit.base10_parse()?))
+            }
+            "disabled_reason" => Ok(FieldItem::DisabledReason(parse_str_value(input)?)),
+            "with_text" => Ok(FieldItem::WithText(parse_str_value(input)?)),
+            "dropdown_search" | "dropdown_option" => {
+                let value = parse_str_value(input)?;
+                selector_syntax::validate_css(&value.value()).map_err(|e| {
+                    syn::Error::new(value.span(), format!("invalid CSS selector: {}", e))
+                })?;
+                if ident == "dropdown_search" {
+                    Ok(FieldItem::DropdownSearch(value))
+                } else {
+                    Ok(FieldItem::DropdownOption(value))
                 }
             }
+            "with_attribute" => {
+                let content;
+                syn::parenthesized!(content in input);
+                let name = content.parse()?;
+                content.parse::<Comma>()?;
+                let value = content.parse()?;
+                Ok(FieldItem::WithAttribute(name, value))
+            }
+            "fallbacks" => {
+                let content;
+                syn::parenthesized!(content in input);
+                Punctuated::<FieldItem, Comma>::parse_terminated(&content)?
+                    .into_iter()
+                    .map(|item| match item {
+                        FieldItem::Selector(selector) => Ok(selector),
+                        _ => Err(syn::Error::new(
+                            ident.span(),
+                            "fallbacks(...) takes only `css`, `xpath`, `id` or `name` selectors",
+                        )),
+                    })
+                    .collect::<syn::Result<_>>()
+                    .map(FieldItem::Fallbacks)
+            }
+            "timeout_secs" => {
+                input.parse::<syn::Token![=]>()?;
+                let lit: syn::LitInt = input.parse()?;
+                Ok(FieldItem::TimeoutSecs(lit.base10_parse()?))
+            }
+            "expect_count" => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(FieldItem::ExpectCount(input.parse()?))
+            }
+            "done_when" => Ok(FieldItem::DoneWhen(parse_str_value(input)?.parse()?)),
+            "done_when_url" => Ok(FieldItem::DoneWhenUrl(parse_str_value(input)?)),
+            "locator" => Ok(FieldItem::Locator(parse_str_value(input)?)),
+            "group" => Ok(FieldItem::Group(parse_str_value(input)?)),
+            "css" | "xpath" | "id" | "name" => {
+                let constructor = selector_constructor(&ident.to_string()).expect("selector kind");
+                let value = parse_str_value(input)?;
+                check_selector(constructor, &value.value())
+                    .map_err(|e| syn::Error::new(value.span(), e))?;
+                Ok(FieldItem::Selector(Selector {
+                    constructor: Ident::new(constructor, ident.span()),
+                    value,
+                }))
+            }
+            _ => Err(syn::Error::new(
+                ident.span(),
+                format!("unknown thirtyfour_actions field option '{}'", ident),
+            )),
         }
     }
+}
 
-    if let syn::Data::Struct(data_struct) = input_parsed.data {
-        for field in data_struct.fields {
-            if let Some(ref field_ident) = field.ident {
-                let field_name_str = field_ident.to_string();
-                // Always generate the base query method.
-                let query_fn_ident =
-                    syn::Ident::new(&format!("query_{}", field_ident), field_ident.span());
-                let query_method = quote! {
-                    /// Query the web element from the DOM.
-                    ///
-                    /// Returns `Some(WebElement)` if found, `None` otherwise.
-                    pub async fn #query_fn_ident(&self, driver: &thirtyfour::WebDriver) -> Option<thirtyfour::WebElement> {
-                        match driver.query(self.#field_ident.clone()).first_opt().await {
-                            Ok(Some(element)) => Some(element),
-                            Ok(None) => None,
-                            Err(e) => {
-                                log::error!("Error querying element {}: {}", #field_name_str, e);
-                                None
-                            }
-                        }
+/// Options collected from all attributes of a single field
+#[derive(Default)]
+struct FieldOptions {
+    methods: Vec<String>,
+    role: Option<Role>,
+    unique: bool,
+    sensitive: bool,
+    no_query: bool,
+    vis: Option<syn::Visibility>,
+    optional: bool,
+    no_defaults: bool,
+    skip: bool,
+    component: bool,
+    resolver: bool,
+    root: bool,
+    lazy: bool,
+    within: Option<Ident>,
+    aliases: Vec<Ident>,
+    field_name: Option<Ident>,
+    shadow_root: Option<Ident>,
+    cfg_feature: Option<syn::LitStr>,
+    fallbacks: Vec<Selector>,
+    nth: Option<usize>,
+    disabled_reason: Option<syn::LitStr>,
+    with_text: Option<syn::LitStr>,
+    with_attributes: Vec<(syn::LitStr, syn::LitStr)>,
+    dropdown_search: Option<syn::LitStr>,
+    dropdown_option: Option<syn::LitStr>,
+    selector: Option<Selector>,
+    timeout_secs: Option<u64>,
+    expect_count: Option<ExpectedCount>,
+    done_when: Option<Ident>,
+    done_when_url: Option<syn::LitStr>,
+    locator: Option<syn::LitStr>,
+    /// Set from the field type rather than an attribute: the field is a map of selectors,
+    /// picked by a `key` argument of its methods
+    keyed: bool,
+    /// Set from the field type: the field holds an already located `WebElement`, used as is
+    stored_element: bool,
+    /// Set from the field type and the struct's `locator_builder`: the field holds a string the
+    /// builder turns into the `By` it is queried with
+    built_with: Option<syn::Path>,
+}
+
+impl FieldOptions {
+    /// Whether the field is located without a selector, by its resolver or as a stored element
+    fn selectorless(&self) -> bool {
+        self.resolver || self.stored_element
+    }
+
+    fn apply(&mut self, item: FieldItem) {
+        match item {
+            FieldItem::Methods(methods) => self.methods.extend(methods),
+            FieldItem::Role(role) => self.role = Some(role),
+            FieldItem::Unique => self.unique = true,
+            FieldItem::Sensitive => self.sensitive = true,
+            FieldItem::NoQuery => self.no_query = true,
+            FieldItem::Vis(vis) => self.vis = Some(vis),
+            FieldItem::Optional => self.optional = true,
+            FieldItem::NoDefaults => self.no_defaults = true,
+            FieldItem::Skip => self.skip = true,
+            FieldItem::Component => self.component = true,
+            FieldItem::Resolver => self.resolver = true,
+            FieldItem::Root => self.root = true,
+            FieldItem::Lazy => self.lazy = true,
+            FieldItem::Within(parent) => self.within = Some(parent),
+            FieldItem::Alias(alias) => self.aliases.push(alias),
+            FieldItem::FieldName(name) => self.field_name = Some(name),
+            FieldItem::ShadowRoot(host) => self.shadow_root = Some(host),
+            FieldItem::CfgFeature(feature) => self.cfg_feature = Some(feature),
+            FieldItem::Fallbacks(fallbacks) => self.fallbacks.extend(fallbacks),
+            FieldItem::Nth(nth) => self.nth = Some(nth),
+            FieldItem::DisabledReason(attribute) => self.disabled_reason = Some(attribute),
+            FieldItem::WithText(text) => self.with_text = Some(text),
+            FieldItem::WithAttribute(name, value) => self.with_attributes.push((name, value)),
+            FieldItem::DropdownSearch(selector) => self.dropdown_search = Some(selector),
+            FieldItem::DropdownOption(selector) => self.dropdown_option = Some(selector),
+            FieldItem::Selector(selector) => self.selector = Some(selector),
+            FieldItem::TimeoutSecs(timeout_secs) => self.timeout_secs = Some(timeout_secs),
+            FieldItem::ExpectCount(expected) => self.expect_count = Some(expected),
+            FieldItem::DoneWhen(marker) => self.done_when = Some(marker),
+            FieldItem::DoneWhenUrl(url) => self.done_when_url = Some(url),
+            FieldItem::Locator(locator) => self.locator = Some(locator),
+            // Read by `group_structs`, which derives the group's struct
+            FieldItem::Group(_) => {}
+        }
+    }
+}
+
+/// A single item of a struct-level `thirtyfour_actions(...)` attribute
+enum StructItem {
+    /// `global(...)` or `default_methods(...)`: methods applied to every field
+    Global(Vec<String>),
+    /// `storage`: generate local/session storage helpers
+    Storage,
+    /// `alerts`: generate alert and prompt helpers
+    Alerts,
+    /// `sink = "field"`: report every action call to the `ActionSink` stored in `field`
+    Sink(Ident),
+    /// `driver = "field"`: use the `WebDriver` stored in `field` instead of a `driver` parameter
+    Driver(Ident),
+    /// `soft_assert = "field"`: record failures of `assert_*` actions in the `SoftAssertions`
+    /// stored in `field` instead of returning them
+    SoftAssert(Ident),
+    /// `soft_methods(...)`: further actions whose failures are recorded by `soft_assert`
+    SoftMethods(Vec<String>),
+    /// `as_trait = "Name"`: also expose the generated methods through a trait implemented for the struct
+    AsTrait(Ident),
+    /// `rate_limit` or `rate_limit = N`: throttle actions, optionally starting at N per second
+    RateLimit(Option<f64>),
+    /// `session_lock`: serialize actions on the same driver session
+    SessionLock,
+    /// `headless_aware` or `headless_aware = true`: let pacing, rate limiting and hover dwell be
+    /// switched off at runtime for headless or CI sessions, detected from `CI` unless forced
+    HeadlessAware(Option<bool>),
+    /// `hover_dwell_ms = N`: wait N milliseconds after hovering, as a user watching a menu would
+    HoverDwellMs(u64),
+    /// `field_enum`: generate an enum naming the struct's fields, and helpers using it
+    FieldEnum,
+    /// `perform`: generate `perform`, running a field's action by name (implies `field_enum`)
+    Perform,
+    /// `from_selectors`: generate `from_selectors`, building the struct from a `SelectorConfig`
+    FromSelectors,
+    /// `return_element`: interactions return the element acted upon instead of `()`
+    ReturnElement,
+    /// `display`: implement `Display` summarizing fields, selectors and configuration
+    Display,
+    /// `race(a, b)`: wait for whichever of two fields appears first
+    Race(Ident, Ident),
+    /// `screenshot(...)`: re-encode and scale screenshots (requires the `image` feature)
+    Screenshot(ScreenshotOptions),
+    /// `url = "..."`: the page's address, opened by the generated `open`
+    Url(syn::LitStr),
+    /// `before_open = "path::to::hook"`: async setup function `open` awaits before navigating
+    BeforeOpen(syn::Path),
+    /// `selectors_file = "..."`: TOML file holding the selectors of fields without inline ones
+    SelectorsFile(syn::LitStr),
+    /// `geolocation(lat = ..., lon = ...)`: geolocation override applied by `open`
+    Geolocation(Geolocation),
+    /// `timezone = "..."`: timezone override applied by `open`
+    Timezone(syn::LitStr),
+    /// `locale = "..."`: locale override applied by `open`
+    Locale(syn::LitStr),
+    /// `requires_cookie = "..."`: cookie checked by `ensure_preconditions` (repeatable)
+    RequiresCookie(syn::LitStr),
+    /// `expected_url_pattern = "..."`: regex the current URL must match (requires the `regex` feature)
+    ExpectedUrlPattern(syn::LitStr),
+    /// `slow_threshold_ms = N`: log a warning for actions taking longer than N milliseconds
+    SlowThresholdMs(u64),
+    /// `log_success` or `log_success = false`: log successful actions at debug level, toggled at
+    /// runtime and optionally starting disabled
+    LogSuccess(bool),
+    /// `dry_run` or `dry_run = true`: let interactions be switched at runtime to only validate their
+    /// element and record what they would have done, optionally starting switched on
+    DryRun(bool),
+    /// `wait_between_actions(ms = N, field = "...")`: keep at least N milliseconds between the
+    /// end of one action and the start of the next on the same instance
+    WaitBetweenActions(ActionPacing),
+    /// `session_audit = "field"`: check every action runs on the driver session the instance was
+    /// bound to, tracked by the `SessionAudit` in that field
+    SessionAudit(Ident),
+    /// `locator_builder = "path::to::fn"`: function building the `By` of string-typed fields
+    LocatorBuilder(syn::Path),
+    /// `error = "path::to::Error"`: error type of generated methods instead of `anyhow::Error`
+    Error(syn::Path),
+    /// `crate = "path::to::thirtyfour"`: path generated code reaches `thirtyfour` by
+    Crate(syn::Path),
+    /// `anyhow = "path::to::anyhow"`: path generated code reaches `anyhow` by
+    Anyhow(syn::Path),
+    /// `log = "path::to::log"`: path generated code reaches `log` by
+    Log(syn::Path),
+    /// `tokio = "path::to::tokio"`: path generated code reaches `tokio` by
+    Tokio(syn::Path),
+    /// `serde_json = "path::to::serde_json"`: path generated code reaches `serde_json` by
+    SerdeJson(syn::Path),
+    /// `vis = "pub(crate)"`: visibility of public generated methods and constants
+    Vis(syn::Visibility),
+    /// `naming = "field_verb"`: order of field name and verb in generated method names, or a
+    /// template such as `"ui_{field}_{verb}"`
+    Naming(MethodNaming),
+    /// `structured_errors`: attach a `ThirtyfourActionsError` kind to every generated error
+    StructuredErrors,
+    /// `timeout_secs = N`: default timeout of waiting methods, used by their `_default` variants
+    TimeoutSecs(u64),
+    /// `poll_ms = N`: interval at which waiting methods poll, 500 ms by default
+    PollMs(u64),
+    /// `broadcast`: generate `broadcast`, running an action on several drivers concurrently
+    Broadcast,
+    /// `strict`: treat every field as `unique` unless it uses `nth` or `fallbacks`
+    Strict,
+}
+
+impl Parse for StructItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // `crate` is a keyword
+        let ident = Ident::parse_any(input)?;
+        match ident.to_string().as_str() {
+            "global" | "default_methods" => Ok(StructItem::Global(parse_method_list(input)?)),
+            "storage" => Ok(StructItem::Storage),
+            "alerts" => Ok(StructItem::Alerts),
+            "broadcast" => Ok(StructItem::Broadcast),
+            "strict" => Ok(StructItem::Strict),
+            "sink" => {
+                input.parse::<syn::Token![=]>()?;
+                let lit: syn::LitStr = input.parse()?;
+                Ok(StructItem::Sink(lit.parse()?))
+            }
+            "driver" => {
+                input.parse::<syn::Token![=]>()?;
+                let lit: syn::LitStr = input.parse()?;
+                Ok(StructItem::Driver(lit.parse()?))
+            }
+            "soft_assert" => {
+                input.parse::<syn::Token![=]>()?;
+                let lit: syn::LitStr = input.parse()?;
+                Ok(StructItem::SoftAssert(lit.parse()?))
+            }
+            "soft_methods" => {
+                let content;
+                syn::parenthesized!(content in input);
+                let actions = Punctuated::<Ident, Comma>::parse_terminated(&content)?;
+                for id in &actions {
+                    if !ACTIONS.contains(&id.to_string().as_str()) {
+                        return Err(syn::Error::new(
+                            id.span(),
+                            format!("Unsupported thirtyfour_actions method: '{}'", id),
+                        ));
                     }
-                };
-                methods.push(query_method);
+                }
+                Ok(StructItem::SoftMethods(
+                    actions.iter().map(|id| id.to_string()).collect(),
+                ))
+            }
+            "as_trait" => {
+                input.parse::<syn::Token![=]>()?;
+                let lit: syn::LitStr = input.parse()?;
+                Ok(StructItem::AsTrait(lit.parse()?))
+            }
+            "session_lock" => Ok(StructItem::SessionLock),
+            "headless_aware" => {
+                if input.peek(syn::Token![=]) {
+                    input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitBool = input.parse()?;
+                    Ok(StructItem::HeadlessAware(Some(lit.value)))
+                } else {
+                    Ok(StructItem::HeadlessAware(None))
+                }
+            }
+            "hover_dwell_ms" => {
+                input.parse::<syn::Token![=]>()?;
+                let lit: syn::LitInt = input.parse()?;
+                Ok(StructItem::HoverDwellMs(lit.base10_parse()?))
+            }
+            "field_enum" => Ok(StructItem::FieldEnum),
+            "perform" => Ok(StructItem::Perform),
+            "from_selectors" => Ok(StructItem::FromSelectors),
+            "display" => Ok(StructItem::Display),
+            "return_element" => Ok(StructItem::ReturnElement),
+            "structured_errors" => Ok(StructItem::StructuredErrors),
+            "vis" => Ok(StructItem::Vis(parse_str_value(input)?.parse()?)),
+            "screenshot" => {
+                let options = input.parse()?;
+                if cfg!(feature = "image") {
+                    Ok(StructItem::Screenshot(options))
+                } else {
+                    Err(syn::Error::new(
+                        ident.span(),
+                        "screenshot(...) requires the `image` feature",
+                    ))
+                }
+            }
+            "url" => Ok(StructItem::Url(parse_str_value(input)?)),
+            "before_open" => Ok(StructItem::BeforeOpen(parse_str_value(input)?.parse()?)),
+            "selectors_file" => Ok(StructItem::SelectorsFile(parse_str_value(input)?)),
+            "geolocation" => Ok(StructItem::Geolocation(input.parse()?)),
+            "timezone" => Ok(StructItem::Timezone(parse_str_value(input)?)),
+            "locale" => Ok(StructItem::Locale(parse_str_value(input)?)),
+            "requires_cookie" => Ok(StructItem::RequiresCookie(parse_str_value(input)?)),
+            "expected_url_pattern" => {
+                let pattern = parse_str_value(input)?;
+                if cfg!(feature = "regex") {
+                    Ok(StructItem::ExpectedUrlPattern(pattern))
+                } else {
+                    Err(syn::Error::new(
+                        ident.span(),
+                        "expected_url_pattern requires the `regex` feature",
+                    ))
+                }
+            }
+            "error" => Ok(StructItem::Error(parse_str_value(input)?.parse()?)),
+            "crate" => Ok(StructItem::Crate(parse_str_value(input)?.parse()?)),
+            "anyhow" => Ok(StructItem::Anyhow(parse_str_value(input)?.parse()?)),
+            "log" => Ok(StructItem::Log(parse_str_value(input)?.parse()?)),
+            "tokio" => Ok(StructItem::Tokio(parse_str_value(input)?.parse()?)),
+            "serde_json" => Ok(StructItem::SerdeJson(parse_str_value(input)?.parse()?)),
+            "naming" => Ok(StructItem::Naming(input.parse()?)),
+            "locator_builder" => Ok(StructItem::LocatorBuilder(parse_str_value(input)?.parse()?)),
+            "slow_threshold_ms" => {
+                input.parse::<syn::Token![=]>()?;
+                let lit: syn::LitInt = input.parse()?;
+                Ok(StructItem::SlowThresholdMs(lit.base10_parse()?))
+            }
+            "log_success" => {
+                if input.peek(syn::Token![=]) {
+                    input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitBool = input.parse()?;
+                    Ok(StructItem::LogSuccess(lit.value))
+                } else {
+                    Ok(StructItem::LogSuccess(true))
+                }
+            }
+            "dry_run" => {
+                if input.peek(syn::Token![=]) {
+                    input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitBool = input.parse()?;
+                    Ok(StructItem::DryRun(lit.value))
+                } else {
+                    Ok(StructItem::DryRun(false))
+                }
+            }
+            "wait_between_actions" => Ok(StructItem::WaitBetweenActions(input.parse()?)),
+            "session_audit" => Ok(StructItem::SessionAudit(parse_str_value(input)?.parse()?)),
+            "timeout_secs" => {
+                input.parse::<syn::Token![=]>()?;
+                let lit: syn::LitInt = input.parse()?;
+                Ok(StructItem::TimeoutSecs(lit.base10_parse()?))
+            }
+            "poll_ms" => {
+                input.parse::<syn::Token![=]>()?;
+                let lit: syn::LitInt = input.parse()?;
+                let poll_ms = lit.base10_parse()?;
+                if poll_ms == 0 {
+                    return Err(syn::Error::new(lit.span(), "poll_ms must be at least 1"));
+                }
+                Ok(StructItem::PollMs(poll_ms))
+            }
+            "race" => {
+                let content;
+                syn::parenthesized!(content in input);
+                let fields = Punctuated::<Ident, Comma>::parse_terminated(&content)?;
+                if fields.len() != 2 {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "race(...) expects exactly two fields",
+                    ));
+                }
+                let mut fields = fields.into_iter();
+                Ok(StructItem::Race(
+                    fields.next().unwrap(),
+                    fields.next().unwrap(),
+                ))
+            }
+            "rate_limit" => {
+                if input.peek(syn::Token![=]) {
+                    input.parse::<syn::Token![=]>()?;
+                    Ok(StructItem::RateLimit(Some(parse_number(input)?)))
+                } else {
+                    Ok(StructItem::RateLimit(None))
+                }
+            }
+            _ => Err(syn::Error::new(
+                ident.span(),
+                format!("unknown thirtyfour_actions struct option '{}'", ident),
+            )),
+        }
+    }
+}
 
-                // Combine global methods with field-specific methods
-                let mut all_methods = global_methods.clone();
-
-                // Add field-specific methods
-                for attr in &field.attrs {
-                    if attr.path().is_ident("thirtyfour_actions") {
-                        match attr.parse_args::<ElementMethods>() {
-                            Ok(parsed) => {
-                                all_methods.extend(parsed.methods);
-                            }
-                            Err(e) => {
-                                return syn::Error::new(
-                                    attr.span(),
-                                    format!("Failed to parse thirtyfour_actions attribute: {}", e),
-                                )
-                                .to_compile_error()
-                                .into();
-                            }
+/// Output format of screenshot-producing actions
+#[derive(Clone, Copy, PartialEq)]
+enum ScreenshotFormat {
+    Png,
+    Jpeg,
+    WebP,
+}
+
+/// `screenshot(format = "...", quality = N, max_width = N)` options
+#[derive(Clone, Copy)]
+struct ScreenshotOptions {
+    format: ScreenshotFormat,
+    quality: u8,
+    max_width: Option<u32>,
+}
+
+impl Parse for ScreenshotOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = ScreenshotOptions {
+            format: ScreenshotFormat::Png,
+            quality: 80,
+            max_width: None,
+        };
+
+        let content;
+        syn::parenthesized!(content in input);
+        while !content.is_empty() {
+            let key: Ident = content.parse()?;
+            content.parse::<syn::Token![=]>()?;
+            match key.to_string().as_str() {
+                "format" => {
+                    let lit: syn::LitStr = content.parse()?;
+                    options.format = match lit.value().as_str() {
+                        "png" => ScreenshotFormat::Png,
+                        "jpeg" | "jpg" => ScreenshotFormat::Jpeg,
+                        "webp" => ScreenshotFormat::WebP,
+                        other => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                format!(
+                                    "unknown screenshot format '{}', expected \"png\", \"jpeg\" or \"webp\"",
+                                    other
+                                ),
+                            ));
                         }
+                    };
+                }
+                "quality" => {
+                    let lit: syn::LitInt = content.parse()?;
+                    options.quality = lit.base10_parse()?;
+                    if !(1..=100).contains(&options.quality) {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            "quality must be between 1 and 100",
+                        ));
                     }
                 }
+                "max_width" => {
+                    let lit: syn::LitInt = content.parse()?;
+                    options.max_width = Some(lit.base10_parse()?);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("unknown screenshot option '{}'", key),
+                    ));
+                }
+            }
+            if !content.is_empty() {
+                content.parse::<Comma>()?;
+            }
+        }
 
-                // Ensure we don't have duplicate methods
-                all_methods.sort();
-                all_methods.dedup();
+        Ok(options)
+    }
+}
 
-                // For each method requested, generate its implementation.
-                for method_name in all_methods {
-                    match method_name.as_str() {
-                        // Basic element interactions
-                        "click" => {
-                            let click_fn_ident = syn::Ident::new(
-                                &format!("click_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Click on the web element.
-                                pub async fn #click_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<()> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            element.click().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to click {}: {}", #field_name_str, e))?;
-                                            Ok(())
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
-                        }
-                        "double_click" => {
-                            let double_click_fn_ident = syn::Ident::new(
-                                &format!("double_click_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Double-click on the web element.
-                                pub async fn #double_click_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<()> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            let actions = driver.action_chain();
-                                            actions.double_click(&element).perform().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to double-click {}: {}", #field_name_str, e))?;
-                                            Ok(())
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
-                        }
-                        "right_click" => {
-                            let right_click_fn_ident = syn::Ident::new(
-                                &format!("right_click_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Right-click (context click) on the web element.
-                                pub async fn #right_click_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<()> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            let actions = driver.action_chain();
-                                            actions.context_click(&element).perform().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to right-click {}: {}", #field_name_str, e))?;
-                                            Ok(())
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
-                        }
-                        "enter_keys" => {
-                            let enter_fn_ident = syn::Ident::new(
-                                &format!("enter_keys_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Enter text into the web element.
-                                pub async fn #enter_fn_ident(&self, driver: &thirtyfour::WebDriver, keys: &str) -> anyhow::Result<()> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(input) => {
-                                            input.send_keys(keys).await
-                                                .map_err(|e| anyhow::anyhow!("Failed to send keys to {}: {}", #field_name_str, e))?;
-                                            Ok(())
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
-                        }
-                        "clear" => {
-                            let clear_fn_ident = syn::Ident::new(
-                                &format!("clear_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Clear input field content.
-                                pub async fn #clear_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<()> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            element.clear().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to clear {}: {}", #field_name_str, e))?;
-                                            Ok(())
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
+/// Options collected from all struct-level attributes
+#[derive(Default)]
+struct StructOptions {
+    global_methods: Vec<String>,
+    storage: bool,
+    alerts: bool,
+    sink: Option<Ident>,
+    driver: Option<Ident>,
+    soft_assert: Option<Ident>,
+    soft_methods: Vec<String>,
+    as_trait: Option<Ident>,
+    /// `Some` when rate limiting is enabled, holding the initial limit
+    rate_limit: Option<Option<f64>>,
+    session_lock: bool,
+    /// `Some` when headless awareness is enabled, holding the forced initial state if any
+    headless_aware: Option<Option<bool>>,
+    hover_dwell_ms: Option<u64>,
+    field_enum: bool,
+    perform: bool,
+    from_selectors: bool,
+    display: bool,
+    return_element: bool,
+    races: Vec<(Ident, Ident)>,
+    screenshot: Option<ScreenshotOptions>,
+    url: Option<syn::LitStr>,
+    before_open: Option<syn::Path>,
+    selectors_file: Option<syn::LitStr>,
+    geolocation: Option<Geolocation>,
+    timezone: Option<syn::LitStr>,
+    locale: Option<syn::LitStr>,
+    required_cookies: Vec<syn::LitStr>,
+    expected_url_pattern: Option<syn::LitStr>,
+    slow_threshold_ms: Option<u64>,
+    /// Whether successful actions are logged until changed at runtime, when enabled at all
+    log_success: Option<bool>,
+    /// Whether dry-run mode starts switched on, when it is available at all
+    dry_run: Option<bool>,
+    wait_between_actions: Option<ActionPacing>,
+    session_audit: Option<Ident>,
+    error: Option<syn::Path>,
+    crate_paths: CratePaths,
+    locator_builder: Option<syn::Path>,
+    naming: Option<MethodNaming>,
+    vis: Option<syn::Visibility>,
+    structured_errors: bool,
+    timeout_secs: Option<u64>,
+    poll_ms: Option<u64>,
+    broadcast: bool,
+    strict: bool,
+}
+
+impl StructOptions {
+    fn apply(&mut self, item: StructItem) {
+        match item {
+            StructItem::Global(methods) => self.global_methods.extend(methods),
+            StructItem::Storage => self.storage = true,
+            StructItem::Alerts => self.alerts = true,
+            StructItem::Broadcast => self.broadcast = true,
+            StructItem::Strict => self.strict = true,
+            StructItem::Sink(field) => self.sink = Some(field),
+            StructItem::Driver(field) => self.driver = Some(field),
+            StructItem::SoftAssert(field) => self.soft_assert = Some(field),
+            StructItem::SoftMethods(actions) => self.soft_methods.extend(actions),
+            StructItem::AsTrait(name) => self.as_trait = Some(name),
+            StructItem::RateLimit(max_per_second) => self.rate_limit = Some(max_per_second),
+            StructItem::SessionLock => self.session_lock = true,
+            StructItem::HeadlessAware(enabled) => self.headless_aware = Some(enabled),
+            StructItem::HoverDwellMs(dwell_ms) => self.hover_dwell_ms = Some(dwell_ms),
+            StructItem::FieldEnum => self.field_enum = true,
+            StructItem::FromSelectors => self.from_selectors = true,
+            StructItem::Perform => {
+                self.perform = true;
+                self.field_enum = true;
+            }
+            StructItem::Display => self.display = true,
+            StructItem::ReturnElement => self.return_element = true,
+            StructItem::Race(first, second) => self.races.push((first, second)),
+            StructItem::Screenshot(options) => self.screenshot = Some(options),
+            StructItem::Url(url) => self.url = Some(url),
+            StructItem::BeforeOpen(hook) => self.before_open = Some(hook),
+            StructItem::SelectorsFile(file) => self.selectors_file = Some(file),
+            StructItem::Geolocation(geolocation) => self.geolocation = Some(geolocation),
+            StructItem::Timezone(timezone) => self.timezone = Some(timezone),
+            StructItem::Locale(locale) => self.locale = Some(locale),
+            StructItem::RequiresCookie(name) => self.required_cookies.push(name),
+            StructItem::ExpectedUrlPattern(pattern) => self.expected_url_pattern = Some(pattern),
+            StructItem::SlowThresholdMs(threshold_ms) => {
+                self.slow_threshold_ms = Some(threshold_ms)
+            }
+            StructItem::LogSuccess(enabled) => self.log_success = Some(enabled),
+            StructItem::DryRun(enabled) => self.dry_run = Some(enabled),
+            StructItem::WaitBetweenActions(pacing) => self.wait_between_actions = Some(pacing),
+            StructItem::SessionAudit(field) => self.session_audit = Some(field),
+            StructItem::Error(error) => self.error = Some(error),
+            StructItem::Crate(_)
+            | StructItem::Anyhow(_)
+            | StructItem::Log(_)
+            | StructItem::Tokio(_)
+            | StructItem::SerdeJson(_) => self.crate_paths.apply(item),
+            StructItem::LocatorBuilder(builder) => self.locator_builder = Some(builder),
+            StructItem::Naming(naming) => self.naming = Some(naming),
+            StructItem::StructuredErrors => self.structured_errors = true,
+            StructItem::Vis(vis) => self.vis = Some(vis),
+            StructItem::TimeoutSecs(timeout_secs) => self.timeout_secs = Some(timeout_secs),
+            StructItem::PollMs(poll_ms) => self.poll_ms = Some(poll_ms),
+        }
+    }
+
+    /// Error type of generated methods, and a function turning a message `String` into one.
+    ///
+    /// With `error`, the function is the `thirtyfour_actions_error` helper generated on the struct.
+    fn error_tokens(&self) -> (TokenStream2, TokenStream2) {
+        let anyhow = &self.crate_paths.anyhow;
+        match &self.error {
+            Some(error) => (quote!(#error), quote!(Self::thirtyfour_actions_error)),
+            None => (quote!(#anyhow::Error), quote!(#anyhow::Error::msg)),
+        }
+    }
+
+    /// Function turning a message `String` and the driver error `e` into a generated error,
+    /// and the tokens passing `e` to it.
+    ///
+    /// With `structured_errors`, `e` is kept as the `ThirtyfourActionsError` under the message;
+    /// otherwise only the message is.
+    fn driver_error_tokens(&self) -> (TokenStream2, TokenStream2) {
+        if self.structured_errors {
+            let runtime = runtime_path();
+            (
+                quote!(#runtime::ThirtyfourActionsError::with_context),
+                quote!(, e),
+            )
+        } else {
+            (self.error_tokens().1, quote!())
+        }
+    }
+
+    /// Interval at which waiting methods poll, in milliseconds
+    fn poll_ms(&self) -> u64 {
+        self.poll_ms.unwrap_or(500)
+    }
+}
+
+/// Render the driver error `e` for an error message, masking `value` when the field is sensitive
+fn error_detail(sensitive: bool, value: TokenStream2) -> TokenStream2 {
+    if sensitive {
+        quote! {{
+            let detail = e.to_string();
+            if #value.is_empty() { detail } else { detail.replace(#value, "***") }
+        }}
+    } else {
+        quote!(e)
+    }
+}
+
+/// Render a value for an error message, masking it when the field is sensitive
+fn shown_value(sensitive: bool, value: TokenStream2) -> TokenStream2 {
+    if sensitive { quote!("***") } else { value }
+}
+
+/// Convert an UpperCamelCase variant name to a snake_case method name part
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (index, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if index > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Convert a snake_case field name to an UpperCamelCase variant name
+fn to_upper_camel_case(name: &str) -> String {
+    name.trim_start_matches("r#")
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Build an expression producing a base64 screenshot of `source` (an element or the driver).
+///
+/// Without screenshot options the driver's base64 PNG is passed through unchanged.
+fn screenshot_tokens(
+    struct_options: &StructOptions,
+    png_call: TokenStream2,
+    base64_call: TokenStream2,
+    field_name_str: &str,
+) -> TokenStream2 {
+    let (driver_error, driver_source) = struct_options.driver_error_tokens();
+    match struct_options.screenshot {
+        None => quote! {
+            #base64_call.await
+                .map_err(|e| #driver_error(format!("Failed to take screenshot of {}: {}", #field_name_str, e) #driver_source))
+        },
+        Some(ScreenshotOptions {
+            format,
+            quality,
+            max_width,
+        }) => {
+            let runtime = runtime_path();
+            let format = match format {
+                ScreenshotFormat::Png => quote!(#runtime::ScreenshotFormat::Png),
+                ScreenshotFormat::Jpeg => quote!(#runtime::ScreenshotFormat::Jpeg),
+                ScreenshotFormat::WebP => quote!(#runtime::ScreenshotFormat::WebP),
+            };
+            let max_width = match max_width {
+                Some(max_width) => quote!(Some(#max_width)),
+                None => quote!(None),
+            };
+            quote! {
+                #png_call.await
+                    .map_err(|e| #driver_error(format!("Failed to take screenshot of {}: {}", #field_name_str, e) #driver_source))
+                    .and_then(|png| #runtime::encode_screenshot(&png, #format, #quality, #max_width)
+                        .map_err(|e| #driver_error(format!("Failed to encode screenshot of {}: {}", #field_name_str, e) #driver_source)))
+            }
+        }
+    }
+}
+
+/// Paths generated code reaches its dependencies by, overridable when they are renamed or
+/// re-exported from a facade crate
+#[derive(Clone)]
+struct CratePaths {
+    thirtyfour: syn::Path,
+    anyhow: syn::Path,
+    log: syn::Path,
+    tokio: syn::Path,
+    serde_json: syn::Path,
+}
+
+impl Default for CratePaths {
+    fn default() -> Self {
+        CratePaths {
+            thirtyfour: syn::parse_quote!(thirtyfour),
+            anyhow: syn::parse_quote!(anyhow),
+            log: syn::parse_quote!(log),
+            tokio: syn::parse_quote!(tokio),
+            serde_json: syn::parse_quote!(serde_json),
+        }
+    }
+}
+
+impl CratePaths {
+    /// Apply a path override, ignoring other options
+    fn apply(&mut self, item: StructItem) {
+        match item {
+            StructItem::Crate(path) => self.thirtyfour = path,
+            StructItem::Anyhow(path) => self.anyhow = path,
+            StructItem::Log(path) => self.log = path,
+            StructItem::Tokio(path) => self.tokio = path,
+            StructItem::SerdeJson(path) => self.serde_json = path,
+            _ => {}
+        }
+    }
+}
+
+/// Path of the support module emitted by `thirtyfour_actions_runtime!()`
+fn runtime_path() -> TokenStream2 {
+    quote!(crate::thirtyfour_actions_runtime)
+}
+
+/// Expand the derive on an enum whose struct-like variants are the states of one page.
+///
+/// Each variant becomes a `<Enum><Variant>` struct with the variant's fields, expanded like any
+/// other page object with the enum's struct-level options, and the enum gets `detect_state`,
+/// probing each variant's anchor field, plus conversions to and from those structs.
+fn state_enum(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let syn::Data::Enum(data_enum) = &input.data else {
+        unreachable!("state_enum is only called for enums")
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "generic state enums are not supported",
+        ));
+    }
+    let enum_name = &input.ident;
+    let enum_vis = &input.vis;
+    let options: Vec<&syn::Attribute> = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("thirtyfour_actions"))
+        .collect();
+    // Options naming a field of the struct, or a single trait, cannot be shared by every state
+    // Fields may take their selector from a file, or be strings built into one
+    let (mut selectors_file, mut locator_builder) = (false, false);
+    let mut naming = MethodNaming::default();
+    let mut crate_paths = CratePaths::default();
+    for attr in &options {
+        for item in attr.parse_args_with(Punctuated::<StructItem, Comma>::parse_terminated)? {
+            let unsupported = match item {
+                StructItem::Naming(scheme) => {
+                    naming = scheme;
+                    None
+                }
+                item @ (StructItem::Crate(_)
+                | StructItem::Anyhow(_)
+                | StructItem::Log(_)
+                | StructItem::Tokio(_)
+                | StructItem::SerdeJson(_)) => {
+                    crate_paths.apply(item);
+                    None
+                }
+                StructItem::SelectorsFile(_) => {
+                    selectors_file = true;
+                    None
+                }
+                StructItem::LocatorBuilder(_) => {
+                    locator_builder = true;
+                    None
+                }
+                StructItem::Sink(field) => Some((field.span(), "sink")),
+                StructItem::Driver(field) => Some((field.span(), "driver")),
+                StructItem::SoftAssert(field) => Some((field.span(), "soft_assert")),
+                StructItem::WaitBetweenActions(pacing) => {
+                    Some((pacing.field.span(), "wait_between_actions"))
+                }
+                StructItem::SessionAudit(field) => Some((field.span(), "session_audit")),
+                StructItem::AsTrait(name) => Some((name.span(), "as_trait")),
+                _ => None,
+            };
+            if let Some((span, option)) = unsupported {
+                return Err(syn::Error::new(
+                    span,
+                    format!("{} is not supported on state enums", option),
+                ));
+            }
+        }
+    }
+
+    let mut expanded = TokenStream2::new();
+    let mut probes = Vec::new();
+    let mut conversions = Vec::new();
+    for variant in &data_enum.variants {
+        let variant_ident = &variant.ident;
+        let syn::Fields::Named(fields) = &variant.fields else {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "state enum variants must have named fields",
+            ));
+        };
+        let field_idents: Vec<&Ident> = fields
+            .named
+            .iter()
+            .map(|field| field.ident.as_ref().expect("named field"))
+            .collect();
+
+        let mut anchor = None;
+        for attr in &variant.attrs {
+            if attr.path().is_ident("thirtyfour_actions") {
+                attr.parse_args_with(|input: ParseStream| {
+                    let key: Ident = input.parse()?;
+                    if key != "anchor" {
+                        return Err(syn::Error::new(
+                            key.span(),
+                            format!("unknown state enum variant option '{}'", key),
+                        ));
+                    }
+                    let field: Ident = parse_str_value(input)?.parse()?;
+                    if !field_idents.contains(&&field) {
+                        return Err(syn::Error::new(
+                            field.span(),
+                            format!("anchor field '{}' not found in variant", field),
+                        ));
+                    }
+                    anchor = Some(field);
+                    Ok(())
+                })?;
+            }
+        }
+        let anchor = match anchor.or_else(|| field_idents.first().map(|&field| field.clone())) {
+            Some(anchor) => anchor,
+            None => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "state enum variants need a field to detect them by",
+                ));
+            }
+        };
+        // `detect_state` builds each state with `new()` and probes its anchor with
+        // `query_<anchor>_with_timeout`, so every field needs its own selector and the anchor's
+        // query must take no further arguments; malformed options are left for `derive_struct`
+        let mut anchor_name = anchor.unraw().to_string();
+        for field in &fields.named {
+            let field_ident = field.ident.as_ref().expect("named field");
+            let mut field_options = FieldOptions::default();
+            for attr in &field.attrs {
+                if attr.path().is_ident("thirtyfour_actions")
+                    && let Ok(items) =
+                        attr.parse_args_with(Punctuated::<FieldItem, Comma>::parse_terminated)
+                {
+                    items.into_iter().for_each(|item| field_options.apply(item));
+                }
+            }
+            let is_anchor = field_ident == &anchor;
+            if is_anchor && let Some(name) = &field_options.field_name {
+                anchor_name = name.unraw().to_string();
+            }
+            let built = locator_builder && is_locator_string(&field.ty);
+            let passed_in = if field_options.skip {
+                Some("is `skip`")
+            } else if field_options.component {
+                Some("is a `component`")
+            } else if field_options.resolver {
+                Some("is a `resolver`")
+            } else if is_stored_element(&field.ty) {
+                Some("holds a stored element")
+            } else if is_selector_map(&field.ty) {
+                Some("is keyed")
+            } else if built && field_options.locator.is_none() {
+                Some("is built by `locator_builder` without a `locator`")
+            } else if field_options.selector.is_none() && !selectors_file && !built {
+                Some("has no selector")
+            } else {
+                None
+            };
+            if let Some(reason) = passed_in {
+                return Err(syn::Error::new(
+                    field_ident.span(),
+                    format!(
+                        "field '{}' of state '{}' {}, so `detect_state` cannot build the state with `new()`",
+                        field_ident, variant_ident, reason
+                    ),
+                ));
+            }
+            if is_anchor
+                && field_options
+                    .selector
+                    .as_ref()
+                    .is_some_and(|selector| !selector.placeholders().is_empty())
+            {
+                return Err(syn::Error::new(
+                    field_ident.span(),
+                    format!(
+                        "anchor field '{}' of state '{}' has a templated selector, so `detect_state` cannot probe it; pick another `anchor`",
+                        field_ident, variant_ident
+                    ),
+                ));
+            }
+        }
+
+        let state_struct = syn::Ident::new(
+            &format!("{}{}", enum_name, variant_ident),
+            variant_ident.span(),
+        );
+        let derived: TokenStream2 = derive_struct(syn::parse_quote! {
+            #(#options)*
+            #enum_vis struct #state_struct #fields
+        });
+        // The struct itself is declared without the field options only the derive understands
+        let mut plain_fields = fields.clone();
+        for field in plain_fields.named.iter_mut() {
+            field
+                .attrs
+                .retain(|attr| !attr.path().is_ident("thirtyfour_actions"));
+        }
+        let struct_doc = format!(
+            " The `{}` state of [`{}`], with its locators and generated actions.",
+            variant_ident, enum_name
+        );
+        expanded.extend(quote! {
+            #[doc = #struct_doc]
+            #enum_vis struct #state_struct #plain_fields
+
+            #derived
+
+            impl From<#state_struct> for #enum_name {
+                fn from(state: #state_struct) -> Self {
+                    let #state_struct { #(#field_idents),* } = state;
+                    Self::#variant_ident { #(#field_idents),* }
+                }
+            }
+        });
+
+        let query_anchor = syn::Ident::new(
+            &naming.method_name("query", &anchor_name, "_with_timeout"),
+            anchor.span(),
+        );
+        probes.push(quote! {
+            let state = #state_struct::new();
+            if state.#query_anchor(driver, 0).await.is_some() {
+                return Some(Self::from(state));
+            }
+        });
+        let into_fn = syn::Ident::new(
+            &format!("into_{}", to_snake_case(&variant_ident.to_string())),
+            variant_ident.span(),
+        );
+        let into_doc = format!(
+            " The fields of the `{}` state as a [`{}`], to run its actions, or `None` in another state.",
+            variant_ident, state_struct
+        );
+        conversions.push(quote! {
+            #[doc = #into_doc]
+            pub fn #into_fn(self) -> Option<#state_struct> {
+                match self {
+                    Self::#variant_ident { #(#field_idents),* } => Some(#state_struct { #(#field_idents),* }),
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            }
+        });
+    }
+
+    let thirtyfour = &crate_paths.thirtyfour;
+    expanded.extend(quote! {
+        impl #enum_name {
+            /// Find which state the page is in by probing the anchor element of each state, in
+            /// declaration order, without waiting.
+            ///
+            /// Returns the first state whose anchor is present, built with its selectors, or `None`
+            /// if no anchor is.
+            pub async fn detect_state(driver: &#thirtyfour::WebDriver) -> Option<Self> {
+                #(#probes)*
+                None
+            }
+
+            #(#conversions)*
+        }
+    });
+    Ok(expanded)
+}
+
+/// Expand the reduced page objects of a struct's field `group`s: a `<Struct><Group>` struct per
+/// group with only its fields and their generated methods, and a `<group>()` method on the struct
+/// copying the fields into it.
+///
+/// The group structs are expanded with the struct's options shaping generated methods; options
+/// naming a field outside the group, `as_trait` and the page-level `open`, browser context,
+/// precondition, `storage` and `alerts` options stay with the struct.
+fn group_structs(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let syn::Data::Struct(data_struct) = &input.data else {
+        return Ok(TokenStream2::new());
+    };
+    // Fields by group label, in order of first appearance; malformed options are left for
+    // `derive_struct` to report
+    let mut groups: Vec<(syn::LitStr, Vec<&syn::Field>)> = Vec::new();
+    for field in &data_struct.fields {
+        let labels = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("thirtyfour_actions"))
+            .filter_map(|attr| {
+                attr.parse_args_with(Punctuated::<FieldItem, Comma>::parse_terminated)
+                    .ok()
+            })
+            .flatten()
+            .filter_map(|item| match item {
+                FieldItem::Group(label) => Some(label),
+                _ => None,
+            });
+        for label in labels {
+            if field.ident.is_none() {
+                return Err(syn::Error::new(
+                    label.span(),
+                    "`group` needs named fields, not a tuple struct",
+                ));
+            }
+            match groups
+                .iter_mut()
+                .find(|(other, _)| other.value() == label.value())
+            {
+                Some((_, fields)) => fields.push(field),
+                None => groups.push((label, vec![field])),
+            }
+        }
+    }
+    if groups.is_empty() {
+        return Ok(TokenStream2::new());
+    }
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`group` is not supported on generic structs",
+        ));
+    }
+
+    let struct_name = &input.ident;
+    let struct_vis = &input.vis;
+    let mut expanded = TokenStream2::new();
+    let mut accessors = Vec::new();
+    for (label, fields) in &groups {
+        let name = label.value();
+        if name != to_snake_case(&name) || syn::parse_str::<Ident>(&name).is_err() {
+            return Err(syn::Error::new(
+                label.span(),
+                "group labels must be snake_case names, e.g. \"header\"",
+            ));
+        }
+        let group_fn = Ident::new(&name, label.span());
+        let field_idents: Vec<&Ident> = fields
+            .iter()
+            .map(|field| field.ident.as_ref().expect("named field"))
+            .collect();
+        let field_names: Vec<String> = field_idents
+            .iter()
+            .map(|field| field.unraw().to_string())
+            .collect();
+        let in_group = |field: &Ident| field_names.contains(&field.unraw().to_string());
+
+        // Struct options with their tokens, to forward them as written
+        let mut items = Vec::new();
+        for attr in &input.attrs {
+            if !attr.path().is_ident("thirtyfour_actions") {
+                continue;
+            }
+            let syn::Meta::List(list) = &attr.meta else {
+                continue;
+            };
+            let mut tokens: Vec<TokenStream2> = vec![TokenStream2::new()];
+            for token in list.tokens.clone() {
+                match &token {
+                    proc_macro2::TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                        tokens.push(TokenStream2::new());
+                    }
+                    _ => tokens.last_mut().expect("one item").extend([token]),
+                }
+            }
+            for tokens in tokens.into_iter().filter(|tokens| !tokens.is_empty()) {
+                items.push((syn::parse2::<StructItem>(tokens.clone())?, tokens));
+            }
+        }
+        let soft_assert_in_group = items
+            .iter()
+            .any(|(item, _)| matches!(item, StructItem::SoftAssert(field) if in_group(field)));
+
+        // Options shaping the generated methods carry over; those naming a field outside the
+        // group, a single trait, or the page itself (its address, browser context and
+        // preconditions, storage and alerts) stay with the struct
+        let mut options = Vec::new();
+        let mut accessor_vis: syn::Visibility = syn::parse_quote!(pub);
+        for (item, tokens) in items {
+            let kept = match item {
+                StructItem::Sink(field)
+                | StructItem::Driver(field)
+                | StructItem::SoftAssert(field)
+                | StructItem::SessionAudit(field) => in_group(&field),
+                StructItem::WaitBetweenActions(pacing) => in_group(&pacing.field),
+                StructItem::Race(first, second) => in_group(&first) && in_group(&second),
+                StructItem::SoftMethods(_) => soft_assert_in_group,
+                StructItem::Vis(vis) => {
+                    accessor_vis = vis;
+                    true
+                }
+                StructItem::SelectorsFile(file) => {
+                    return Err(syn::Error::new(
+                        file.span(),
+                        "`selectors_file` cannot be combined with field `group`s",
+                    ));
+                }
+                StructItem::AsTrait(_)
+                | StructItem::Url(_)
+                | StructItem::BeforeOpen(_)
+                | StructItem::Geolocation(_)
+                | StructItem::Timezone(_)
+                | StructItem::Locale(_)
+                | StructItem::RequiresCookie(_)
+                | StructItem::ExpectedUrlPattern(_)
+                | StructItem::Storage
+                | StructItem::Alerts => false,
+                StructItem::Global(_)
+                | StructItem::RateLimit(_)
+                | StructItem::SessionLock
+                | StructItem::FieldEnum
+                | StructItem::Perform
+                | StructItem::FromSelectors
+                | StructItem::ReturnElement
+                | StructItem::Display
+                | StructItem::Screenshot(_)
+                | StructItem::SlowThresholdMs(_)
+                | StructItem::LogSuccess(_)
+                | StructItem::DryRun(_)
+                | StructItem::HeadlessAware(_)
+                | StructItem::HoverDwellMs(_)
+                | StructItem::LocatorBuilder(_)
+                | StructItem::Error(_)
+                | StructItem::Crate(_)
+                | StructItem::Anyhow(_)
+                | StructItem::Log(_)
+                | StructItem::Tokio(_)
+                | StructItem::SerdeJson(_)
+                | StructItem::Naming(_)
+                | StructItem::StructuredErrors
+                | StructItem::TimeoutSecs(_)
+                | StructItem::PollMs(_)
+                | StructItem::Broadcast
+                | StructItem::Strict => true,
+            };
+            if kept {
+                options.push(tokens);
+            }
+        }
+
+        let group_struct = syn::Ident::new(
+            &format!("{}{}", struct_name, to_upper_camel_case(&label.value())),
+            label.span(),
+        );
+        let derived: TokenStream2 = derive_struct(syn::parse_quote! {
+            #[thirtyfour_actions(#(#options),*)]
+            #struct_vis struct #group_struct {
+                #(#fields),*
+            }
+        });
+        // The struct itself is declared without the field options only the derive understands
+        let plain_fields = fields.iter().map(|field| {
+            let mut field = (*field).clone();
+            field
+                .attrs
+                .retain(|attr| !attr.path().is_ident("thirtyfour_actions"));
+            field
+        });
+        let struct_doc = format!(
+            " The `{}` fields of [`{}`], with their locators and generated actions.",
+            label.value(),
+            struct_name
+        );
+        expanded.extend(quote! {
+            #[doc = #struct_doc]
+            #struct_vis struct #group_struct {
+                #(#plain_fields),*
+            }
+
+            #derived
+        });
+
+        let accessor_doc = format!(
+            " Copy the `{}` fields into a [`{}`], to pass them on without the rest of the page.",
+            label.value(),
+            group_struct
+        );
+        accessors.push(quote! {
+            #[doc = #accessor_doc]
+            #accessor_vis fn #group_fn(&self) -> #group_struct {
+                #group_struct {
+                    #(#field_idents: Clone::clone(&self.#field_idents)),*
+                }
+            }
+        });
+    }
+
+    expanded.extend(quote! {
+        impl #struct_name {
+            #(#accessors)*
+        }
+    });
+    Ok(expanded)
+}
+
+/// The custom derive macro automatically generates asynchronous helper methods for web elements.
+///
+/// For every field in the struct, it always generates a base query method named:
+///     query_<field>(&self, driver: &thirtyfour::WebDriver)
+///
+/// Global methods can be specified at the struct level:
+///     #[thirtyfour_actions(global(click, is_displayed))]
+///
+/// Field-specific methods can be added:
+///     #[thirtyfour_actions(methods(enter_keys, clear))]
+///
+/// Global methods are applied to ALL fields, and can be combined with field-specific methods.
+/// `default_methods(...)` is an alias for `global(...)`; mark a field `no_defaults` to replace
+/// them with its own `methods(...)`.
+///
+/// `all` expands to every supported action, and `all_except(...)` to all but the listed ones:
+///     #[thirtyfour_actions(all_except(drag_to, draw_path))]
+///
+/// Add `unique` (or `strict`) to a field to treat a selector matching more than one element as
+/// not found:
+///     #[thirtyfour_actions(unique, methods(click))]
+///
+/// Unique fields also get `try_query_<field>`, returning `Ok(None)` when nothing matches and an
+/// error naming the match count when several do; their actions fail with that error too. Put
+/// `strict` on the struct to make every field unique, except those using `nth` or `fallbacks`:
+///     #[thirtyfour_actions(strict)]
+///
+/// Add `no_query` to a field to keep its `query_<field>` method private.
+///
+/// Generated methods and constants are `pub` by default. `vis` on the struct sets another
+/// visibility for all of them, e.g. to keep them out of a test crate's public API, and `vis` on a
+/// field overrides it for that field's methods:
+///     #[thirtyfour_actions(vis = "pub(crate)")]
+///
+/// In lists of identical elements, `nth` picks the match at a zero-based index instead of the
+/// first one, for the field's query, actions and waits:
+///     #[thirtyfour_actions(nth = 2, methods(click))]
+///
+/// Give a field an `alias` to also generate each of its public methods under the alias name,
+/// delegating to the original, e.g. `click_primary_cta` next to `click_submit` while callers
+/// migrate to a new name:
+///     #[thirtyfour_actions(alias = "primary_cta", methods(click))]
+///
+/// Generic structs keep their parameters, lifetimes and where-clauses on every generated impl.
+/// Tuple structs are supported too: their fields are named `field_0`, `field_1`, ... in generated
+/// methods (`query_field_0`, `click_field_0`), or by their `field_name`:
+///     struct SubmitButton(#[thirtyfour_actions(field_name = "button", css = "button", methods(click))] By);
+///
+/// Two fields generating a method of the same name, e.g. through an `alias`, are a compile error
+/// pointing at both. `rename` (a synonym of `field_name`) gives a field another name in its
+/// generated methods, by which other options then refer to it too:
+///     #[thirtyfour_actions(rename = "login_button", css = "#login", methods(click))]
+///
+/// Per-field methods are named `<verb>_<field>` by default. `naming = "field_verb"` puts the field
+/// first (`username_click`, `username_query`), grouping each element's methods together, and a
+/// template with one `{verb}` and one `{field}` sets any other prefix or suffix; the `_once`,
+/// `_default` and `_with_timeout` variants keep their suffix last:
+///     #[thirtyfour_actions(naming = "ui_{field}_{verb}")]
+///
+/// Fields named with raw identifiers drop the `r#` in generated names, so `r#type` gets
+/// `query_type` and `click_type` and is reported as `type`; selector placeholders named after
+/// keywords, such as `{type}`, become raw parameters (`r#type: &str`).
+///
+/// Give a field `expect_count` to have the generated `validate_counts` check how many elements
+/// its selector matches, as an exact number or a range, e.g. to catch selectors that started
+/// matching several elements after a frontend change:
+///     #[thirtyfour_actions(css = ".cart-total", expect_count = 1)]
+///     #[thirtyfour_actions(css = ".cart-row", expect_count = 1..)]
+///
+/// Mark fields that are often absent by design (banners, toasts) `optional`: their actions then
+/// return `Ok(false)` or `Ok(None)` instead of an error when the element is not found, with
+/// `Option<T>` replacing any other return type `T`:
+///     #[thirtyfour_actions(optional, methods(get_text, click))]
+///
+/// Set `cfg_feature` on fields that only exist in some product builds to compile their actions
+/// only when the consuming crate enables that cargo feature; `query_<field>` is always generated:
+///     #[thirtyfour_actions(cfg_feature = "admin-ui", methods(click))]
+///
+/// Mark fields that are not locators (base URLs, flags) `skip` to leave them out entirely:
+///     #[thirtyfour_actions(skip)]
+///
+/// Page objects compose: mark a field whose type also derives `ImplThirtyfourActions` as a
+/// `component`, and its actions are called through the field, e.g. `page.header.click_logout(&driver)`:
+///     #[thirtyfour_actions(component)]
+/// A component declares the element it lives in with a `root` field. Every other field of the
+/// struct is then searched within that element rather than the whole document, so components
+/// repeated on a page need no globally unique selectors:
+///     #[thirtyfour_actions(root, css = "header.site-header")]
+/// A single field can be scoped under a sibling instead with `within`, so its `query_<field>`
+/// first finds the sibling's element and then searches inside it:
+///     #[thirtyfour_actions(within = "cart_row", css = "button.remove")]
+/// Fields inside a web component are reached with `shadow_root`, which searches the shadow root
+/// of a host field (WebDriver supports only CSS selectors there). Hosts can themselves be inside another host's shadow root, so nested
+/// components are reached one `shadow_root` hop at a time:
+///     #[thirtyfour_actions(shadow_root = "date_picker", css = "button.today")]
+///
+/// Fields can declare their selector inline with `css`, `xpath`, `id` or `name`, which
+/// generates a `<FIELD>_SELECTOR_DESC` constant describing it:
+///     #[thirtyfour_actions(css = "#login > button.submit")]
+/// CSS and XPath selectors are checked at compile time, so a typo like `div[data-id='x'` or
+/// `//li[@class='item'` fails the build.
+/// `{placeholder}`s in an inline selector make it a template: the field's queries and actions
+/// take one `&str` per placeholder after `driver`, filled into the selector as given, and
+/// `locator_<field>` builds the filled-in `By`:
+///     #[thirtyfour_actions(xpath = "//tr[@data-id='{id}']", methods(click))]
+///     row_by_id: By,
+/// generates `query_row_by_id(&driver, id)`, `click_row_by_id(&driver, id)` and so on. Templated
+/// fields are left out of the field enum and cannot be scopes, markers or have a `role`.
+/// A field typed `HashMap<String, By>` (or `BTreeMap`) holds one selector per key instead; its
+/// methods take the `key` after `driver` like a placeholder and fail naming the known keys when
+/// the map has no entry for it. The map is passed to `new()`:
+///     #[thirtyfour_actions(methods(click))]
+///     tabs: HashMap<String, By>,
+/// generates `query_tabs(&driver, "billing")`, `click_tabs(&driver, "billing")` and so on.
+/// To keep selectors out of Rust source, name a TOML file (relative to the crate's manifest
+/// directory) with `selectors_file`; it is read at compile time and fills in the selector of every
+/// field that declares none, so missing, unknown and malformed entries fail the build:
+///     #[thirtyfour_actions(selectors_file = "selectors/login.toml")]
+/// with entries written like the inline options:
+///     username = { css = "input[name='user']" }
+///     submit.xpath = "//button[@type='submit']"
+/// Selectors that differ between UI versions can be listed in `fallbacks`, tried in order when
+/// the field's own selector matches nothing; `query_<field>` logs which fallback matched, and
+/// waits accept any of them:
+///     #[thirtyfour_actions(css = "#checkout", fallbacks(css = ".legacy-checkout", xpath = "//button[text()='Pay']"))]
+/// `with_text` and `with_attribute` narrow a field's matches to elements with that text or
+/// attribute value, instead of spelling the condition out in XPath:
+///     #[thirtyfour_actions(css = "button", with_text = "Submit", with_attribute("data-state", "ready"))]
+/// When every locator field declares one, `new()` builds the struct from them, taking the
+/// sink, driver, `skip`, `component`, `resolver`, `WebElement` and selector map fields as
+/// parameters, and `Default` is implemented when there are none.
+///
+/// Locator fields may also be typed `Arc<By>` or `Cow<'static, By>`, so clones of a page object
+/// share one set of selectors:
+///     submit: std::sync::Arc<thirtyfour::By>,
+///
+/// Every struct gets `selector_map()`, mapping the name of each locator field to its `By`, for
+/// reporting and selector-audit tooling.
+///
+/// Give a field with `click` or `submit` a `done_when` marker field or `done_when_url` text to
+/// generate `click_<field>_once`/`submit_<field>_once`, which do nothing when the action already
+/// took effect, so retrying them never submits a form twice:
+///     #[thirtyfour_actions(done_when = "confirmation", methods(click))]
+///
+/// `wait_for_no_overlap` scrolls the element to the middle of the viewport and waits until nothing
+/// covers its center point (sticky headers, toasts, chat widgets), as a precondition for clicks
+/// near them:
+///     #[thirtyfour_actions(methods(wait_for_no_overlap, click))]
+///
+/// `get_element_state` generates `get_element_state_<field>(driver)`, returning whether the
+/// element exists, is displayed, enabled and selected, its rect and the start of its text as one
+/// `ElementState` (requires `thirtyfour_actions_runtime!()`):
+///     #[thirtyfour_actions(methods(get_element_state))]
+///
+/// `dump_subtree` generates `dump_subtree_<field>(driver, max_depth)`, returning the element's
+/// descendants as an indented tree of tags, ids, classes and text snippets for debugging:
+///     #[thirtyfour_actions(methods(dump_subtree))]
+///
+/// Set `disabled_reason` to the attribute explaining why a field is disabled (`title`,
+/// `data-disabled-reason`, ...) to include it when `wait_until_clickable` or
+/// `wait_for_enabled_then_click` fails on a disabled element:
+///     #[thirtyfour_actions(disabled_reason = "data-disabled-reason", methods(wait_for_enabled_then_click))]
+///
+/// JavaScript dropdown widgets (select2 and the like) ignore `select_by_*`; `custom_dropdown`
+/// generates `pick_<field>(driver, text)` instead, which clicks the widget, types `text` into
+/// the search box and clicks the matching option. The search box and options are looked up in
+/// the whole document and default to select2 and ARIA combobox markup; override them with
+/// `dropdown_search` and `dropdown_option`:
+///     #[thirtyfour_actions(dropdown_search = ".search input", dropdown_option = "li.item", methods(custom_dropdown))]
+///
+/// Fields can be given a semantic role:
+///     #[thirtyfour_actions(role = "error_message")]
+/// Fields marked `error_message` generate `collect_errors`, and together with a field marked
+/// `submit` also `submit_and_collect_errors`. Fields marked `load_marker` generate
+/// `wait_until_ready`, and fields marked `overlay` generate `dismiss_overlays`.
+///
+/// Mark credential fields `sensitive` so values typed or selected through them are masked
+/// as `***` in error messages and action records:
+///     #[thirtyfour_actions(sensitive, methods(enter_keys))]
+///
+/// Fields of thirtyfour's component types (`ElementResolver<WebElement>` or
+/// `ElementResolver<SomeComponent>`) can be marked `resolver` to locate them through the
+/// resolver instead of a `By`, so one model serves both thirtyfour components and this macro.
+/// Their actions and waits act on the resolved component's base element, `resolve_<field>`
+/// returns the component itself, and `new()` takes them as parameters:
+///     #[thirtyfour_actions(resolver, methods(click, wait_for))]
+///     submit: ElementResolver<WebElement>,
+///
+/// A field typed `WebElement` (or `Arc<WebElement>`) caches an element located once: its
+/// query returns the stored element after checking it is not stale, instead of searching the
+/// page, and `new()` takes it as a parameter:
+///     #[thirtyfour_actions(methods(click, get_text))]
+///     row: WebElement,
+///
+/// With `locator_builder`, fields typed `String` (or `&str`) hold a locator string that the named
+/// `fn(&str) -> By` turns into the field's `By` each time it is queried, e.g. a test id. Their
+/// initial value comes from `locator = "..."`, or `new()` takes it as a parameter:
+///     #[thirtyfour_actions(locator_builder = "crate::locators::by_testid")]
+///     #[thirtyfour_actions(locator = "login-submit", methods(click))]
+///     submit: String,
+///
+/// Action calls can be reported to an `ActionSink` stored in a field of the struct
+/// (requires `thirtyfour_actions_runtime!()`):
+///     #[thirtyfour_actions(sink = "audit")]
+///
+/// Name a field holding a `SoftAssertions` (or an `Arc` of one) with `soft_assert` to have failing
+/// `assert_*` actions record their error there and return `Ok(())`, so one run reports every
+/// failed check; `finish()` on the collector then fails with all of them. `soft_methods` adds
+/// other actions returning `Result<()>` (requires `thirtyfour_actions_runtime!()`):
+///     #[thirtyfour_actions(soft_assert = "checks", soft_methods(scroll_to))]
+///
+/// Name a field holding the `WebDriver` (or an `Arc<WebDriver>`) with `driver` so generated
+/// methods take only `&self` instead of a `driver` argument on every call:
+///     #[thirtyfour_actions(driver = "driver")]
+///
+/// Actions can be throttled per struct (requires `thirtyfour_actions_runtime!()`); the limit
+/// can be changed at runtime through the generated `rate_limiter()`:
+///     #[thirtyfour_actions(rate_limit = 5)]
+///
+/// Add `session_lock` to serialize actions from tasks sharing one WebDriver session
+/// (requires `thirtyfour_actions_runtime!()`):
+///     #[thirtyfour_actions(session_lock)]
+///
+/// `hover_dwell_ms` makes `hover_*` actions wait after moving to their element, so menus and
+/// tooltips open as they would for a user:
+///     #[thirtyfour_actions(hover_dwell_ms = 400)]
+///
+/// Add `headless_aware` to keep one page object for local and CI runs: while the generated
+/// `headless()` switch is on, `wait_between_actions` pacing, `rate_limit` throttling and hover
+/// dwell are skipped. It starts on when the `CI` environment variable is set (other than to
+/// `false` or `0`); `headless_aware = true` or `false` forces the initial state, `set_enabled`
+/// changes it and `detect(&driver)` also turns it on for a headless browser user agent (requires
+/// `thirtyfour_actions_runtime!()`):
+///     #[thirtyfour_actions(headless_aware, rate_limit = 5, hover_dwell_ms = 400)]
+///     Page::headless().detect(&driver).await;
+///
+/// Set `slow_threshold_ms` to log a warning naming the action, field and duration whenever a
+/// generated method takes longer than the threshold:
+///     #[thirtyfour_actions(slow_threshold_ms = 2000)]
+///
+/// Add `log_success` to also log every successful action at debug level with its field and
+/// duration, so the last step that worked is known when triaging a long flow. The generated
+/// `success_logging()` flag turns the logs on and off at runtime; `log_success = false` starts
+/// with them off:
+///     #[thirtyfour_actions(log_success)]
+///     Page::success_logging().store(false, Ordering::Relaxed);
+///
+/// Add `dry_run` to validate long flows' selectors against a new environment without mutating
+/// data: while the generated `dry_run()` switch is on, interactions (`click`, `enter_keys`,
+/// `select_by_*`, ...) fail if their element is missing, hidden or disabled and otherwise skip
+/// the interaction, recording it instead; `take_actions()` returns what would have been done.
+/// `dry_run = true` starts with the switch on (requires `thirtyfour_actions_runtime!()`):
+///     #[thirtyfour_actions(dry_run)]
+///     Page::dry_run().set_enabled(true);
+///
+/// `wait_between_actions` keeps at least `ms` milliseconds between the end of one generated
+/// action and the start of the next on the same instance, for pages that debounce input. The
+/// named field holds an `ActionPacer` (or an `Arc` of one) remembering when the last action
+/// finished; `new` creates it (requires `thirtyfour_actions_runtime!()`):
+///     #[thirtyfour_actions(wait_between_actions(ms = 300, field = "pacer"))]
+///     pacer: ActionPacer,
+///
+/// To track down driver handles mixed up between parallel tests, `session_audit` names a field
+/// holding a `SessionAudit` that records the session of the instance's first action, or of the
+/// generated `bind_session`. Every generated method returning a `Result` then fails, naming both
+/// session IDs, when called with another session (requires `thirtyfour_actions_runtime!()`):
+///     #[thirtyfour_actions(session_audit = "session")]
+///     session: SessionAudit,
+///
+/// Set `error` to have generated methods return `Result<_, E>` instead of `anyhow::Result`.
+/// `E` must implement `From<thirtyfour::error::WebDriverError>` (and `Display` with `sink` or `soft_assert`); errors
+/// raised by the generated code itself are converted from a `WebDriverError::CustomError`
+/// carrying the message, through a private `thirtyfour_actions_error` function on the struct:
+///     #[thirtyfour_actions(error = "crate::UiError")]
+///
+/// Add `structured_errors` to make every generated error carry a `ThirtyfourActionsError` (`NotFound`,
+/// `Timeout`, `Stale`, `DriverError`, ...) that can be matched with `anyhow::Error::downcast_ref`,
+/// keeping the usual message as context (requires `thirtyfour_actions_runtime!()`):
+///     #[thirtyfour_actions(structured_errors)]
+///
+/// Generated code names its dependencies by their crate names, so the crate using the derive
+/// must depend on `thirtyfour`, `anyhow`, `log` and `tokio` (with its `time`, `macros`, `sync`
+/// and `rt` features), and on `serde_json` for `get_attributes_all`, `geolocation`, `timezone`
+/// and `locale`. The `regex`, `reqwest`, `image`, `serde` and `tracing` features of this crate
+/// generate code calling the crate of the same name, which must then be a dependency too.
+/// When `thirtyfour`, `anyhow`, `log`, `tokio` or `serde_json` are renamed in `Cargo.toml` or
+/// re-exported from a facade crate, point generated code at them with `crate`, `anyhow`, `log`,
+/// `tokio` and `serde_json`, and give the same overrides to `thirtyfour_actions_runtime!(...)`:
+///     #[thirtyfour_actions(crate = "::my_facade::thirtyfour", anyhow = "::my_facade::anyhow")]
+///     #[thirtyfour_actions(tokio = "::my_facade::tokio", serde_json = "::my_facade::serde_json")]
+///
+/// With the `tracing` feature, query failures and slow-action warnings are emitted as `tracing`
+/// events with `element`, `selector` and `action` fields instead of going through `log`.
+///
+/// Set `timeout_secs` to give every method taking a `timeout_secs` argument (`wait_for_*`,
+/// `wait_until_clickable_*`, `wait_until_ready`, ...) a `<method>_default` variant without it,
+/// and `poll_ms` to change how often waiting methods poll (500 ms by default):
+///     #[thirtyfour_actions(timeout_secs = 15, poll_ms = 250)]
+/// A field-level `timeout_secs` overrides the default for that field's `_default` waits, and
+/// makes its queries (and so its actions) wait that long for the element to appear:
+///     #[thirtyfour_actions(timeout_secs = 60, methods(wait_for, click))]
+/// Every query and every action without a `timeout_secs` argument also gets a
+/// `<method>_with_timeout` variant taking one, which waits that long for the element instead,
+/// e.g. `click_submit_with_timeout(&driver, 90)` for a single slow step.
+///
+/// Add `field_enum` to generate a `<Struct>Field` enum naming every field, a `locator` accessor,
+/// the `wait_for_any`/`wait_for_all` combinators that take a list of fields, and `get_texts_of`,
+/// which reads the text of several fields concurrently. They query each field through its
+/// `query_<field>` methods, as its actions do, so scopes, fallbacks, filters and `nth` apply:
+///     #[thirtyfour_actions(field_enum)]
+/// It also generates `resolve_all`, which queries every field at once and returns the elements
+/// found. Mark fields that are expensive to query (slow XPath, content below the fold) `lazy` to
+/// leave them out, and call `resolve_all_include_lazy` to query them too:
+///     #[thirtyfour_actions(lazy, xpath = "//table//tr[last()]")]
+///
+/// Declare outcome pairs with `race(a, b)` to generate `race_<a>_vs_<b>`, which waits for
+/// whichever field appears first and returns a `<Struct><A>Or<B>` enum saying which one it was:
+///     #[thirtyfour_actions(race(success_toast, error_banner))]
+///
+/// Add `perform` (which implies `field_enum`) to generate `perform(driver, field, action)`,
+/// running a field's zero-argument action by name and returning its value as an `ActionOutput`
+/// (requires `thirtyfour_actions_runtime!()`); action sinks receive the same type in
+/// `ActionRecord::output`. With the `serde` feature `ActionOutput` is serializable:
+///     #[thirtyfour_actions(perform)]
+///
+/// Add `from_selectors` to generate `from_selectors(config, ...)`, which builds the struct from a
+/// `SelectorConfig` mapping field names to selectors, taking the same parameters as `new()` after
+/// it (requires `thirtyfour_actions_runtime!()`). With the `serde` feature the config deserializes
+/// from files such as `login_button: { css: "#login" }` in YAML:
+///     #[thirtyfour_actions(from_selectors)]
+///
+/// Waits the driver cannot do itself (`wait_for_attribute_to_contain`, `wait_for_attribute_to_match`,
+/// `wait_for_any`, races, ...) poll with `tokio::time::sleep`, as does `rate_limit`, so generated
+/// methods must run on a Tokio runtime with its timer enabled, e.g. under `#[tokio::main]` or
+/// `#[tokio::test]`.
+///
+/// With the `image` feature, screenshot actions can re-encode and downscale their output
+/// (requires `thirtyfour_actions_runtime!()`):
+///     #[thirtyfour_actions(screenshot(format = "jpeg", quality = 70, max_width = 1280))]
+///
+/// Give the struct a `url` to generate `open`, which navigates to it. Geolocation, timezone and
+/// locale overrides generate `apply_context` (Chromium only, via CDP), which `open` applies first:
+///     #[thirtyfour_actions(url = "https://example.com/store", geolocation(lat = 52.52, lon = 13.40), timezone = "Europe/Berlin", locale = "de-DE")]
+///
+/// The `url` may contain `{placeholder}`s, filled from the environment variable named after each
+/// placeholder in upper case, or from the parameters of the generated `open_with`:
+///     #[thirtyfour_actions(url = "{base}/login")]
+///
+/// `before_open` names an async function `open` awaits before navigating, taking the page
+/// object and the driver and returning a `Result` whose error converts into the error type of
+/// the generated methods, so a page object can seed test data or set up auth itself. It may be a method of the struct:
+///     #[thirtyfour_actions(url = "https://example.com/orders", before_open = "Self::seed_orders")]
+///     async fn seed_orders(&self, driver: &WebDriver) -> anyhow::Result<()>
+///
+/// Each `requires_cookie` generates a check in `ensure_preconditions`, which sets the cookie from
+/// a provided value or fails fast when it is missing from the session:
+///     #[thirtyfour_actions(requires_cookie = "session", requires_cookie = "csrf_token")]
+///
+/// With the `regex` feature, `expected_url_pattern` generates `assert_on_page`, which `open` and
+/// `wait_until_ready` run to catch unexpected redirects:
+///     #[thirtyfour_actions(expected_url_pattern = "^https://example\\.com/account")]
+///
+/// With the `debug-tools` feature, every struct also gets `debug_menu`, which prints its fields
+/// and lets you trigger zero-argument actions by name from stdin during a paused, headed run.
+///
+/// Add `display` to implement `Display`, listing each field's selector and enabled actions along
+/// with the struct's configuration, e.g. for logging which page objects a run used:
+///     #[thirtyfour_actions(display)]
+///
+/// Add `return_element` to have interactions (`click`, `enter_keys`, `select_by_*`, ...) return
+/// the `WebElement` they acted on, so further thirtyfour calls need no second query:
+///     #[thirtyfour_actions(return_element)]
+///
+/// Add `broadcast` to generate `broadcast`, which runs a closure calling any generated action on
+/// a slice of drivers concurrently and returns the result of each, for simple multi-user and load
+/// scenarios:
+///     #[thirtyfour_actions(broadcast)]
+///
+/// Add `as_trait` to also generate a trait declaring every public `&self` method, implemented
+/// for the struct by delegating to its inherent methods, so orchestration code can be generic
+/// over pages or hold them as trait objects. Async methods return boxed `Send` futures there:
+///     #[thirtyfour_actions(as_trait = "LoginPageActions")]
+///
+/// The derive also accepts an enum whose struct-like variants are the states of one page. Each
+/// variant becomes a `<Enum><Variant>` struct holding its fields and their generated actions,
+/// expanded with the enum's options (except those naming a field, and `as_trait`). The enum gets
+/// `detect_state`, which probes each state's `anchor` field (its first field by default) and
+/// returns the first state present, and `into_<variant>` converting a state into its struct.
+/// States are built from their selectors, so their fields cannot be `skip`, `component`,
+/// `resolver`, keyed or stored elements, and the anchor's selector cannot be templated:
+///     #[derive(ImplThirtyfourActions)]
+///     enum LoginPage {
+///         LoggedOut {
+///             #[thirtyfour_actions(id = "username", methods(enter_keys))]
+///             username: By,
+///         },
+///         #[thirtyfour_actions(anchor = "avatar")]
+///         LoggedIn {
+///             #[thirtyfour_actions(css = ".menu", methods(click))]
+///             menu: By,
+///             #[thirtyfour_actions(css = ".avatar")]
+///             avatar: By,
+///         },
+///     }
+///     if let Some(page) = LoginPage::detect_state(&driver).await.and_then(LoginPage::into_logged_in) {
+///         page.click_menu(&driver).await?;
+///     }
+///
+/// Tag fields with a `group` to also generate a reduced page object per group: a
+/// `<Struct><Group>` struct with only the group's fields and their generated methods, and a
+/// `<group>()` method on the struct cloning the fields into it, so grouped fields must implement
+/// `Clone`. Shared parts such as a header are then declared once and other pages hold the group
+/// struct, e.g. as a `component` field:
+///     #[thirtyfour_actions(css = "header .logo", methods(click), group = "header")]
+///     logo: By,
+/// gives `LoginPageHeader` with `click_logo`, and `LoginPage::header()`.
+/// Group structs are expanded with the struct's options that shape generated methods (`global`,
+/// `naming`, `error`, `timeout_secs`, `dry_run`, ...), and with `sink`, `driver`, `soft_assert`,
+/// `session_audit`, `wait_between_actions` and `race` when the fields they name are in the
+/// group. Page-level options (`url`, `before_open`, `geolocation`, `timezone`, `locale`,
+/// `requires_cookie`, `expected_url_pattern`, `storage`, `alerts`) and `as_trait` stay with the
+/// struct. Runtime switches such as `dry_run()` and `rate_limiter()` are the group struct's own.
+/// Groups are not supported on generic structs.
+///
+/// Struct-level helpers can be enabled alongside global methods:
+///     #[thirtyfour_actions(storage)]
+/// generates `get_local_storage`/`set_local_storage` and `get_session_storage`/`set_session_storage`,
+/// and `#[thirtyfour_actions(alerts)]` generates `get_alert_text`, `accept_alert`, `dismiss_alert`
+/// and `answer_prompt`.
+#[proc_macro_derive(ImplThirtyfourActions, attributes(thirtyfour_actions))]
+pub fn impl_thirtyfour_actions(input: TokenStream) -> TokenStream {
+    expand(parse_macro_input!(input as DeriveInput)).into()
+}
+
+/// Expand the derive on its parsed input
+fn expand(input_parsed: DeriveInput) -> TokenStream2 {
+    if matches!(input_parsed.data, syn::Data::Enum(_)) {
+        return state_enum(input_parsed).unwrap_or_else(syn::Error::into_compile_error);
+    }
+    let groups = match group_structs(&input_parsed) {
+        Ok(groups) => groups,
+        Err(e) => return e.into_compile_error(),
+    };
+    let mut expanded = derive_struct(input_parsed);
+    expanded.extend(groups);
+    expanded
+}
+
+/// Expand the derive on a struct, the page object itself.
+fn derive_struct(input_parsed: DeriveInput) -> TokenStream2 {
+    let input_span = input_parsed.span();
+    let struct_name = input_parsed.ident;
+    let struct_vis = input_parsed.vis;
+    // Generated impls repeat the struct's generic parameters and bounds
+    let generics = input_parsed.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut methods = Vec::new();
+
+    // Extract struct-level options from struct attributes
+    let mut struct_options = StructOptions::default();
+    for attr in &input_parsed.attrs {
+        if attr.path().is_ident("thirtyfour_actions") {
+            match attr.parse_args_with(Punctuated::<StructItem, Comma>::parse_terminated) {
+                Ok(items) => {
+                    for item in items {
+                        struct_options.apply(item);
+                    }
+                }
+                Err(e) => {
+                    return syn::Error::new(
+                        e.span(),
+                        format!("Failed to parse thirtyfour_actions attribute: {}", e),
+                    )
+                    .to_compile_error();
+                }
+            }
+        }
+    }
+    if let Some(error) = &struct_options.error
+        && struct_options.structured_errors
+    {
+        return syn::Error::new_spanned(
+            error,
+            "structured_errors cannot be combined with error = \"...\"",
+        )
+        .to_compile_error();
+    }
+    let global_methods = &struct_options.global_methods;
+    let (error_type, message_error) = struct_options.error_tokens();
+    let (driver_error, driver_source) = struct_options.driver_error_tokens();
+    let CratePaths {
+        thirtyfour,
+        log,
+        tokio,
+        serde_json,
+        ..
+    } = &struct_options.crate_paths;
+
+    // Fields marked with a role, used by struct-level helpers
+    let mut error_fields = Vec::new();
+    let mut submit_field = None;
+    let mut load_marker_fields = Vec::new();
+    let mut overlay_fields = Vec::new();
+    let mut sink_field_found = false;
+    let mut driver_field_found = false;
+    let mut soft_assert_field_found = false;
+    let mut pacer_field_found = false;
+    let mut audit_field_found = false;
+
+    // (field, variant) pairs for the generated field enum
+    let mut enum_fields = Vec::new();
+    // Fields whose selectors have `{placeholder}`s
+    let mut template_fields = Vec::new();
+    // Fields holding a locator string for `locator_builder`, which `selector_map` builds
+    let mut built_fields = Vec::new();
+    // Fields marked `lazy`, which `resolve_all` leaves out
+    let mut lazy_fields = Vec::new();
+    // Match count checks of fields with `expect_count`, run by `validate_counts`
+    let mut count_checks = Vec::new();
+    // Zero-argument actions offered by `debug_menu`: (method name, call printing its result)
+    let mut debug_actions: Vec<(TokenStream2, String, TokenStream2)> = Vec::new();
+    // Zero-argument actions offered by `perform`: (cfg, field variant, action name, call)
+    let mut perform_actions: Vec<(TokenStream2, Ident, String, TokenStream2)> = Vec::new();
+    // (field, name, enabled actions) for the `Display` summary
+    let mut summary_fields = Vec::new();
+    // Field initializers and parameters of the generated `new`, which needs every locator
+    // field to declare an inline selector
+    let mut constructor_inits = Vec::new();
+    let mut constructor_params = Vec::new();
+    // Locator fields built from the selectors bound by `from_selectors`
+    let mut selector_config_inits = Vec::new();
+    // Fields passed to constructors as parameters, in `constructor_params` order
+    let mut constructor_param_fields = Vec::new();
+    let mut all_selectors_inline = true;
+
+    // Scopes and fallback selectors of every field, and how their methods are named
+    let mut lookups = Lookups {
+        naming: struct_options.naming.clone().unwrap_or_default(),
+        crate_paths: struct_options.crate_paths.clone(),
+        ..Lookups::default()
+    };
+
+    // Path and remaining entries of the `selectors_file`, taken by the fields they name
+    let mut selectors_file = match &struct_options.selectors_file {
+        Some(file) => match read_selectors_file(file) {
+            Ok(selectors) => Some(selectors),
+            Err(e) => return e.to_compile_error(),
+        },
+        None => None,
+    };
+
+    // Renamed fields and the unnamed fields of a tuple struct, by the names generated for them
+    let mut renamed_members = HashMap::new();
+    // Methods of fields with a `vis` of their own, left alone by the struct's `vis`
+    let mut field_vis_methods = Vec::new();
+    if let syn::Data::Struct(data_struct) = input_parsed.data {
+        let declared_names: Vec<String> = data_struct
+            .fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref().map(|ident| ident.to_string()))
+            .collect();
+        // Options of every named field, collected up front because a `root` field scopes the
+        // fields declared before it too
+        let mut fields = Vec::new();
+        for (index, mut field) in data_struct.fields.into_iter().enumerate() {
+            let mut field_options = FieldOptions::default();
+            for attr in &field.attrs {
+                if attr.path().is_ident("thirtyfour_actions") {
+                    match attr.parse_args_with(Punctuated::<FieldItem, Comma>::parse_terminated) {
+                        Ok(items) => {
+                            for item in items {
+                                field_options.apply(item);
+                            }
                         }
-                        "submit" => {
-                            let submit_fn_ident = syn::Ident::new(
-                                &format!("submit_{}", field_ident),
-                                field_ident.span(),
+                        Err(e) => {
+                            return syn::Error::new(
+                                e.span(),
+                                format!("Failed to parse thirtyfour_actions attribute: {}", e),
+                            )
+                            .to_compile_error();
+                        }
+                    }
+                }
+            }
+            // Renamed and unnamed fields are generated for under their name, and addressed by
+            // their member at the end
+            match (&field.ident, field_options.field_name.take()) {
+                (Some(ident), Some(name)) if name != *ident => {
+                    if declared_names.contains(&name.to_string()) {
+                        return syn::Error::new(
+                            name.span(),
+                            format!("cannot rename to '{}', which names another field", name),
+                        )
+                        .to_compile_error();
+                    }
+                    renamed_members.insert(name.to_string(), syn::Member::Named(ident.clone()));
+                    field.ident = Some(name);
+                }
+                (Some(_), _) => {}
+                (None, name) => {
+                    let name = name
+                        .unwrap_or_else(|| Ident::new(&format!("field_{}", index), field.span()));
+                    renamed_members.insert(
+                        name.to_string(),
+                        syn::Member::Unnamed(syn::Index::from(index)),
+                    );
+                    field.ident = Some(name);
+                }
+            }
+            let field_ident = field.ident.as_ref().expect("named field");
+            let holds_state = [
+                &struct_options.sink,
+                &struct_options.driver,
+                &struct_options.soft_assert,
+                &struct_options
+                    .wait_between_actions
+                    .as_ref()
+                    .map(|pacing| pacing.field.clone()),
+                &struct_options.session_audit,
+            ]
+            .iter()
+            .any(|state_field| state_field.as_ref() == Some(field_ident));
+            let is_locator = !holds_state
+                && !field_options.skip
+                && !field_options.component
+                && !field_options.resolver;
+            field_options.keyed = is_locator && is_selector_map(&field.ty);
+            field_options.stored_element = is_locator && is_stored_element(&field.ty);
+            if is_locator
+                && is_locator_string(&field.ty)
+                && let Some(builder) = &struct_options.locator_builder
+            {
+                field_options.built_with = Some(builder.clone());
+                lookups.builders.insert(
+                    field.ident.as_ref().expect("named field").to_string(),
+                    builder.clone(),
+                );
+            }
+            if let (
+                Some(file),
+                Some(SelectorsFile {
+                    selectors: file_selectors,
+                    ..
+                }),
+            ) = (&struct_options.selectors_file, &mut selectors_file)
+            {
+                let is_locator = is_locator
+                    && !field_options.keyed
+                    && !field_options.stored_element
+                    && field_options.built_with.is_none();
+                match file_selectors.remove(&field_ident.unraw().to_string()) {
+                    Some((selector, _)) if is_locator && field_options.selector.is_none() => {
+                        field_options.selector = Some(selector);
+                    }
+                    Some((_, line)) => {
+                        let reason = if is_locator {
+                            "which already declares an inline selector"
+                        } else if field_options.keyed {
+                            "which holds a map of selectors"
+                        } else if field_options.stored_element {
+                            "which holds a `WebElement`"
+                        } else if field_options.built_with.is_some() {
+                            "which holds a locator string for `locator_builder`"
+                        } else {
+                            "which is not a locator field"
+                        };
+                        return syn::Error::new(
+                            field_ident.span(),
+                            format!(
+                                "selectors file {} (line {}) has a selector for '{}', {}",
+                                file.value(),
+                                line,
+                                field_ident,
+                                reason
+                            ),
+                        )
+                        .to_compile_error();
+                    }
+                    None if is_locator && field_options.selector.is_none() => {
+                        return syn::Error::new(
+                            field_ident.span(),
+                            format!(
+                                "selectors file {} has no selector for '{}'",
+                                file.value(),
+                                field_ident
+                            ),
+                        )
+                        .to_compile_error();
+                    }
+                    None => {}
+                }
+            }
+            if field_options.nth.is_some()
+                && (field_options.unique || !field_options.fallbacks.is_empty())
+            {
+                return syn::Error::new(
+                    field.ident.span(),
+                    "`nth` cannot be combined with `unique` or `fallbacks`",
+                )
+                .to_compile_error();
+            }
+            if field_options.resolver
+                && (field_options.selector.is_some()
+                    || field_options.nth.is_some()
+                    || field_options.unique
+                    || !field_options.fallbacks.is_empty()
+                    || field_options.with_text.is_some()
+                    || !field_options.with_attributes.is_empty()
+                    || field_options.role == Some(Role::LoadMarker))
+            {
+                return syn::Error::new(
+                    field.ident.span(),
+                    "a `resolver` field is located by its `ElementResolver` and cannot have a \
+                     selector, `nth`, `unique`, `fallbacks`, `with_text`, `with_attribute` or \
+                     `role = \"load_marker\"`",
+                )
+                .to_compile_error();
+            }
+            if field_options.stored_element
+                && (field_options.selector.is_some()
+                    || field_options.nth.is_some()
+                    || field_options.unique
+                    || !field_options.fallbacks.is_empty()
+                    || field_options.with_text.is_some()
+                    || !field_options.with_attributes.is_empty()
+                    || field_options.within.is_some()
+                    || field_options.shadow_root.is_some()
+                    || field_options.root
+                    || field_options.role == Some(Role::LoadMarker))
+            {
+                return syn::Error::new(
+                    field.ident.span(),
+                    "a field holding a `WebElement` is used as stored and cannot have a selector, \
+                     `nth`, `unique`, `fallbacks`, `with_text`, `with_attribute`, `within`, \
+                     `shadow_root`, `root` or `role = \"load_marker\"`",
+                )
+                .to_compile_error();
+            }
+            if field_options.built_with.is_some() && field_options.selector.is_some() {
+                return syn::Error::new(
+                    field.ident.span(),
+                    "a field built by `locator_builder` holds its locator string and cannot have a \
+                     selector; set its initial value with `locator = \"...\"`",
+                )
+                .to_compile_error();
+            }
+            if let Some(locator) = &field_options.locator
+                && field_options.built_with.is_none()
+            {
+                return syn::Error::new(
+                    locator.span(),
+                    "`locator = \"...\"` only applies to `String` or `&str` fields of a struct with \
+                     `locator_builder`",
+                )
+                .to_compile_error();
+            }
+            if field_options.expect_count.is_some()
+                && (field_options.selectorless()
+                    || field_options.keyed
+                    || field_options
+                        .selector
+                        .as_ref()
+                        .is_some_and(|selector| !selector.placeholders().is_empty()))
+            {
+                return syn::Error::new(
+                    field.ident.span(),
+                    "expect_count needs a selector without placeholders to count the matches of",
+                )
+                .to_compile_error();
+            }
+            if field_options.with_text.is_some() || !field_options.with_attributes.is_empty() {
+                let with_text = field_options.with_text.iter();
+                let (names, values): (Vec<_>, Vec<_>) =
+                    field_options.with_attributes.iter().cloned().unzip();
+                lookups.filters.insert(
+                    field.ident.as_ref().expect("named field").to_string(),
+                    quote!(#(.with_text(#with_text))* #(.with_attribute(#names, #values))*),
+                );
+            }
+            if !field_options.fallbacks.is_empty() {
+                let field_ident = field.ident.as_ref().expect("named field");
+                if field_options.unique {
+                    return syn::Error::new(
+                        field_ident.span(),
+                        "`fallbacks` cannot be combined with `unique`",
+                    )
+                    .to_compile_error();
+                }
+                lookups
+                    .fallbacks
+                    .insert(field_ident.to_string(), field_options.fallbacks.clone());
+            }
+            if struct_options.strict
+                && !field_options.selectorless()
+                && field_options.nth.is_none()
+                && field_options.fallbacks.is_empty()
+            {
+                field_options.unique = true;
+            }
+            fields.push((field, field_options));
+        }
+        // Keys left over name no field, e.g. after a field was renamed
+        if let (
+            Some(file),
+            Some(SelectorsFile {
+                selectors: file_selectors,
+                ..
+            }),
+        ) = (&struct_options.selectors_file, &selectors_file)
+            && let Some((field, (_, line))) =
+                file_selectors.iter().min_by_key(|(_, (_, line))| *line)
+        {
+            return syn::Error::new(
+                file.span(),
+                format!(
+                    "selectors file {} (line {}) has a selector for '{}', which is not a field of {}",
+                    file.value(),
+                    line,
+                    field,
+                    struct_name
+                ),
+            )
+            .to_compile_error();
+        }
+
+        for (field, field_options) in &fields {
+            let Some(marker) = &field_options.done_when else {
+                continue;
+            };
+            if fields.iter().any(|(other, other_options)| {
+                other.ident.as_ref() == Some(marker) && other_options.selectorless()
+            }) {
+                return syn::Error::new(
+                    field.ident.span(),
+                    format!(
+                        "`done_when` marker '{}' cannot be a `resolver` or `WebElement` field",
+                        marker
+                    ),
+                )
+                .to_compile_error();
+            }
+        }
+
+        // Templated and keyed fields are only reachable through their own methods, which take
+        // the placeholder values or key
+        for (field, field_options) in &fields {
+            let placeholders = field_options
+                .selector
+                .as_ref()
+                .map(Selector::placeholders)
+                .unwrap_or_default();
+            if placeholders.is_empty() && !field_options.keyed {
+                continue;
+            }
+            let field_ident = field.ident.as_ref().expect("named field");
+            if let Some(selector) = &field_options.selector
+                && let Some(reserved) = placeholders
+                    .iter()
+                    .find(|placeholder| *placeholder == "driver" || *placeholder == "self")
+            {
+                return syn::Error::new(
+                    selector.value.span(),
+                    format!("'{}' cannot be used as a selector placeholder", reserved),
+                )
+                .to_compile_error();
+            }
+            let dependent = fields.iter().find_map(|(other, other_options)| {
+                [
+                    (&other_options.within, "`within`"),
+                    (&other_options.shadow_root, "`shadow_root`"),
+                    (&other_options.done_when, "`done_when`"),
+                ]
+                .into_iter()
+                .find(|(target, _)| target.as_ref() == Some(field_ident))
+                .map(|(_, option)| (other.ident.as_ref().expect("named field"), option))
+            });
+            let problem = if field_options.keyed && field_options.selector.is_some() {
+                Some("cannot have a selector of its own".to_string())
+            } else if !field_options.fallbacks.is_empty() {
+                Some("cannot have `fallbacks`".to_string())
+            } else if field_options.role.is_some() {
+                Some("cannot have a `role`".to_string())
+            } else if field_options.root {
+                Some("cannot be the `root`".to_string())
+            } else if let Some((other, option)) = dependent {
+                Some(format!("cannot be the {} of '{}'", option, other))
+            } else if struct_options
+                .races
+                .iter()
+                .any(|(first, second)| first == field_ident || second == field_ident)
+            {
+                Some("cannot take part in a `race`".to_string())
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                let kind = if field_options.keyed {
+                    "holds a map of selectors"
+                } else {
+                    "has a selector with placeholders"
+                };
+                return syn::Error::new(
+                    field_ident.span(),
+                    format!("field '{}' {} and {}", field_ident, kind, problem),
+                )
+                .to_compile_error();
+            }
+        }
+
+        let mut root_field: Option<Ident> = None;
+        for (field, field_options) in &fields {
+            if !field_options.root {
+                continue;
+            }
+            let field_ident = field.ident.as_ref().expect("named field");
+            if field_options.skip || field_options.component {
+                return syn::Error::new(
+                    field_ident.span(),
+                    "a `root` field must be a locator, not `skip` or `component`",
+                )
+                .to_compile_error();
+            }
+            if root_field.is_some() {
+                return syn::Error::new(field_ident.span(), "only one field can be the `root`")
+                    .to_compile_error();
+            }
+            root_field = Some(field_ident.clone());
+        }
+        if let Some(root_field) = root_field {
+            for (field, field_options) in &fields {
+                let field_ident = field.ident.as_ref().expect("named field");
+                if *field_ident != root_field && !field_options.skip && !field_options.component {
+                    lookups.scopes.insert(
+                        field_ident.to_string(),
+                        Scope {
+                            field: root_field.clone(),
+                            shadow: false,
+                        },
+                    );
+                }
+            }
+        }
+        // `within` and `shadow_root` scope a field under a sibling, overriding the root
+        for (field, field_options) in &fields {
+            let field_ident = field.ident.as_ref().expect("named field");
+            let (option, scope) = match (&field_options.within, &field_options.shadow_root) {
+                (Some(_), Some(_)) => {
+                    return syn::Error::new(
+                        field_ident.span(),
+                        "`within` and `shadow_root` cannot be combined",
+                    )
+                    .to_compile_error();
+                }
+                (Some(parent), None) => (
+                    "within",
+                    Scope {
+                        field: parent.clone(),
+                        shadow: false,
+                    },
+                ),
+                (None, Some(host)) => (
+                    "shadow_root",
+                    Scope {
+                        field: host.clone(),
+                        shadow: true,
+                    },
+                ),
+                (None, None) => continue,
+            };
+            let parent = &scope.field;
+            let parent_is_locator = fields.iter().any(|(other, other_options)| {
+                other.ident.as_ref() == Some(parent)
+                    && !other_options.skip
+                    && !other_options.component
+                    && struct_options.sink.as_ref() != Some(parent)
+                    && struct_options.driver.as_ref() != Some(parent)
+                    && struct_options.soft_assert.as_ref() != Some(parent)
+                    && struct_options
+                        .wait_between_actions
+                        .as_ref()
+                        .is_none_or(|pacing| &pacing.field != parent)
+                    && struct_options.session_audit.as_ref() != Some(parent)
+            });
+            if !parent_is_locator {
+                return syn::Error::new(
+                    parent.span(),
+                    format!(
+                        "{} field '{}' is not a locator field of this struct",
+                        option, parent
+                    ),
+                )
+                .to_compile_error();
+            }
+            lookups.scopes.insert(field_ident.to_string(), scope);
+        }
+        for (_, field_options) in &fields {
+            let Some(parent) = field_options
+                .within
+                .as_ref()
+                .or(field_options.shadow_root.as_ref())
+            else {
+                continue;
+            };
+            let mut current = parent.to_string();
+            let mut steps = 0;
+            while let Some(next) = lookups.scopes.get(&current) {
+                if steps == lookups.scopes.len() {
+                    return syn::Error::new(
+                        parent.span(),
+                        format!("scoped fields form a cycle through '{}'", parent),
+                    )
+                    .to_compile_error();
+                }
+                current = next.field.to_string();
+                steps += 1;
+            }
+        }
+
+        for (field, field_options) in fields {
+            if let Some(ref field_ident) = field.ident {
+                // The sink, driver and soft assertion fields hold runtime state, not locators
+                let is_sink = struct_options.sink.as_ref() == Some(field_ident);
+                let is_driver = struct_options.driver.as_ref() == Some(field_ident);
+                let is_soft_assert = struct_options.soft_assert.as_ref() == Some(field_ident);
+                if is_sink || is_driver || is_soft_assert {
+                    sink_field_found |= is_sink;
+                    driver_field_found |= is_driver;
+                    soft_assert_field_found |= is_soft_assert;
+                    let field_ty = &field.ty;
+                    constructor_params.push(quote!(#field_ident: #field_ty));
+                    constructor_param_fields.push(field_ident.clone());
+                    constructor_inits.push(quote!(#field_ident));
+                    continue;
+                }
+                // The pacer starts out idle, so it is created rather than passed in
+                if struct_options
+                    .wait_between_actions
+                    .as_ref()
+                    .is_some_and(|pacing| &pacing.field == field_ident)
+                {
+                    pacer_field_found = true;
+                    constructor_inits.push(quote!(#field_ident: Default::default()));
+                    selector_config_inits.push(quote!(#field_ident: Default::default()));
+                    continue;
+                }
+                // Likewise unbound until the first action or `bind_session`
+                if struct_options.session_audit.as_ref() == Some(field_ident) {
+                    audit_field_found = true;
+                    constructor_inits.push(quote!(#field_ident: Default::default()));
+                    selector_config_inits.push(quote!(#field_ident: Default::default()));
+                    continue;
+                }
+
+                let field_name_str = field_ident.unraw().to_string();
+                let query_fn_ident = lookups.naming.method_ident("query", field_ident, "");
+
+                // Skipped fields are plain data, and components carry their own locators and
+                // actions, reached through the field itself
+                if field_options.skip || field_options.component {
+                    let field_ty = &field.ty;
+                    constructor_params.push(quote!(#field_ident: #field_ty));
+                    constructor_param_fields.push(field_ident.clone());
+                    constructor_inits.push(quote!(#field_ident));
+                    continue;
+                }
+                // Values filled into the field's selector, or the key a keyed field's selector is
+                // looked up by, taken by its methods
+                let placeholders = if field_options.keyed {
+                    vec![syn::Ident::new("key", field_ident.span())]
+                } else {
+                    field_options
+                        .selector
+                        .as_ref()
+                        .map(Selector::placeholders)
+                        .unwrap_or_default()
+                };
+
+                // Resolvers are built by their thirtyfour component, stored elements were located
+                // beforehand, templated selectors need their placeholder values and keyed fields
+                // their key, so none has a `By` for the field enum's helpers; all but templates
+                // are passed to constructors
+                if field_options.selectorless() || field_options.keyed {
+                    let field_ty = &field.ty;
+                    constructor_params.push(quote!(#field_ident: #field_ty));
+                    constructor_param_fields.push(field_ident.clone());
+                    constructor_inits.push(quote!(#field_ident));
+                } else if field_options.built_with.is_some() {
+                    // Locator strings start from their `locator`, or are passed to constructors
+                    match &field_options.locator {
+                        Some(locator) => {
+                            constructor_inits.push(quote!(#field_ident: #locator.into()));
+                            selector_config_inits.push(quote!(#field_ident: #locator.into()));
+                        }
+                        None => {
+                            let field_ty = &field.ty;
+                            constructor_params.push(quote!(#field_ident: #field_ty));
+                            constructor_param_fields.push(field_ident.clone());
+                            constructor_inits.push(quote!(#field_ident));
+                        }
+                    }
+                    built_fields.push(field_ident.clone());
+                } else {
+                    match &field_options.selector {
+                        Some(Selector { constructor, value }) => {
+                            let locator = locator_init(
+                                &field.ty,
+                                quote!(#thirtyfour::By::#constructor(#value)),
                             );
-                            let method = quote! {
-                                /// Submit a form element.
-                                pub async fn #submit_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<()> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            element.submit().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to submit form {}: {}", #field_name_str, e))?;
-                                            Ok(())
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
+                            constructor_inits.push(quote!(#field_ident: #locator));
+                            // Templates are filled in per call, so they keep their declared selector
+                            if !placeholders.is_empty() {
+                                selector_config_inits.push(quote!(#field_ident: #locator));
+                            }
                         }
-                        "hover" => {
-                            let hover_fn_ident = syn::Ident::new(
-                                &format!("hover_{}", field_ident),
+                        None => all_selectors_inline = false,
+                    }
+                    if placeholders.is_empty() {
+                        let config_locator = locator_init(&field.ty, quote!(#field_ident));
+                        selector_config_inits.push(quote!(#field_ident: #config_locator));
+                        enum_fields.push((
+                            field_ident.clone(),
+                            syn::Ident::new(
+                                &to_upper_camel_case(&field_name_str),
                                 field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Hover over the web element (move mouse to it).
-                                pub async fn #hover_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<()> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            let actions = driver.action_chain();
-                                            actions.move_to_element(&element).perform().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to hover over {}: {}", #field_name_str, e))?;
-                                            Ok(())
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
+                            ),
+                        ));
+                    } else {
+                        template_fields.push(field_ident.clone());
+                    }
+                }
+                if field_options.lazy {
+                    lazy_fields.push(field_ident.clone());
+                }
+                if let Some(expected) = field_options.expect_count {
+                    let lookup = scoped_query(
+                        &lookups,
+                        field_ident,
+                        field_locator(field_ident, &field_options, &lookups),
+                        quote!(.nowait().all_from_selector().await),
+                        |_| quote!(Ok(Vec::new())),
+                    );
+                    let min = expected.min;
+                    let in_range = match expected.max {
+                        Some(max) => quote!((#min..=#max).contains(&elements.len())),
+                        None => quote!(elements.len() >= #min),
+                    };
+                    let expectation = expected.describe();
+                    count_checks.push(quote! {
+                        match #lookup {
+                            Ok(elements) if !(#in_range) => mismatches.push(format!(
+                                "{} matched {} elements, expected {}",
+                                #field_name_str,
+                                elements.len(),
+                                #expectation
+                            )),
+                            Ok(_) => {}
+                            Err(e) => mismatches.push(format!(
+                                "failed to count the matches of {}: {}",
+                                #field_name_str, e
+                            )),
+                        }
+                    });
+                }
+
+                // Always generate the base query method.
+                let selector = selector_debug(field_ident, &field_options);
+                let not_found_event = query_event(&selector, &field_name_str, None, log);
+                let error_event = query_event(&selector, &field_name_str, Some(quote!(e)), log);
+                // With a field timeout the query waits for the element to appear
+                let poll_ms = struct_options.poll_ms();
+                let query_wait = match field_options.timeout_secs {
+                    Some(timeout_secs) => {
+                        quote! {
+                            .wait(
+                                std::time::Duration::from_secs(#timeout_secs),
+                                std::time::Duration::from_millis(#poll_ms),
+                            )
+                        }
+                    }
+                    None => quote!(),
+                };
+                // `_with_timeout` variants wait for their `timeout_secs` argument instead
+                let timeout_wait = quote! {
+                    .wait(
+                        std::time::Duration::from_secs(timeout_secs),
+                        std::time::Duration::from_millis(#poll_ms),
+                    )
+                };
+                // With `no_query` the query stays available to generated actions only
+                let query_vis = if field_options.no_query {
+                    quote!(#[allow(dead_code)])
+                } else {
+                    quote!(pub)
+                };
+                // Bodies of the query and, with `unique`, of `try_query_<field>` reporting
+                // ambiguous matches as errors, waiting with `query_wait` (or until `fallback_secs`
+                // for fallback selectors)
+                let query_bodies = |query_wait: &TokenStream2, fallback_secs: &TokenStream2| {
+                    if field_options.resolver {
+                        // Retried like fallbacks, as the resolver waits by its own poller
+                        let query_body = quote! {
+                            use std::time::{Duration, Instant};
+                            let _ = driver;
+                            let deadline = Instant::now() + Duration::from_secs(#fallback_secs);
+                            loop {
+                                match self.#field_ident.resolve_present().await {
+                                    Ok(resolved) => {
+                                        return Some(#thirtyfour::components::Component::base_element(&resolved));
                                     }
+                                    Err(e) if Instant::now() >= deadline => {
+                                        #error_event
+                                        return None;
+                                    }
+                                    Err(_) => #tokio::time::sleep(Duration::from_millis(#poll_ms)).await,
                                 }
-                            };
-                            methods.push(method);
-                        }
-                        "drag_to" => {
-                            let drag_to_fn_ident = syn::Ident::new(
-                                &format!("drag_{}_to", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Drag this element to another target element.
-                                pub async fn #drag_to_fn_ident(&self, driver: &thirtyfour::WebDriver, target_element: &thirtyfour::WebElement) -> anyhow::Result<()> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            let actions = driver.action_chain();
-                                            actions.drag_and_drop(&element, target_element).perform().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to drag {} to target: {}", #field_name_str, e))?;
-                                            Ok(())
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
+                            }
+                        };
+                        (query_body, None)
+                    } else if field_options.stored_element {
+                        // A stored element is either attached or stale, and waiting changes neither
+                        let stale_event = query_event(
+                            &selector,
+                            &field_name_str,
+                            Some(quote!("stored element is stale")),
+                            log,
+                        );
+                        let query_body = quote! {
+                            let _ = (driver, #fallback_secs);
+                            let element = #thirtyfour::WebElement::clone(&self.#field_ident);
+                            match element.is_present().await {
+                                Ok(true) => Some(element),
+                                Ok(false) => {
+                                    #stale_event
+                                    None
+                                }
+                                Err(e) => {
+                                    #error_event
+                                    None
+                                }
+                            }
+                        };
+                        (query_body, None)
+                    } else if field_options.unique {
+                        let lookup = scoped_query(
+                            &lookups,
+                            field_ident,
+                            field_locator(field_ident, &field_options, &lookups),
+                            quote!(#query_wait.all_from_selector().await),
+                            |_| quote!(Ok(Vec::new())),
+                        );
+                        let try_query_body = quote! {
+                            match #lookup {
+                                Ok(elements) => match elements.len() {
+                                    0 => Ok(None),
+                                    1 => Ok(elements.into_iter().next()),
+                                    count => Err(#message_error(format!(
+                                        "Selector for {} matched {} elements, expected exactly one",
+                                        #field_name_str, count
+                                    ))),
+                                },
+                                Err(e) => Err(#driver_error(format!("Error querying element {}: {}", #field_name_str, e) #driver_source)),
+                            }
+                        };
+                        let ambiguous_event = query_event(
+                            &selector,
+                            &field_name_str,
+                            Some(quote!(format!(
+                                "selector matched {} elements, expected exactly one",
+                                count
+                            ))),
+                            log,
+                        );
+                        let query_body = quote! {
+                            match #lookup {
+                                Ok(elements) => match elements.len() {
+                                    0 => {
+                                        #not_found_event
+                                        None
+                                    }
+                                    1 => elements.into_iter().next(),
+                                    count => {
+                                        #ambiguous_event
+                                        None
                                     }
+                                },
+                                Err(e) => {
+                                    #error_event
+                                    None
                                 }
-                            };
-                            methods.push(method);
+                            }
+                        };
+                        (query_body, Some(try_query_body))
+                    } else if let Some(nth) = field_options.nth {
+                        let lookup = scoped_query(
+                            &lookups,
+                            field_ident,
+                            field_locator(field_ident, &field_options, &lookups),
+                            quote!(#query_wait.all_from_selector().await),
+                            |_| quote!(Ok(Vec::new())),
+                        );
+                        let query_body = quote! {
+                            match #lookup {
+                                Ok(elements) => match elements.into_iter().nth(#nth) {
+                                    Some(element) => Some(element),
+                                    None => {
+                                        #not_found_event
+                                        None
+                                    }
+                                },
+                                Err(e) => {
+                                    #error_event
+                                    None
+                                }
+                            }
+                        };
+                        (query_body, None)
+                    } else if !field_options.fallbacks.is_empty() {
+                        // Tried one selector at a time to report which one matched
+                        let filters = lookups.filters.get(&field_name_str);
+                        let lookup = scope_query(
+                            lookups.scopes.get(&field_name_str),
+                            &lookups.naming,
+                            quote!(locator.clone()),
+                            quote!(#filters.nowait().first_opt().await),
+                            |_| quote!(Ok(None)),
+                        );
+                        let primary_locator = field_locator(field_ident, &field_options, &lookups);
+                        let fallback_locators = field_options.fallbacks.iter().map(
+                            |Selector { constructor, value }| quote!(#thirtyfour::By::#constructor(#value)),
+                        );
+                        let fallback_event = if cfg!(feature = "tracing") {
+                            quote! {
+                                tracing::info!(element = #field_name_str, selector = ?locator, action = "query", "Element found with fallback selector");
+                            }
+                        } else {
+                            quote! {
+                                #log::info!("Element {} found with fallback selector {:?}", #field_name_str, locator);
+                            }
+                        };
+                        let query_body = quote! {
+                            use std::time::{Duration, Instant};
+                            let locators = [#primary_locator, #(#fallback_locators),*];
+                            let deadline = Instant::now() + Duration::from_secs(#fallback_secs);
+                            loop {
+                                for (index, locator) in locators.iter().enumerate() {
+                                    match #lookup {
+                                        Ok(Some(element)) => {
+                                            if index > 0 {
+                                                #fallback_event
+                                            }
+                                            return Some(element);
+                                        }
+                                        Ok(None) => {}
+                                        Err(e) => {
+                                            #error_event
+                                            return None;
+                                        }
+                                    }
+                                }
+                                if Instant::now() >= deadline {
+                                    break;
+                                }
+                                #tokio::time::sleep(Duration::from_millis(#poll_ms)).await;
+                            }
+                            #not_found_event
+                            None
+                        };
+                        (query_body, None)
+                    } else {
+                        let lookup = scoped_query(
+                            &lookups,
+                            field_ident,
+                            field_locator(field_ident, &field_options, &lookups),
+                            quote!(#query_wait.first_opt().await),
+                            |_| quote!(Ok(None)),
+                        );
+                        let query_body = quote! {
+                            match #lookup {
+                                Ok(Some(element)) => Some(element),
+                                Ok(None) => {
+                                    #not_found_event
+                                    None
+                                }
+                                Err(e) => {
+                                    #error_event
+                                    None
+                                }
+                            }
+                        };
+                        (query_body, None)
+                    }
+                };
+                // Keyed fields look their selector up first, logging a missing key like an error
+                let query_bodies = |query_wait: &TokenStream2, fallback_secs: &TokenStream2| {
+                    let query_binding = keyed_locator_binding(
+                        field_ident,
+                        &field_options,
+                        &lookups.naming,
+                        Some(quote!({
+                            #error_event
+                            return None;
+                        })),
+                    );
+                    let try_query_binding =
+                        keyed_locator_binding(field_ident, &field_options, &lookups.naming, None);
+                    let (query_body, try_query_body) = query_bodies(query_wait, fallback_secs);
+                    (
+                        quote!(#query_binding #query_body),
+                        try_query_body.map(|body| quote!(#try_query_binding #body)),
+                    )
+                };
+                let field_timeout_secs = field_options.timeout_secs.unwrap_or(0);
+                let (query_body, try_query_body) =
+                    query_bodies(&query_wait, &quote!(#field_timeout_secs));
+                let (timeout_query_body, timeout_try_query_body) =
+                    query_bodies(&timeout_wait, &quote!(timeout_secs));
+                let query_timeout_fn_ident = syn::Ident::new(
+                    &format!("{}_with_timeout", query_fn_ident),
+                    field_ident.span(),
+                );
+                let query_timeout_doc = format!(
+                    " Like [`Self::{}`], waiting up to `timeout_secs` for the element to appear.",
+                    query_fn_ident
+                );
+                let query_method = quote! {
+                    /// Query the web element from the DOM.
+                    ///
+                    /// Returns `Some(WebElement)` if found, `None` otherwise.
+                    #query_vis async fn #query_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> Option<#thirtyfour::WebElement> {
+                        #query_body
+                    }
+
+                    #[doc = #query_timeout_doc]
+                    #query_vis async fn #query_timeout_fn_ident(&self, driver: &#thirtyfour::WebDriver, timeout_secs: u64) -> Option<#thirtyfour::WebElement> {
+                        #timeout_query_body
+                    }
+                };
+                let try_query_method = try_query_body.map(|try_query_body| {
+                    let try_query_fn_ident = lookups.naming.method_ident("try_query", field_ident, "");
+                    let try_query_timeout_fn_ident = lookups.naming.method_ident("try_query", field_ident, "_with_timeout");
+                    let try_query_timeout_doc = format!(
+                        " Like [`Self::{}`], waiting up to `timeout_secs` for the element to appear.",
+                        try_query_fn_ident
+                    );
+                    quote! {
+                        /// Query the web element from the DOM, requiring the selector to match at most one element.
+                        ///
+                        /// Returns `Ok(None)` if nothing matches, and an error with the count if several elements do.
+                        #query_vis async fn #try_query_fn_ident(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<Option<#thirtyfour::WebElement>, #error_type> {
+                            #try_query_body
                         }
 
-                        // Element properties and state
-                        "get_text" => {
-                            let get_text_fn_ident = syn::Ident::new(
-                                &format!("get_text_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Get the text content of the web element.
-                                pub async fn #get_text_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<String> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            element.text().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to get text from {}: {}", #field_name_str, e))
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
+                        #[doc = #try_query_timeout_doc]
+                        #query_vis async fn #try_query_timeout_fn_ident(&self, driver: &#thirtyfour::WebDriver, timeout_secs: u64) -> std::result::Result<Option<#thirtyfour::WebElement>, #error_type> {
+                            #timeout_try_query_body
+                        }
+                    }
+                });
+                let first_query_method = methods.len();
+                methods.push(query_method);
+                methods.extend(try_query_method);
+
+                if field_options.resolver {
+                    let resolved_ty = match &field.ty {
+                        syn::Type::Path(path) => path
+                            .path
+                            .segments
+                            .last()
+                            .filter(|segment| segment.ident == "ElementResolver")
+                            .and_then(|segment| match &segment.arguments {
+                                syn::PathArguments::AngleBracketed(args) => args.args.first(),
+                                _ => None,
+                            }),
+                        _ => None,
+                    };
+                    let Some(resolved_ty) = resolved_ty else {
+                        return syn::Error::new(
+                            field.ty.span(),
+                            "a `resolver` field must have type `ElementResolver<T>`",
+                        )
+                        .to_compile_error();
+                    };
+                    let resolve_fn_ident = lookups.naming.method_ident("resolve", field_ident, "");
+                    methods.push(quote! {
+                        /// Resolve the field through its `ElementResolver`, returning the thirtyfour
+                        /// component (or element) it holds.
+                        pub async fn #resolve_fn_ident(&self) -> std::result::Result<#resolved_ty, #error_type> {
+                            self.#field_ident.resolve_present().await
+                                .map_err(|e| #driver_error(format!("Failed to resolve {}: {}", #field_name_str, e) #driver_source))
+                        }
+                    });
+                }
+
+                if let Some(selector) = &field_options.selector {
+                    let desc_ident = syn::Ident::new(
+                        &format!("{}_SELECTOR_DESC", field_name_str.to_uppercase()),
+                        field_ident.span(),
+                    );
+                    let desc = selector.describe();
+                    methods.push(quote! {
+                        /// The inline selector of this field, rendered like its `By` constructor.
+                        pub const #desc_ident: &'static str = #desc;
+                    });
+                    if !placeholders.is_empty() {
+                        let locator_fn_ident =
+                            lookups.naming.method_ident("locator", field_ident, "");
+                        let Selector { constructor, value } = selector;
+                        methods.push(quote! {
+                            /// Build the selector of this field with its placeholders filled in.
+                            pub fn #locator_fn_ident(&self, #(#placeholders: &str),*) -> #thirtyfour::By {
+                                #thirtyfour::By::#constructor(format!(#value, #(#placeholders = #placeholders),*).as_str())
+                            }
+                        });
+                    }
+                }
+                if field_options.keyed {
+                    let locator_fn_ident = lookups.naming.method_ident("locator", field_ident, "");
+                    methods.push(quote! {
+                        /// Look up the selector of this field for `key`, failing with the known keys
+                        /// if there is none.
+                        pub fn #locator_fn_ident(&self, key: &str) -> std::result::Result<#thirtyfour::By, #error_type> {
+                            match self.#field_ident.get(key) {
+                                Some(locator) => Ok(#thirtyfour::By::clone(locator)),
+                                None => {
+                                    let mut keys: Vec<String> =
+                                        self.#field_ident.keys().map(|key| key.to_string()).collect();
+                                    keys.sort();
+                                    Err(#message_error(format!(
+                                        "{} has no selector for key '{}' (known keys: {})",
+                                        #field_name_str,
+                                        key,
+                                        keys.join(", ")
+                                    )))
                                 }
-                            };
-                            methods.push(method);
+                            }
                         }
-                        "get_attribute" => {
-                            let get_attr_fn_ident = syn::Ident::new(
-                                &format!("get_attribute_{}", field_ident),
+                    });
+                }
+
+                match field_options.role {
+                    Some(Role::ErrorMessage) => {
+                        error_fields.push(query_fn_ident.clone());
+                    }
+                    Some(Role::Submit) => {
+                        if submit_field.is_some() {
+                            return syn::Error::new(
                                 field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Get a specific attribute value from the web element.
-                                pub async fn #get_attr_fn_ident(&self, driver: &thirtyfour::WebDriver, attribute: &str) -> anyhow::Result<Option<String>> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            element.attr(attribute).await
-                                                .map_err(|e| anyhow::anyhow!("Failed to get attribute '{}' from {}: {}",
-                                                    attribute, #field_name_str, e))
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
+                                "only one field can have role = \"submit\"",
+                            )
+                            .to_compile_error();
                         }
-                        "get_value" => {
-                            let get_value_fn_ident = syn::Ident::new(
-                                &format!("get_value_{}", field_ident),
+                        submit_field = Some((
+                            selector_debug(field_ident, &field_options),
+                            query_fn_ident.clone(),
+                            field_name_str.clone(),
+                        ));
+                    }
+                    Some(Role::LoadMarker) => {
+                        load_marker_fields.push((field_ident.clone(), field_name_str.clone()));
+                    }
+                    Some(Role::Overlay) => {
+                        overlay_fields.push(query_fn_ident.clone());
+                    }
+                    None => {}
+                }
+
+                // Combine global methods with field-specific methods
+                let mut all_methods = if field_options.no_defaults {
+                    Vec::new()
+                } else {
+                    global_methods.clone()
+                };
+                all_methods.extend(field_options.methods.iter().cloned());
+
+                // Ensure we don't have duplicate methods
+                all_methods.sort();
+                all_methods.dedup();
+                summary_fields.push((
+                    selector_debug(field_ident, &field_options),
+                    field_name_str.clone(),
+                    all_methods.join(", "),
+                ));
+
+                let field_context = FieldContext {
+                    field_ident,
+                    field_name_str: &field_name_str,
+                    query_fn_ident: &query_fn_ident,
+                    options: &field_options,
+                    struct_options: &struct_options,
+                    lookups: &lookups,
+                };
+
+                // With `cfg_feature` the actions only exist in consumer builds enabling the feature
+                let field_cfg = match &field_options.cfg_feature {
+                    Some(feature) => quote!(#[cfg(feature = #feature)]),
+                    None => quote!(),
+                };
+
+                // For each method requested, generate its implementation.
+                let first_field_method = methods.len();
+                let once_methods = idempotent_methods(&field_context, &all_methods);
+                // (index in `methods`, action) of actions failing softly with `soft_assert`
+                let mut soft_actions = Vec::new();
+                for method_name in all_methods {
+                    if struct_options.perform
+                        && placeholders.is_empty()
+                        && DEBUG_MENU_ACTIONS.contains(&method_name.as_str())
+                    {
+                        let action_fn_ident =
+                            lookups.naming.method_ident(&method_name, field_ident, "");
+                        let call = if method_name == "exists" {
+                            quote!(self.#action_fn_ident(driver).await)
+                        } else {
+                            quote!(self.#action_fn_ident(driver).await?)
+                        };
+                        perform_actions.push((
+                            field_cfg.clone(),
+                            syn::Ident::new(
+                                &to_upper_camel_case(&field_name_str),
                                 field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Get the value attribute of a form control element.
-                                pub async fn #get_value_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<Option<String>> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            element.attr("value").await
-                                                .map_err(|e| anyhow::anyhow!("Failed to get value from {}: {}", #field_name_str, e))
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
+                            ),
+                            method_name.clone(),
+                            call,
+                        ));
+                    }
+                    if cfg!(feature = "debug-tools")
+                        && placeholders.is_empty()
+                        && DEBUG_MENU_ACTIONS.contains(&method_name.as_str())
+                    {
+                        let debug_fn_ident =
+                            lookups.naming.method_ident(&method_name, field_ident, "");
+                        let debug_name = debug_fn_ident.to_string();
+                        let call = if method_name == "exists" {
+                            quote!(println!("{:?}", self.#debug_fn_ident(driver).await))
+                        } else {
+                            quote! {
+                                match self.#debug_fn_ident(driver).await {
+                                    Ok(value) => println!("{:?}", value),
+                                    Err(e) => println!("error: {}", e),
                                 }
-                            };
+                            }
+                        };
+                        debug_actions.push((field_cfg.clone(), debug_name, call));
+                    }
+                    match field_action(&field_context, &method_name) {
+                        Ok(method) => {
+                            if method_name.starts_with("assert_")
+                                || struct_options.soft_methods.contains(&method_name)
+                            {
+                                soft_actions.push((methods.len(), method_name.clone()));
+                            }
                             methods.push(method);
                         }
-                        "get_css_value" => {
-                            let get_css_fn_ident = syn::Ident::new(
-                                &format!("get_css_value_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Get a CSS property value of the web element.
-                                pub async fn #get_css_fn_ident(&self, driver: &thirtyfour::WebDriver, property: &str) -> anyhow::Result<String> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            element.css_value(property).await
-                                                .map_err(|e| anyhow::anyhow!("Failed to get CSS property '{}' from {}: {}",
-                                                    property, #field_name_str, e))
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
+                        Err(e) => return e.to_compile_error(),
+                    }
+                }
+
+                if field_options.optional
+                    && let Err(e) = optional_methods(
+                        &mut methods[first_field_method..],
+                        &query_fn_ident,
+                        &lookups.naming.method_ident("try_query", field_ident, ""),
+                        &error_type,
+                    )
+                {
+                    return e.to_compile_error();
+                }
+                if let Err(e) = wrap_methods(
+                    &mut methods[first_field_method..],
+                    &struct_options,
+                    &struct_name.to_string(),
+                    Some(&field_name_str),
+                    field_options.sensitive,
+                ) {
+                    return e.to_compile_error();
+                }
+                // Outside the wrappers, so sinks still see the failures
+                if let Some(collector) = &struct_options.soft_assert {
+                    for (index, action) in &soft_actions {
+                        match soften_methods(
+                            &mut methods[*index..=*index],
+                            collector,
+                            &struct_name.to_string(),
+                            |_| true,
+                        ) {
+                            Ok(0) if struct_options.soft_methods.contains(action) => {
+                                return syn::Error::new(
+                                    collector.span(),
+                                    format!(
+                                        "soft_methods: '{}' does not return Result<()> on field '{}'",
+                                        action, field_name_str
+                                    ),
+                                )
+                                .to_compile_error();
+                            }
+                            Ok(_) => {}
+                            Err(e) => return e.to_compile_error(),
+                        }
+                    }
+                }
+                // Copies of the wrapped actions, generated after the `_default` variants so those
+                // are not derived from them
+                let timeout_variants = match timeout_override_methods(
+                    &methods[first_field_method..],
+                    &[
+                        query_fn_ident.clone(),
+                        lookups.naming.method_ident("try_query", field_ident, ""),
+                    ],
+                ) {
+                    Ok(variants) => variants,
+                    Err(e) => return e.to_compile_error(),
+                };
+                // Calls the wrapped actions, so not wrapped again
+                methods.extend(once_methods);
+                if let Some(timeout_secs) =
+                    field_options.timeout_secs.or(struct_options.timeout_secs)
+                {
+                    match default_timeout_methods(&methods[first_field_method..], timeout_secs) {
+                        Ok(defaults) => methods.extend(defaults),
+                        Err(e) => return e.to_compile_error(),
+                    }
+                }
+                methods.extend(timeout_variants);
+                for alias in &field_options.aliases {
+                    match alias_methods(
+                        &methods[first_query_method..],
+                        field_ident,
+                        alias,
+                        &lookups.naming,
+                    ) {
+                        Ok(aliases) => methods.extend(aliases),
+                        Err(e) => return e.to_compile_error(),
+                    }
+                }
+                if let Some(vis) = &field_options.vis {
+                    if let Err(e) = restrict_methods(&mut methods[first_query_method..], vis) {
+                        return e.to_compile_error();
+                    }
+                    field_vis_methods.push(first_query_method..methods.len());
+                }
+                if !placeholders.is_empty()
+                    && let Err(e) = placeholder_methods(
+                        &mut methods[first_query_method..],
+                        &lookups.naming.method_ident("locator", field_ident, ""),
+                        &placeholders,
+                    )
+                {
+                    return e.to_compile_error();
+                }
+                // The query stays unconditional for struct-level helpers and scoped fields
+                if let Some(feature) = &field_options.cfg_feature
+                    && let Err(e) = cfg_methods(&mut methods[first_field_method..], feature)
+                {
+                    return e.to_compile_error();
+                }
+            }
+        }
+    } else {
+        return syn::Error::new(
+            input_span,
+            "ImplThirtyfourActions can only be derived for structs and enums",
+        )
+        .to_compile_error();
+    }
+
+    if let Some(sink_field) = &struct_options.sink
+        && !sink_field_found
+    {
+        return syn::Error::new(
+            sink_field.span(),
+            format!("sink field '{}' not found in struct", sink_field),
+        )
+        .to_compile_error();
+    }
+
+    if let Some(soft_assert_field) = &struct_options.soft_assert
+        && !soft_assert_field_found
+    {
+        return syn::Error::new(
+            soft_assert_field.span(),
+            format!(
+                "soft_assert field '{}' not found in struct",
+                soft_assert_field
+            ),
+        )
+        .to_compile_error();
+    }
+    if let Some(pacing) = &struct_options.wait_between_actions
+        && !pacer_field_found
+    {
+        return syn::Error::new(
+            pacing.field.span(),
+            format!(
+                "wait_between_actions field '{}' not found in struct",
+                pacing.field
+            ),
+        )
+        .to_compile_error();
+    }
+    if let Some(audit_field) = &struct_options.session_audit
+        && !audit_field_found
+    {
+        return syn::Error::new(
+            audit_field.span(),
+            format!("session_audit field '{}' not found in struct", audit_field),
+        )
+        .to_compile_error();
+    }
+    if let Some(hook) = &struct_options.before_open
+        && struct_options.url.is_none()
+    {
+        return syn::Error::new(hook.span(), "before_open requires url = \"...\"")
+            .to_compile_error();
+    }
+    if struct_options.soft_assert.is_none() && !struct_options.soft_methods.is_empty() {
+        return syn::Error::new(
+            input_span,
+            "soft_methods(...) requires soft_assert = \"field\"",
+        )
+        .to_compile_error();
+    }
+
+    if let Some(driver_field) = &struct_options.driver {
+        if !driver_field_found {
+            return syn::Error::new(
+                driver_field.span(),
+                format!("driver field '{}' not found in struct", driver_field),
+            )
+            .to_compile_error();
+        }
+        if struct_options.broadcast {
+            return syn::Error::new(
+                driver_field.span(),
+                "`broadcast` passes a driver to each call and cannot be combined with `driver`",
+            )
+            .to_compile_error();
+        }
+    }
+
+    // Items generated next to the struct's impl block
+    let mut items = Vec::new();
+    // Rebuild when the selectors file changes
+    if let Some(SelectorsFile { path, .. }) = &selectors_file {
+        items.push(quote!(
+            const _: &str = include_str!(#path);
+        ));
+    }
+
+    // Constructor from inline selectors; other fields become parameters
+    if all_selectors_inline && (!enum_fields.is_empty() || !built_fields.is_empty()) {
+        methods.push(quote! {
+            /// Create the page object from the selectors declared for its fields.
+            pub fn new(#(#constructor_params),*) -> Self {
+                Self {
+                    #(#constructor_inits,)*
+                }
+            }
+        });
+        if constructor_params.is_empty() {
+            items.push(quote! {
+                impl #impl_generics Default for #struct_name #ty_generics #where_clause {
+                    fn default() -> Self {
+                        Self::new()
+                    }
+                }
+            });
+        }
+    }
+
+    // Messages of the generated methods become the configured error through this helper
+    if let Some(error) = &struct_options.error {
+        methods.push(quote! {
+            /// Turn an error message of the generated methods into the configured error type.
+            #[allow(dead_code)]
+            fn thirtyfour_actions_error(message: String) -> #error {
+                <#error as From<#thirtyfour::error::WebDriverError>>::from(
+                    #thirtyfour::error::WebDriverError::CustomError(message),
+                )
+            }
+        });
+    }
+
+    if struct_options.from_selectors {
+        let runtime = runtime_path();
+        let locator_fields: Vec<_> = enum_fields.iter().map(|(field, _)| field).collect();
+        let locator_names: Vec<String> = locator_fields
+            .iter()
+            .map(|field| field.unraw().to_string())
+            .collect();
+        methods.push(quote! {
+            /// Create the page object from selectors loaded at runtime, looked up by field name.
+            ///
+            /// Fails naming every locator field missing from `config`; entries for other names
+            /// are ignored.
+            pub fn from_selectors(config: &#runtime::SelectorConfig, #(#constructor_params),*) -> std::result::Result<Self, #error_type> {
+                match (#(config.get(#locator_names),)*) {
+                    (#(Some(#locator_fields),)*) => Ok(Self {
+                        #(#selector_config_inits,)*
+                        #(#constructor_param_fields,)*
+                    }),
+                    #[allow(unreachable_patterns)]
+                    _ => {
+                        let missing: Vec<&str> = [#(#locator_names),*]
+                            .into_iter()
+                            .filter(|name| config.get(name).is_none())
+                            .collect();
+                        Err(#message_error(format!("Selector config has no selector for {}", missing.join(", "))))
+                    }
+                }
+            }
+        });
+    }
+
+    // Struct-level helpers that are not tied to a single field
+    let poll_ms = struct_options.poll_ms();
+    let first_struct_method = methods.len();
+    if struct_options.storage {
+        for (prefix, storage) in [("local", "localStorage"), ("session", "sessionStorage")] {
+            let get_fn_ident = syn::Ident::new(&format!("get_{}_storage", prefix), input_span);
+            let set_fn_ident = syn::Ident::new(&format!("set_{}_storage", prefix), input_span);
+            let get_script = format!("return window.{}.getItem(arguments[0]);", storage);
+            let set_script = format!("window.{}.setItem(arguments[0], arguments[1]);", storage);
+            let get_doc = format!(" Read a value from the page's `{}`.", storage);
+            let set_doc = format!(" Write a value to the page's `{}`.", storage);
+            methods.push(quote! {
+                #[doc = #get_doc]
+                ///
+                /// Returns `None` if the key is not set.
+                pub async fn #get_fn_ident(&self, driver: &#thirtyfour::WebDriver, key: &str) -> std::result::Result<Option<String>, #error_type> {
+                    let ret = driver.execute(#get_script, vec![key.into()]).await
+                        .map_err(|e| #driver_error(format!("Failed to read {} key '{}': {}", #storage, key, e) #driver_source))?;
+                    Ok(ret.json().as_str().map(|value| value.to_string()))
+                }
+
+                #[doc = #set_doc]
+                pub async fn #set_fn_ident(&self, driver: &#thirtyfour::WebDriver, key: &str, value: &str) -> std::result::Result<(), #error_type> {
+                    driver.execute(#set_script, vec![key.into(), value.into()]).await
+                        .map_err(|e| #driver_error(format!("Failed to write {} key '{}': {}", #storage, key, e) #driver_source))?;
+                    Ok(())
+                }
+            });
+        }
+    }
+
+    if !error_fields.is_empty() {
+        methods.push(quote! {
+            /// Collect the texts of all displayed fields marked `role = "error_message"`.
+            pub async fn collect_errors(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<Vec<String>, #error_type> {
+                let mut errors = Vec::new();
+                #(
+                    if let Some(element) = self.#error_fields(driver).await {
+                        if element.is_displayed().await.unwrap_or(false) {
+                            let text = element.text().await
+                                .map_err(|e| #driver_error(format!("Failed to get error text: {}", e) #driver_source))?;
+                            if !text.trim().is_empty() {
+                                errors.push(text);
+                            }
+                        }
+                    }
+                )*
+                Ok(errors)
+            }
+        });
+
+        if let Some((submit_selector, submit_query_fn_ident, submit_name_str)) = submit_field {
+            let not_found =
+                not_found_error(&struct_options, quote!(#submit_name_str), submit_selector);
+            methods.push(quote! {
+                /// Click the field marked `role = "submit"`, wait briefly, then collect all displayed error messages.
+                pub async fn submit_and_collect_errors(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<Vec<String>, #error_type> {
+                    match self.#submit_query_fn_ident(driver).await {
+                        Some(element) => {
+                            element.click().await
+                                .map_err(|e| #driver_error(format!("Failed to click {}: {}", #submit_name_str, e) #driver_source))?;
+                        },
+                        None => return Err(#not_found)
+                    }
+                    #tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                    self.collect_errors(driver).await
+                }
+            });
+        }
+    }
+
+    // Redirect detection, run after navigation and load checks
+    let check_on_page = if let Some(pattern) = &struct_options.expected_url_pattern {
+        methods.push(quote! {
+            /// Check that the current URL matches `expected_url_pattern`.
+            ///
+            /// Catches unexpected redirects (expired logins, error pages) before acting on the page.
+            pub async fn assert_on_page(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<(), #error_type> {
+                static PATTERN: std::sync::OnceLock<Result<regex::Regex, regex::Error>> =
+                    std::sync::OnceLock::new();
+                let pattern = PATTERN.get_or_init(|| regex::Regex::new(#pattern)).as_ref()
+                    .map_err(|e| #driver_error(format!("Invalid expected_url_pattern {}: {}", #pattern, e) #driver_source))?;
+                let current_url = driver.current_url().await
+                    .map_err(|e| #driver_error(format!("Failed to get current URL: {}", e) #driver_source))?;
+                if pattern.is_match(current_url.as_str()) {
+                    Ok(())
+                } else {
+                    Err(#message_error(format!(
+                        "Unexpected page: URL {} does not match {}",
+                        current_url.as_str(),
+                        #pattern
+                    )))
+                }
+            }
+        });
+        quote!(self.assert_on_page(driver).await?;)
+    } else {
+        quote!()
+    };
+
+    if !load_marker_fields.is_empty() {
+        let (marker_fields, marker_names): (Vec<_>, Vec<_>) =
+            load_marker_fields.into_iter().unzip();
+        let marker_waits = marker_fields.iter().map(|marker_field| {
+            scoped_query(
+                &lookups,
+                marker_field,
+                stored_locator(&lookups, marker_field),
+                quote! {
+                    .wait(Duration::from_secs(timeout_secs), Duration::from_millis(#poll_ms))
+                    .visible()
+                    .first()
+                    .await
+                },
+                |scope_name| scope_missing(scope_name, thirtyfour),
+            )
+        });
+        let marker_timeouts = marker_names.iter().map(|marker_name| {
+            timeout_error(
+                &struct_options,
+                quote!(#marker_name),
+                quote!(Duration::from_secs(timeout_secs)),
+                quote!("Timed out waiting for page to be ready ({} not visible): {}", #marker_name, e),
+            )
+        });
+        methods.push(quote! {
+            /// Wait until every field marked `role = "load_marker"` is visible.
+            pub async fn wait_until_ready(&self, driver: &#thirtyfour::WebDriver, timeout_secs: u64) -> std::result::Result<(), #error_type> {
+                use std::time::Duration;
+                #(
+                    let marker = #marker_waits;
+                    marker.map_err(|e| #marker_timeouts)?;
+                )*
+                #check_on_page
+                Ok(())
+            }
+        });
+    }
+
+    if !overlay_fields.is_empty() {
+        methods.push(quote! {
+            /// Click every displayed field marked `role = "overlay"` to dismiss it.
+            ///
+            /// Returns the number of overlays that were dismissed.
+            pub async fn dismiss_overlays(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<usize, #error_type> {
+                let mut dismissed = 0;
+                #(
+                    if let Some(element) = self.#overlay_fields(driver).await {
+                        if element.is_displayed().await.unwrap_or(false) {
+                            element.click().await
+                                .map_err(|e| #driver_error(format!("Failed to dismiss overlay: {}", e) #driver_source))?;
+                            dismissed += 1;
+                        }
+                    }
+                )*
+                Ok(dismissed)
+            }
+        });
+    }
+
+    // Items emitted next to the impl block
+
+    for (first, second) in &struct_options.races {
+        let mut variants = Vec::new();
+        for field in [first, second] {
+            match enum_fields
+                .iter()
+                .find(|(field_ident, _)| field_ident == field)
+            {
+                Some((_, variant)) => variants.push(variant.clone()),
+                None => {
+                    return syn::Error::new(
+                        field.span(),
+                        format!("race field '{}' not found in struct", field),
+                    )
+                    .to_compile_error();
+                }
+            }
+        }
+        let (first_variant, second_variant) = (&variants[0], &variants[1]);
+        let (first_name, second_name) = (first.to_string(), second.to_string());
+        let outcome_ident = syn::Ident::new(
+            &format!("{}{}Or{}", struct_name, first_variant, second_variant),
+            first.span(),
+        );
+        let race_fn_ident = syn::Ident::new(
+            &format!("race_{}_vs_{}", first.unraw(), second.unraw()),
+            first.span(),
+        );
+        let outcome_doc = format!(" Which of `{}` or `{}` appeared first.", first, second);
+        let (first_lookup, second_lookup) = (
+            scoped_query(
+                &lookups,
+                first,
+                stored_locator(&lookups, first),
+                quote!(.nowait().first_opt().await),
+                |_| quote!(Ok(None)),
+            ),
+            scoped_query(
+                &lookups,
+                second,
+                stored_locator(&lookups, second),
+                quote!(.nowait().first_opt().await),
+                |_| quote!(Ok(None)),
+            ),
+        );
+        let timeout = timeout_error(
+            &struct_options,
+            quote!(format!("{} or {}", #first_name, #second_name)),
+            quote!(Duration::from_secs(timeout_secs)),
+            quote! {
+                "Timed out waiting for {} ({:?}) or {} ({:?}) to be visible",
+                #first_name, self.#first, #second_name, self.#second
+            },
+        );
+        items.push(quote! {
+            #[doc = #outcome_doc]
+            #[derive(Debug, Clone)]
+            #struct_vis enum #outcome_ident {
+                #first_variant(#thirtyfour::WebElement),
+                #second_variant(#thirtyfour::WebElement),
+            }
+        });
+        methods.push(quote! {
+            /// Wait until either element is visible and report which one appeared first.
+            pub async fn #race_fn_ident(&self, driver: &#thirtyfour::WebDriver, timeout_secs: u64) -> std::result::Result<#outcome_ident, #error_type> {
+                use std::time::{Duration, Instant};
+                let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+                loop {
+                    if let Ok(Some(element)) = #first_lookup {
+                        if element.is_displayed().await.unwrap_or(false) {
+                            return Ok(#outcome_ident::#first_variant(element));
                         }
-                        "has_class" => {
-                            let has_class_fn_ident = syn::Ident::new(
-                                &format!("has_class_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Check if the element has a specific CSS class.
-                                pub async fn #has_class_fn_ident(&self, driver: &thirtyfour::WebDriver, class_name: &str) -> anyhow::Result<bool> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            let class_attr = element.attr("class").await
-                                                .map_err(|e| anyhow::anyhow!("Failed to get class attribute from {}: {}", #field_name_str, e))?;
-
-                                            match class_attr {
-                                                Some(classes) => {
-                                                    let class_list: Vec<&str> = classes.split_whitespace().collect();
-                                                    Ok(class_list.contains(&class_name))
-                                                },
-                                                None => Ok(false)
-                                            }
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
+                    }
+                    if let Ok(Some(element)) = #second_lookup {
+                        if element.is_displayed().await.unwrap_or(false) {
+                            return Ok(#outcome_ident::#second_variant(element));
                         }
+                    }
+                    if Instant::now() >= deadline {
+                        return Err(#timeout);
+                    }
+                    #tokio::time::sleep(Duration::from_millis(#poll_ms)).await;
+                }
+            }
+        });
+    }
 
-                        // Element state checks
-                        "is_displayed" => {
-                            let is_displayed_fn_ident = syn::Ident::new(
-                                &format!("is_displayed_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Check if the web element is displayed.
-                                pub async fn #is_displayed_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<bool> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            element.is_displayed().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to check if {} is displayed: {}", #field_name_str, e))
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
+    let field_enum_ident = syn::Ident::new(&format!("{}Field", struct_name), struct_name.span());
+    if struct_options.field_enum {
+        // Read each field through its query method, as its actions do
+        let (query_fns, query_variants): (Vec<_>, Vec<_>) = enum_fields
+            .iter()
+            .map(|(field, variant)| (lookups.naming.method_ident("query", field, ""), variant))
+            .unzip();
+        let query_field = quote! {
+            match field {
+                #(#field_enum_ident::#query_variants => self.#query_fns(driver).await,)*
+            }
+        };
+        // Polling combinators query once per round, without a field's own `timeout_secs` wait
+        let query_once_fns: Vec<_> = query_fns
+            .iter()
+            .map(|query_fn| syn::Ident::new(&format!("{}_with_timeout", query_fn), query_fn.span()))
+            .collect();
+        let query_field_once = quote! {
+            match field {
+                #(#field_enum_ident::#query_variants => self.#query_once_fns(driver, 0).await,)*
+            }
+        };
+        let any_timeout = timeout_error(
+            &struct_options,
+            quote!(names.join(", ")),
+            quote!(Duration::from_secs(timeout_secs)),
+            quote!(
+                "Timed out waiting for any of [{}] to be visible",
+                names.join(", ")
+            ),
+        );
+        let all_timeout = timeout_error(
+            &struct_options,
+            quote!(field.name()),
+            quote!(Duration::from_secs(timeout_secs)),
+            quote!("Timed out waiting for {} to be visible", field.name()),
+        );
+        let not_found = not_found_error(
+            &struct_options,
+            quote!(field.name()),
+            quote!(self.locator(field)),
+        );
+        // Body querying the given fields concurrently, collecting the ones found
+        let resolve = |include_lazy: bool| {
+            let variants: Vec<_> = enum_fields
+                .iter()
+                .filter(|(field, _)| include_lazy || !lazy_fields.contains(field))
+                .map(|(_, variant)| variant)
+                .collect();
+            if variants.is_empty() {
+                return quote! {
+                    let _ = driver;
+                    std::collections::HashMap::new()
+                };
+            }
+            quote! {
+                use std::task::Poll;
+                let mut queries: Vec<_> = [#(#field_enum_ident::#variants),*]
+                    .into_iter()
+                    .map(|field| Some(Box::pin(async move { (field, #query_field) })))
+                    .collect();
+                let mut elements = std::collections::HashMap::new();
+                std::future::poll_fn(|cx| {
+                    let mut done = true;
+                    for slot in queries.iter_mut() {
+                        if let Some(query) = slot {
+                            match std::future::Future::poll(query.as_mut(), cx) {
+                                Poll::Ready((field, element)) => {
+                                    if let Some(element) = element {
+                                        elements.insert(field, element);
                                     }
+                                    *slot = None;
                                 }
-                            };
-                            methods.push(method);
+                                Poll::Pending => done = false,
+                            }
                         }
-                        "is_selected" => {
-                            let is_selected_fn_ident = syn::Ident::new(
-                                &format!("is_selected_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Check if the web element is selected.
-                                pub async fn #is_selected_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<bool> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            element.is_selected().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to check if {} is selected: {}", #field_name_str, e))
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
+                    }
+                    if done { Poll::Ready(()) } else { Poll::Pending }
+                })
+                .await;
+                elements
+            }
+        };
+        let (resolve_eager, resolve_all) = (resolve(false), resolve(true));
+        methods.push(quote! {
+            /// Query every field not marked `lazy` concurrently, returning the elements found.
+            pub async fn resolve_all(&self, driver: &#thirtyfour::WebDriver) -> std::collections::HashMap<#field_enum_ident, #thirtyfour::WebElement> {
+                #resolve_eager
+            }
+
+            /// Query every field concurrently, including `lazy` ones, returning the elements found.
+            pub async fn resolve_all_include_lazy(&self, driver: &#thirtyfour::WebDriver) -> std::collections::HashMap<#field_enum_ident, #thirtyfour::WebElement> {
+                #resolve_all
+            }
+
+            /// Wait until any of `fields` is visible, returning the first one found.
+            pub async fn wait_for_any(&self, driver: &#thirtyfour::WebDriver, fields: &[#field_enum_ident], timeout_secs: u64) -> std::result::Result<(#field_enum_ident, #thirtyfour::WebElement), #error_type> {
+                use std::time::{Duration, Instant};
+                let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+                loop {
+                    for field in fields.iter().copied() {
+                        if let Some(element) = #query_field_once {
+                            if element.is_displayed().await.unwrap_or(false) {
+                                return Ok((field, element));
+                            }
                         }
-                        "is_enabled" => {
-                            let is_enabled_fn_ident = syn::Ident::new(
-                                &format!("is_enabled_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Check if the web element is enabled.
-                                pub async fn #is_enabled_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<bool> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            element.is_enabled().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to check if {} is enabled: {}", #field_name_str, e))
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
+                    }
+                    if Instant::now() >= deadline {
+                        let names: Vec<&str> = fields.iter().map(|field| field.name()).collect();
+                        return Err(#any_timeout);
+                    }
+                    #tokio::time::sleep(Duration::from_millis(#poll_ms)).await;
+                }
+            }
+
+            /// Wait until all of `fields` are visible, returning their elements in order.
+            pub async fn wait_for_all(&self, driver: &#thirtyfour::WebDriver, fields: &[#field_enum_ident], timeout_secs: u64) -> std::result::Result<Vec<#thirtyfour::WebElement>, #error_type> {
+                use std::time::{Duration, Instant};
+                let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+                let mut elements = Vec::with_capacity(fields.len());
+                for field in fields.iter().copied() {
+                    let element = loop {
+                        if let Some(element) = #query_field_once {
+                            if element.is_displayed().await.unwrap_or(false) {
+                                break element;
+                            }
                         }
-                        "exists" => {
-                            let exists_fn_ident = syn::Ident::new(
-                                &format!("exists_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Check if the element exists in the DOM without throwing an error.
-                                pub async fn #exists_fn_ident(&self, driver: &thirtyfour::WebDriver) -> bool {
-                                    match driver.query(self.#field_ident.clone()).exists().await {
-                                        Ok(exists) => exists,
-                                        Err(_) => false
-                                    }
-                                }
-                            };
-                            methods.push(method);
+                        if Instant::now() >= deadline {
+                            return Err(#all_timeout);
                         }
+                        #tokio::time::sleep(Duration::from_millis(#poll_ms)).await;
+                    };
+                    elements.push(element);
+                }
+                Ok(elements)
+            }
 
-                        // Select element methods
-                        "select_by_text" => {
-                            let select_text_fn_ident = syn::Ident::new(
-                                &format!("select_by_text_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Select an option from a dropdown by its visible text.
-                                pub async fn #select_text_fn_ident(&self, driver: &thirtyfour::WebDriver, text: &str) -> anyhow::Result<()> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            let select = thirtyfour::components::select::SelectElement::new(&element);
-                                            select.select_by_visible_text(text).await
-                                                .map_err(|e| anyhow::anyhow!("Failed to select text '{}' in {}: {}", text, #field_name_str, e))?;
-                                            Ok(())
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
+            /// Read the text of each of `fields` concurrently.
+            pub async fn get_texts_of(&self, driver: &#thirtyfour::WebDriver, fields: &[#field_enum_ident]) -> std::result::Result<std::collections::HashMap<#field_enum_ident, String>, #error_type> {
+                use std::task::Poll;
+                let mut reads: Vec<_> = fields
+                    .iter()
+                    .copied()
+                    .map(|field| {
+                        Some(Box::pin(async move {
+                            let element = #query_field;
+                            let text = match element {
+                                Some(element) => element.text().await
+                                    .map_err(|e| #driver_error(format!("Failed to get text from {}: {}", field.name(), e) #driver_source)),
+                                None => Err(#not_found),
                             };
-                            methods.push(method);
-                        }
-                        "select_by_value" => {
-                            let select_value_fn_ident = syn::Ident::new(
-                                &format!("select_by_value_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Select an option from a dropdown by its value attribute.
-                                pub async fn #select_value_fn_ident(&self, driver: &thirtyfour::WebDriver, value: &str) -> anyhow::Result<()> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            let select = thirtyfour::components::select::SelectElement::new(&element);
-                                            select.select_by_value(value).await
-                                                .map_err(|e| anyhow::anyhow!("Failed to select value '{}' in {}: {}", value, #field_name_str, e))?;
-                                            Ok(())
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
-                        }
-                        "select_by_index" => {
-                            let select_index_fn_ident = syn::Ident::new(
-                                &format!("select_by_index_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Select an option from a dropdown by its index.
-                                pub async fn #select_index_fn_ident(&self, driver: &thirtyfour::WebDriver, index: usize) -> anyhow::Result<()> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            let select = thirtyfour::components::select::SelectElement::new(&element);
-                                            select.select_by_index(index).await
-                                                .map_err(|e| anyhow::anyhow!("Failed to select index {} in {}: {}", index, #field_name_str, e))?;
-                                            Ok(())
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
+                            (field, text)
+                        }))
+                    })
+                    .collect();
+                let mut texts = std::collections::HashMap::with_capacity(fields.len());
+                let mut failure = None;
+                std::future::poll_fn(|cx| {
+                    let mut done = true;
+                    for slot in reads.iter_mut() {
+                        if let Some(read) = slot {
+                            match std::future::Future::poll(read.as_mut(), cx) {
+                                Poll::Ready((field, Ok(text))) => {
+                                    texts.insert(field, text);
+                                    *slot = None;
                                 }
-                            };
-                            methods.push(method);
-                        }
-                        "get_selected_text" => {
-                            let get_selected_fn_ident = syn::Ident::new(
-                                &format!("get_selected_text_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Get the text of the currently selected option in a dropdown.
-                                pub async fn #get_selected_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<String> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            let select = thirtyfour::components::select::SelectElement::new(&element);
-                                            select.first_selected_option().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to get selected option in {}: {}", #field_name_str, e))?
-                                                .text().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to get text of selected option in {}: {}", #field_name_str, e))
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
+                                Poll::Ready((_, Err(e))) => {
+                                    failure.get_or_insert(e);
+                                    *slot = None;
                                 }
-                            };
-                            methods.push(method);
+                                Poll::Pending => done = false,
+                            }
                         }
+                    }
+                    if done { Poll::Ready(()) } else { Poll::Pending }
+                })
+                .await;
+                match failure {
+                    Some(e) => Err(e),
+                    None => Ok(texts),
+                }
+            }
+        });
+    }
 
-                        // Visibility and waiting methods
-                        "scroll_to" => {
-                            let scroll_fn_ident = syn::Ident::new(
-                                &format!("scroll_to_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Scroll the element into view.
-                                pub async fn #scroll_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<()> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            driver.execute(r#"arguments[0].scrollIntoView();"#, vec![element.clone().into()]).await
-                                                .map_err(|e| anyhow::anyhow!("Failed to scroll to {}: {}", #field_name_str, e))?;
-                                            Ok(())
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
-                                }
-                            };
-                            methods.push(method);
+    // Browser context overrides, applied through the Chrome DevTools Protocol
+    let mut context_overrides = Vec::new();
+    if let Some(Geolocation {
+        latitude,
+        longitude,
+        accuracy,
+    }) = struct_options.geolocation
+    {
+        context_overrides.push(quote! {
+            dev_tools.execute_cdp_with_params(
+                "Emulation.setGeolocationOverride",
+                #serde_json::json!({ "latitude": #latitude, "longitude": #longitude, "accuracy": #accuracy }),
+            ).await
+                .map_err(|e| #driver_error(format!("Failed to override geolocation: {}", e) #driver_source))?;
+        });
+    }
+    if let Some(timezone) = &struct_options.timezone {
+        context_overrides.push(quote! {
+            dev_tools.execute_cdp_with_params(
+                "Emulation.setTimezoneOverride",
+                #serde_json::json!({ "timezoneId": #timezone }),
+            ).await
+                .map_err(|e| #driver_error(format!("Failed to override timezone: {}", e) #driver_source))?;
+        });
+    }
+    if let Some(locale) = &struct_options.locale {
+        context_overrides.push(quote! {
+            dev_tools.execute_cdp_with_params(
+                "Emulation.setLocaleOverride",
+                #serde_json::json!({ "locale": #locale }),
+            ).await
+                .map_err(|e| #driver_error(format!("Failed to override locale: {}", e) #driver_source))?;
+        });
+    }
+    if !context_overrides.is_empty() {
+        methods.push(quote! {
+            /// Apply the declared geolocation, timezone and locale overrides to the browser session.
+            ///
+            /// Uses the Chrome DevTools Protocol, so it requires a Chromium-based browser.
+            pub async fn apply_context(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<(), #error_type> {
+                let dev_tools = #thirtyfour::extensions::cdp::ChromeDevTools::new(driver.handle.clone());
+                #(#context_overrides)*
+                Ok(())
+            }
+        });
+    }
+
+    if !struct_options.required_cookies.is_empty() {
+        let required_cookies = &struct_options.required_cookies;
+        methods.push(quote! {
+            /// Make sure every cookie this page requires is present before using it.
+            ///
+            /// Cookies with a value in `provided` are set; the rest must already exist in the
+            /// session, otherwise this fails instead of landing on a login redirect.
+            pub async fn ensure_preconditions(
+                &self,
+                driver: &#thirtyfour::WebDriver,
+                provided: &[(&str, &str)],
+            ) -> std::result::Result<(), #error_type> {
+                for name in [#(#required_cookies),*] {
+                    match provided.iter().find(|(provided_name, _)| *provided_name == name) {
+                        Some((_, value)) => {
+                            driver.add_cookie(#thirtyfour::Cookie::new(name, *value)).await
+                                .map_err(|e| #driver_error(format!("Failed to set required cookie {}: {}", name, e) #driver_source))?;
                         }
-                        "wait_for" => {
-                            let wait_fn_ident = syn::Ident::new(
-                                &format!("wait_for_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Wait for the element to be present and visible with timeout.
-                                pub async fn #wait_fn_ident(&self, driver: &thirtyfour::WebDriver, timeout_secs: u64) -> anyhow::Result<thirtyfour::WebElement> {
-                                    use std::time::Duration;
-                                    driver.query(self.#field_ident.clone())
-                                        .wait(Duration::from_secs(timeout_secs), Duration::from_millis(500))
-                                        .visible()
-                                        .first()
-                                        .await
-                                        .map_err(|e| anyhow::anyhow!("Timed out waiting for {} to be visible: {}", #field_name_str, e))
-                                }
-                            };
-                            methods.push(method);
+                        None => {
+                            driver.get_named_cookie(name).await
+                                .map_err(|e| #driver_error(format!("Required cookie {} is missing: {}", name, e) #driver_source))?;
                         }
-                        "wait_until_clickable" => {
-                            let wait_clickable_fn_ident = syn::Ident::new(
-                                &format!("wait_until_clickable_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Wait until the element is clickable (visible and enabled).
-                                pub async fn #wait_clickable_fn_ident(&self, driver: &thirtyfour::WebDriver, timeout_secs: u64) -> anyhow::Result<thirtyfour::WebElement> {
-                                    use std::time::Duration;
-                                    let element = driver.query(self.#field_ident.clone())
-                                        .wait(Duration::from_secs(timeout_secs), Duration::from_millis(500))
-                                        .visible()
-                                        .first()
-                                        .await
-                                        .map_err(|e| anyhow::anyhow!("Timed out waiting for {} to be visible: {}", #field_name_str, e))?;
-
-                                    // Check if enabled
-                                    if !element.is_enabled().await
-                                        .map_err(|e| anyhow::anyhow!("Failed to check if {} is enabled: {}", #field_name_str, e))? {
-                                        return Err(anyhow::anyhow!("Element {} is not clickable (disabled)", #field_name_str));
-                                    }
+                    }
+                }
+                Ok(())
+            }
+        });
+    }
 
-                                    Ok(element)
-                                }
-                            };
-                            methods.push(method);
-                        }
-                        "take_screenshot" => {
-                            let screenshot_fn_ident = syn::Ident::new(
-                                &format!("take_screenshot_{}", field_ident),
-                                field_ident.span(),
-                            );
-                            let method = quote! {
-                                /// Take a screenshot of just this element and return the PNG image data as base64.
-                                pub async fn #screenshot_fn_ident(&self, driver: &thirtyfour::WebDriver) -> anyhow::Result<String> {
-                                    match self.#query_fn_ident(driver).await {
-                                        Some(element) => {
-                                            element.screenshot_as_base64().await
-                                                .map_err(|e| anyhow::anyhow!("Failed to take screenshot of {}: {}", #field_name_str, e))
-                                        },
-                                        None => Err(anyhow::anyhow!("Element {} not found", #field_name_str))
-                                    }
+    // Synchronous, so pushed after the async helpers are wrapped
+    let mut resolve_url = None;
+    if let Some(url) = &struct_options.url {
+        let apply_context = if context_overrides.is_empty() {
+            quote!()
+        } else {
+            quote!(self.apply_context(driver).await?;)
+        };
+        // The setup hook runs first, e.g. to seed test data the page will show
+        let apply_context = match &struct_options.before_open {
+            Some(hook) => quote! {
+                #hook(self, driver).await?;
+                #apply_context
+            },
+            None => apply_context,
+        };
+        let placeholders = match url_placeholders(url) {
+            Ok(placeholders) => placeholders,
+            Err(e) => return e.to_compile_error(),
+        };
+        if placeholders.is_empty() {
+            methods.push(quote! {
+                /// Navigate to this page, running any `before_open` hook and applying any declared
+                /// context overrides first.
+                pub async fn open(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<(), #error_type> {
+                    #apply_context
+                    driver.goto(#url).await
+                        .map_err(|e| #driver_error(format!("Failed to open {}: {}", #url, e) #driver_source))?;
+                    #check_on_page
+                    Ok(())
+                }
+            });
+        } else {
+            let env_vars: Vec<String> = placeholders
+                .iter()
+                .map(|name| name.to_uppercase())
+                .collect();
+            resolve_url = Some(quote! {
+                /// Fill the `{placeholder}`s of this page's URL from `params`, falling back to the
+                /// environment variable named after each placeholder in upper case.
+                pub fn resolve_url(&self, params: &[(&str, &str)]) -> std::result::Result<String, #error_type> {
+                    let mut url = String::from(#url);
+                    for (placeholder, env_var) in [#((#placeholders, #env_vars)),*] {
+                        let value = match params.iter().find(|(name, _)| *name == placeholder) {
+                            Some((_, value)) => value.to_string(),
+                            None => std::env::var(env_var).map_err(|_| #message_error(format!(
+                                "No value for url placeholder {{{}}}: pass it to open_with or set {}",
+                                placeholder,
+                                env_var
+                            )))?,
+                        };
+                        url = url.replace(&format!("{{{}}}", placeholder), &value);
+                    }
+                    Ok(url)
+                }
+            });
+            methods.push(quote! {
+                /// Navigate to this page with its URL placeholders filled from environment variables,
+                /// running any `before_open` hook and applying any declared context overrides first.
+                pub async fn open(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<(), #error_type> {
+                    let url = self.resolve_url(&[])?;
+                    #apply_context
+                    driver.goto(&url).await
+                        .map_err(|e| #driver_error(format!("Failed to open {}: {}", url, e) #driver_source))?;
+                    #check_on_page
+                    Ok(())
+                }
+
+                /// Navigate to this page with its URL placeholders filled from `params` (or
+                /// environment variables), running any `before_open` hook and applying any declared
+                /// context overrides first.
+                pub async fn open_with(&self, driver: &#thirtyfour::WebDriver, params: &[(&str, &str)]) -> std::result::Result<(), #error_type> {
+                    let url = self.resolve_url(params)?;
+                    #apply_context
+                    driver.goto(&url).await
+                        .map_err(|e| #driver_error(format!("Failed to open {}: {}", url, e) #driver_source))?;
+                    #check_on_page
+                    Ok(())
+                }
+            });
+        }
+    }
+
+    if struct_options.alerts {
+        methods.push(quote! {
+            /// Get the text of the currently open alert, confirm or prompt.
+            pub async fn get_alert_text(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<String, #error_type> {
+                driver.get_alert_text().await
+                    .map_err(|e| #driver_error(format!("Failed to get alert text: {}", e) #driver_source))
+            }
+
+            /// Accept the currently open alert or confirm.
+            pub async fn accept_alert(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<(), #error_type> {
+                driver.accept_alert().await
+                    .map_err(|e| #driver_error(format!("Failed to accept alert: {}", e) #driver_source))
+            }
+
+            /// Dismiss the currently open alert, confirm or prompt.
+            pub async fn dismiss_alert(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<(), #error_type> {
+                driver.dismiss_alert().await
+                    .map_err(|e| #driver_error(format!("Failed to dismiss alert: {}", e) #driver_source))
+            }
+
+            /// Type `text` into the currently open `window.prompt` and accept it.
+            pub async fn answer_prompt(&self, driver: &#thirtyfour::WebDriver, text: &str) -> std::result::Result<(), #error_type> {
+                driver.send_alert_text(text).await
+                    .map_err(|e| #driver_error(format!("Failed to answer prompt: {}", e) #driver_source))?;
+                driver.accept_alert().await
+                    .map_err(|e| #driver_error(format!("Failed to accept prompt: {}", e) #driver_source))
+            }
+        });
+    }
+
+    if let Err(e) = wrap_methods(
+        &mut methods[first_struct_method..],
+        &struct_options,
+        &struct_name.to_string(),
+        None,
+        false,
+    ) {
+        return e.to_compile_error();
+    }
+    if let Some(collector) = &struct_options.soft_assert
+        && let Err(e) = soften_methods(
+            &mut methods[first_struct_method..],
+            collector,
+            &struct_name.to_string(),
+            |name| name.starts_with("assert_"),
+        )
+    {
+        return e.to_compile_error();
+    }
+    if let Some(timeout_secs) = struct_options.timeout_secs {
+        match default_timeout_methods(&methods[first_struct_method..], timeout_secs) {
+            Ok(defaults) => methods.extend(defaults),
+            Err(e) => return e.to_compile_error(),
+        }
+    }
+
+    methods.extend(resolve_url);
+
+    // Runs already wrapped actions, so it is not wrapped itself
+    if struct_options.broadcast {
+        methods.push(quote! {
+            /// Run `action` on every driver concurrently, e.g. to simulate several users at once.
+            ///
+            /// Returns each driver's result, in the order of `drivers`:
+            /// `page.broadcast(&drivers, |page, driver| page.click_submit(driver)).await`
+            pub async fn broadcast<'a, F, Fut, T>(&'a self, drivers: &'a [#thirtyfour::WebDriver], action: F) -> Vec<T>
+            where
+                F: Fn(&'a Self, &'a #thirtyfour::WebDriver) -> Fut,
+                Fut: std::future::Future<Output = T>,
+            {
+                use std::task::Poll;
+                let mut pending: Vec<_> = drivers
+                    .iter()
+                    .map(|driver| Some(Box::pin(action(self, driver))))
+                    .collect();
+                let mut results: Vec<Option<T>> = drivers.iter().map(|_| None).collect();
+                std::future::poll_fn(|cx| {
+                    let mut done = true;
+                    for (slot, result) in pending.iter_mut().zip(results.iter_mut()) {
+                        if let Some(future) = slot {
+                            match std::future::Future::poll(future.as_mut(), cx) {
+                                Poll::Ready(value) => {
+                                    *result = Some(value);
+                                    *slot = None;
                                 }
-                            };
-                            methods.push(method);
+                                Poll::Pending => done = false,
+                            }
                         }
+                    }
+                    if done { Poll::Ready(()) } else { Poll::Pending }
+                })
+                .await;
+                results
+                    .into_iter()
+                    .map(|result| result.expect("every action completed"))
+                    .collect()
+            }
+        });
+    }
 
-                        // If the method isn't supported, generate a compile-time error
-                        _ => {
-                            return syn::Error::new(
-                                field_ident.span(),
-                                format!(
-                                    "Unsupported thirtyfour_actions method: '{}' for field {}",
-                                    method_name, field_name_str
-                                ),
-                            )
-                            .to_compile_error()
-                            .into();
-                        }
+    if let Some(max_per_second) = struct_options.rate_limit {
+        let runtime = runtime_path();
+        let initial = match max_per_second {
+            Some(max_per_second) => quote!(Some(#max_per_second)),
+            None => quote!(None),
+        };
+        methods.push(quote! {
+            /// The rate limiter shared by every instance of this page object.
+            ///
+            /// Use `set_max_per_second` to change the limit at runtime.
+            pub fn rate_limiter() -> &'static #runtime::RateLimiter {
+                static RATE_LIMITER: #runtime::RateLimiter = #runtime::RateLimiter::new(#initial);
+                &RATE_LIMITER
+            }
+        });
+    }
+
+    if let Some(enabled) = struct_options.headless_aware {
+        let runtime = runtime_path();
+        let initial = match enabled {
+            Some(enabled) => quote!(Some(#enabled)),
+            None => quote!(None),
+        };
+        methods.push(quote! {
+            /// The headless switch shared by every instance of this page object.
+            ///
+            /// While it is on, actions skip pacing, rate limiting and hover dwell. Unless set, it
+            /// is on when the `CI` environment variable is set.
+            pub fn headless() -> &'static #runtime::HeadlessSwitch {
+                static HEADLESS: #runtime::HeadlessSwitch = #runtime::HeadlessSwitch::new(#initial);
+                &HEADLESS
+            }
+        });
+    }
+
+    if let Some(enabled) = struct_options.log_success {
+        methods.push(quote! {
+            /// Whether successful actions of every instance of this page object are logged at
+            /// debug level.
+            ///
+            /// Store `false` or `true` to toggle the logs at runtime, e.g. around the part of a
+            /// long flow being triaged.
+            pub fn success_logging() -> &'static std::sync::atomic::AtomicBool {
+                static SUCCESS_LOGGING: std::sync::atomic::AtomicBool =
+                    std::sync::atomic::AtomicBool::new(#enabled);
+                &SUCCESS_LOGGING
+            }
+        });
+    }
+
+    if let Some(enabled) = struct_options.dry_run {
+        let runtime = runtime_path();
+        methods.push(quote! {
+            /// The dry-run switch shared by every instance of this page object.
+            ///
+            /// While it is enabled, interactions check that their element exists, is visible and
+            /// is enabled, then record what they would have done instead of doing it.
+            pub fn dry_run() -> &'static #runtime::DryRun {
+                static DRY_RUN: #runtime::DryRun = #runtime::DryRun::new(#enabled);
+                &DRY_RUN
+            }
+        });
+    }
+
+    if let Some(audit_field) = &struct_options.session_audit {
+        methods.push(quote! {
+            /// Bind this instance to the session of `driver`, so actions called with any other
+            /// session fail. Without it, the first action binds the instance.
+            pub fn bind_session(&self, driver: &#thirtyfour::WebDriver) {
+                self.#audit_field.bind(driver.session_id().to_string());
+            }
+        });
+    }
+
+    if cfg!(feature = "debug-tools") {
+        let struct_name_str = struct_name.to_string();
+        let debug_field_idents: Vec<_> = enum_fields.iter().map(|(field, _)| field).collect();
+        let debug_field_names: Vec<String> = debug_field_idents
+            .iter()
+            .map(|field| field.unraw().to_string())
+            .collect();
+        let (debug_action_cfgs, debug_action_names, debug_action_calls) =
+            debug_actions.into_iter().fold(
+                (Vec::new(), Vec::new(), Vec::new()),
+                |(mut cfgs, mut names, mut calls), (cfg, name, call)| {
+                    cfgs.push(cfg);
+                    names.push(name);
+                    calls.push(call);
+                    (cfgs, names, calls)
+                },
+            );
+        methods.push(quote! {
+            /// Print this page's fields and run its actions interactively from stdin.
+            ///
+            /// Blocks the calling task while waiting for input; enter `quit` to return.
+            pub async fn debug_menu(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<(), #error_type> {
+                use std::io::Write;
+                println!("{} fields:", #struct_name_str);
+                #(println!("  {}: {:?}", #debug_field_names, self.#debug_field_idents);)*
+                println!("Actions (enter a name, or `quit` to continue):");
+                #(#debug_action_cfgs println!("  {}", #debug_action_names);)*
+                loop {
+                    print!("> ");
+                    std::io::stdout().flush()
+                        .map_err(|e| #driver_error(format!("Failed to write to stdout: {}", e) #driver_source))?;
+                    let mut line = String::new();
+                    let read = std::io::stdin().read_line(&mut line)
+                        .map_err(|e| #driver_error(format!("Failed to read from stdin: {}", e) #driver_source))?;
+                    if read == 0 {
+                        break;
+                    }
+                    match line.trim() {
+                        "" => {}
+                        "quit" | "q" => break,
+                        #(#debug_action_cfgs #debug_action_names => #debug_action_calls,)*
+                        other => println!("unknown action '{}'", other),
                     }
                 }
+                Ok(())
             }
+        });
+    }
+
+    let map_field_idents: Vec<_> = enum_fields
+        .iter()
+        .map(|(field, _)| field)
+        .chain(&template_fields)
+        .collect();
+    let map_field_names: Vec<String> = map_field_idents
+        .iter()
+        .map(|field| field.unraw().to_string())
+        .collect();
+    let built_entries = built_fields.iter().map(|field| {
+        let name = field.unraw().to_string();
+        let builder = &struct_options.locator_builder;
+        quote!((#name, #builder(&self.#field)))
+    });
+    methods.push(quote! {
+        /// Map the name of each locator field to its selector, e.g. for reporting which
+        /// locators a page uses.
+        ///
+        /// `resolver`, `skip` and `component` fields have no selector of their own and are left out.
+        pub fn selector_map(&self) -> std::collections::HashMap<&'static str, #thirtyfour::By> {
+            std::collections::HashMap::from([
+                #((#map_field_names, #thirtyfour::By::clone(&self.#map_field_idents)),)*
+                #(#built_entries,)*
+            ])
         }
-    } else {
-        return syn::Error::new(
-            input_span,
-            "ImplThirtyfourActions can only be derived for structs",
+    });
+
+    if !count_checks.is_empty() {
+        methods.push(quote! {
+            /// Check that every field with `expect_count` matches as many elements as expected,
+            /// catching selectors that silently started matching several elements, or none.
+            ///
+            /// Fails with every mismatch found.
+            pub async fn validate_counts(&self, driver: &#thirtyfour::WebDriver) -> std::result::Result<(), #error_type> {
+                let mut mismatches: Vec<String> = Vec::new();
+                #(#count_checks)*
+                if mismatches.is_empty() {
+                    Ok(())
+                } else {
+                    Err(#message_error(format!("Unexpected match counts: {}", mismatches.join("; "))))
+                }
+            }
+        });
+    }
+
+    if struct_options.field_enum {
+        let (enum_field_idents, enum_variants): (Vec<_>, Vec<_>) = enum_fields.into_iter().unzip();
+        let enum_field_names: Vec<String> = enum_field_idents
+            .iter()
+            .map(|ident| ident.unraw().to_string())
+            .collect();
+        let enum_doc = format!(" Fields of [`{}`].", struct_name);
+        items.push(quote! {
+            #[doc = #enum_doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #struct_vis enum #field_enum_ident {
+                #(#enum_variants,)*
+            }
+
+            impl #field_enum_ident {
+                /// Every field, in declaration order.
+                pub const ALL: &'static [Self] = &[#(Self::#enum_variants),*];
+
+                /// The field's name as declared in the struct.
+                pub fn name(&self) -> &'static str {
+                    match self {
+                        #(Self::#enum_variants => #enum_field_names,)*
+                    }
+                }
+            }
+        });
+        if struct_options.perform {
+            let runtime = runtime_path();
+            let (perform_cfgs, perform_variants, perform_names, perform_calls) =
+                perform_actions.into_iter().fold(
+                    (Vec::new(), Vec::new(), Vec::new(), Vec::new()),
+                    |(mut cfgs, mut variants, mut names, mut calls), (cfg, variant, name, call)| {
+                        cfgs.push(cfg);
+                        variants.push(variant);
+                        names.push(name);
+                        calls.push(call);
+                        (cfgs, variants, names, calls)
+                    },
+                );
+            methods.push(quote! {
+                /// Run the zero-argument action named `action` (`click`, `get_text`, ...) on `field`.
+                ///
+                /// Fails when the action is not generated for the field.
+                pub async fn perform(&self, driver: &#thirtyfour::WebDriver, field: #field_enum_ident, action: &str) -> std::result::Result<#runtime::ActionOutput, #error_type> {
+                    match (field, action) {
+                        #(#perform_cfgs (#field_enum_ident::#perform_variants, #perform_names) => Ok(#runtime::ActionOutput::from(#perform_calls)),)*
+                        (field, action) => Err(#message_error(format!("Action {} is not available on field {}", action, field.name()))),
+                    }
+                }
+            });
+        }
+        methods.push(quote! {
+            /// Get the locator of a field.
+            pub fn locator(&self, field: #field_enum_ident) -> &#thirtyfour::By {
+                match field {
+                    #(#field_enum_ident::#enum_variants => &self.#enum_field_idents,)*
+                }
+            }
+        });
+    }
+
+    if struct_options.display {
+        let struct_name_str = struct_name.to_string();
+        let mut config_lines = Vec::new();
+        if let Some(url) = &struct_options.url {
+            config_lines.push(format!("url: {}", url.value()));
+        }
+        if let Some(pattern) = &struct_options.expected_url_pattern {
+            config_lines.push(format!("expected_url_pattern: {}", pattern.value()));
+        }
+        if let Some(hook) = &struct_options.before_open {
+            config_lines.push(format!(
+                "before_open: {}",
+                quote!(#hook).to_string().replace(' ', "")
+            ));
+        }
+        if let Some(max_per_second) = struct_options.rate_limit {
+            config_lines.push(match max_per_second {
+                Some(max_per_second) => format!("rate_limit: {} per second", max_per_second),
+                None => "rate_limit: unlimited".to_string(),
+            });
+        }
+        if struct_options.session_lock {
+            config_lines.push("session_lock".to_string());
+        }
+        if let Some(enabled) = struct_options.headless_aware {
+            config_lines.push(match enabled {
+                Some(enabled) => format!("headless_aware: {}", enabled),
+                None => "headless_aware: detected".to_string(),
+            });
+        }
+        if let Some(dwell_ms) = struct_options.hover_dwell_ms {
+            config_lines.push(format!("hover_dwell_ms: {}", dwell_ms));
+        }
+        if let Some(threshold_ms) = struct_options.slow_threshold_ms {
+            config_lines.push(format!("slow_threshold_ms: {}", threshold_ms));
+        }
+        if let Some(enabled) = struct_options.log_success {
+            config_lines.push(format!("log_success: {}", enabled));
+        }
+        if let Some(enabled) = struct_options.dry_run {
+            config_lines.push(format!("dry_run: {}", enabled));
+        }
+        if let Some(pacing) = &struct_options.wait_between_actions {
+            config_lines.push(format!("wait_between_actions: {} ms", pacing.interval_ms));
+        }
+        if struct_options.session_audit.is_some() {
+            config_lines.push("session_audit".to_string());
+        }
+        if struct_options.structured_errors {
+            config_lines.push("structured_errors".to_string());
+        }
+        if let Some(timeout_secs) = struct_options.timeout_secs {
+            config_lines.push(format!("timeout_secs: {}", timeout_secs));
+        }
+        if let Some(poll_ms) = struct_options.poll_ms {
+            config_lines.push(format!("poll_ms: {}", poll_ms));
+        }
+        let (summary_selectors, summary_names, summary_actions) = summary_fields.into_iter().fold(
+            (Vec::new(), Vec::new(), Vec::new()),
+            |(mut selectors, mut names, mut actions), (selector, name, enabled)| {
+                selectors.push(selector);
+                names.push(name);
+                actions.push(enabled);
+                (selectors, names, actions)
+            },
+        );
+        items.push(quote! {
+            impl #impl_generics std::fmt::Display for #struct_name #ty_generics #where_clause {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    writeln!(f, "{}", #struct_name_str)?;
+                    #(writeln!(f, "  {}: {:?} [{}]", #summary_names, #summary_selectors, #summary_actions)?;)*
+                    #(writeln!(f, "  {}", #config_lines)?;)*
+                    Ok(())
+                }
+            }
+        });
+    }
+
+    if let Some(vis) = &struct_options.vis {
+        for (index, method) in methods.iter_mut().enumerate() {
+            if field_vis_methods.iter().any(|range| range.contains(&index)) {
+                continue;
+            }
+            if let Err(e) = restrict_methods(std::slice::from_mut(method), vis) {
+                return e.to_compile_error();
+            }
+        }
+    }
+
+    if let Err(e) = check_name_collisions(&methods) {
+        return e.to_compile_error();
+    }
+
+    if let Some(driver_field) = &struct_options.driver
+        && let Err(e) = bind_driver_methods(
+            &mut methods,
+            driver_field,
+            &struct_options.crate_paths.thirtyfour,
         )
-        .to_compile_error()
-        .into();
+    {
+        return e.to_compile_error();
+    }
+
+    if let Some(trait_ident) = &struct_options.as_trait {
+        let (decls, impls) = match trait_methods(&methods, &struct_name) {
+            Ok(trait_methods) => trait_methods,
+            Err(e) => return e.to_compile_error(),
+        };
+        let trait_doc = format!(" Generated actions of [`{}`].", struct_name);
+        items.push(quote! {
+            #[doc = #trait_doc]
+            #[allow(clippy::type_complexity)]
+            #struct_vis trait #trait_ident {
+                #(#decls)*
+            }
+
+            impl #impl_generics #trait_ident for #struct_name #ty_generics #where_clause {
+                #(#impls)*
+            }
+        });
     }
 
     let expanded = quote! {
-        impl #struct_name {
+        #(#items)*
+
+        impl #impl_generics #struct_name #ty_generics #where_clause {
             #(#methods)*
         }
     };
 
-    TokenStream::from(expanded)
+    if renamed_members.is_empty() {
+        expanded
+    } else {
+        rename_members(expanded, &renamed_members)
+    }
+}
+
+/// Emits the `thirtyfour_actions_runtime` support module used by generated code.
+///
+/// Invoke once at the crate root when using options that need shared runtime types:
+///     impl_thirtyfour_actions::thirtyfour_actions_runtime!();
+///
+/// It takes the derive's `crate`, `anyhow`, `log`, `tokio` and `serde_json` path overrides, if any:
+///     impl_thirtyfour_actions::thirtyfour_actions_runtime!(crate = "::my_facade::thirtyfour");
+#[proc_macro]
+pub fn thirtyfour_actions_runtime(input: TokenStream) -> TokenStream {
+    let items =
+        match syn::parse::Parser::parse(Punctuated::<StructItem, Comma>::parse_terminated, input) {
+            Ok(items) => items,
+            Err(e) => return e.to_compile_error().into(),
+        };
+    let mut crate_paths = CratePaths::default();
+    for item in items {
+        match item {
+            StructItem::Crate(_)
+            | StructItem::Anyhow(_)
+            | StructItem::Log(_)
+            | StructItem::Tokio(_)
+            | StructItem::SerdeJson(_) => crate_paths.apply(item),
+            _ => {
+                return syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    "thirtyfour_actions_runtime!() only takes `crate`, `anyhow`, `log`, `tokio` \
+                     and `serde_json` paths",
+                )
+                .to_compile_error()
+                .into();
+            }
+        }
+    }
+
+    TokenStream::from(runtime_module(&crate_paths))
 }
+
+#[cfg(test)]
+mod tests;
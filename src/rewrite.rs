@@ -0,0 +1,98 @@
+//! Token-level rewrites of the finished expansion for fields generated under another name:
+//! renamed fields and the unnamed fields of tuple structs.
+
+use super::*;
+
+/// Make generated code address renamed fields, and the fields of a tuple struct, by their member.
+///
+/// Such fields are generated for under the names in `members`, so `self.<name>` field
+/// accesses and `Self { <name>: ... }` initializers are rewritten to the field's index or
+/// declared name.
+pub(crate) fn rename_members(
+    tokens: TokenStream2,
+    members: &HashMap<String, syn::Member>,
+) -> TokenStream2 {
+    use proc_macro2::{Delimiter, TokenTree};
+
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let is_punct = |i: usize, ch: char| matches!(tokens.get(i), Some(TokenTree::Punct(p)) if p.as_char() == ch);
+    let is_ident =
+        |i: usize, name: &str| matches!(tokens.get(i), Some(TokenTree::Ident(id)) if id == name);
+    let member = |i: usize| match tokens.get(i) {
+        Some(TokenTree::Ident(ident)) => members.get(&ident.to_string()),
+        _ => None,
+    };
+
+    let mut out = TokenStream2::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        // `self.<name>`, but not a method call `self.<name>(...)`
+        if is_ident(i, "self")
+            && is_punct(i + 1, '.')
+            && let Some(index) = member(i + 2)
+            && !matches!(tokens.get(i + 3), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis)
+        {
+            out.extend(tokens[i..i + 2].iter().cloned());
+            out.extend(quote!(#index));
+            i += 3;
+            continue;
+        }
+        match &tokens[i] {
+            TokenTree::Group(group) => {
+                let mut stream = rename_members(group.stream(), members);
+                // A struct expression, not the body of a function returning `Self`
+                let is_initializer = group.delimiter() == Delimiter::Brace
+                    && i > 0
+                    && is_ident(i - 1, "Self")
+                    && !(i > 1 && is_punct(i - 2, '>'));
+                if is_initializer {
+                    stream = rename_initializers(stream, members);
+                }
+                let mut rewritten = proc_macro2::Group::new(group.delimiter(), stream);
+                rewritten.set_span(group.span());
+                out.extend([TokenTree::Group(rewritten)]);
+            }
+            token => out.extend([token.clone()]),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Rewrite the `<name>: value` and shorthand `<name>` fields of a struct expression's body to
+/// the members in `members`
+pub(crate) fn rename_initializers(
+    tokens: TokenStream2,
+    members: &HashMap<String, syn::Member>,
+) -> TokenStream2 {
+    use proc_macro2::{Spacing, TokenTree};
+
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut out = TokenStream2::new();
+    for (position, initializer) in tokens
+        .split(|token| matches!(token, TokenTree::Punct(p) if p.as_char() == ','))
+        .enumerate()
+    {
+        if position > 0 {
+            out.extend(quote!(,));
+        }
+        let index = match initializer.first() {
+            Some(TokenTree::Ident(ident)) => members.get(&ident.to_string()),
+            _ => None,
+        };
+        match (index, initializer.get(1)) {
+            (Some(index), None) => {
+                let name = &initializer[0];
+                out.extend(quote!(#index: #name));
+            }
+            (Some(index), Some(TokenTree::Punct(colon)))
+                if colon.as_char() == ':' && colon.spacing() == Spacing::Alone =>
+            {
+                out.extend(quote!(#index));
+                out.extend(initializer[1..].iter().cloned());
+            }
+            _ => out.extend(initializer.iter().cloned()),
+        }
+    }
+    out
+}
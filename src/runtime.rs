@@ -0,0 +1,689 @@
+//! The `thirtyfour_actions_runtime` support module emitted by `thirtyfour_actions_runtime!()`.
+
+use super::*;
+
+/// Items of the `thirtyfour_actions_runtime` module
+pub(crate) fn runtime_module(crate_paths: &CratePaths) -> TokenStream2 {
+    let CratePaths {
+        thirtyfour,
+        anyhow,
+        tokio,
+        ..
+    } = crate_paths;
+    let image_support = if cfg!(feature = "image") {
+        quote! {
+            /// Output format of re-encoded screenshots.
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub enum ScreenshotFormat {
+                Png,
+                Jpeg,
+                WebP,
+            }
+
+            /// Scale a PNG screenshot down to `max_width` and re-encode it, returning base64.
+            ///
+            /// `quality` applies to JPEG only; WebP is encoded losslessly.
+            pub fn encode_screenshot(
+                png: &[u8],
+                format: ScreenshotFormat,
+                quality: u8,
+                max_width: Option<u32>,
+            ) -> Result<String, image::ImageError> {
+                let mut image = image::load_from_memory(png)?;
+                if let Some(max_width) = max_width {
+                    if image.width() > max_width {
+                        let height = (image.height() as u64 * max_width as u64 / image.width() as u64) as u32;
+                        image = image.resize(max_width, height.max(1), image::imageops::FilterType::Triangle);
+                    }
+                }
+
+                let mut encoded = Vec::new();
+                match format {
+                    ScreenshotFormat::Png => {
+                        image.write_to(&mut std::io::Cursor::new(&mut encoded), image::ImageFormat::Png)?;
+                    }
+                    ScreenshotFormat::Jpeg => {
+                        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut encoded, quality);
+                        image.to_rgb8().write_with_encoder(encoder)?;
+                    }
+                    ScreenshotFormat::WebP => {
+                        let encoder = image::codecs::webp::WebPEncoder::new_lossless(&mut encoded);
+                        image.to_rgba8().write_with_encoder(encoder)?;
+                    }
+                }
+                Ok(base64_encode(&encoded))
+            }
+
+            fn base64_encode(bytes: &[u8]) -> String {
+                const ALPHABET: &[u8; 64] =
+                    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+                let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+                for chunk in bytes.chunks(3) {
+                    let n = (chunk[0] as u32) << 16
+                        | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+                        | *chunk.get(2).unwrap_or(&0) as u32;
+                    for i in 0..4 {
+                        if i <= chunk.len() {
+                            out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+                        } else {
+                            out.push('=');
+                        }
+                    }
+                }
+                out
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    let ocr_support = if cfg!(feature = "ocr") {
+        quote! {
+            /// Text recognition engine used by `read_text_ocr` actions.
+            pub trait OcrEngine: Send + Sync {
+                /// Extract the text rendered in a PNG image.
+                fn recognize(&self, png: &[u8]) -> Result<String, Box<dyn std::error::Error + Send + Sync>>;
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    let (serde_derive, serde_rename_all, serde_transparent) = if cfg!(feature = "serde") {
+        (
+            quote!(#[derive(serde::Serialize, serde::Deserialize)]),
+            quote!(#[serde(rename_all = "snake_case")]),
+            quote!(#[serde(transparent)]),
+        )
+    } else {
+        (quote!(), quote!(), quote!())
+    };
+
+    quote! {
+        /// Runtime support types for `ImplThirtyfourActions`.
+        #[allow(dead_code)]
+        pub mod thirtyfour_actions_runtime {
+            /// Placeholder recorded instead of values passed to `sensitive` fields.
+            pub const REDACTED: &str = "***";
+
+            /// A structured record of a single generated action call.
+            #[derive(Debug, Clone)]
+            pub struct ActionRecord {
+                /// Name of the page-object struct.
+                pub page: &'static str,
+                /// Name of the field acted upon, `None` for struct-level helpers.
+                pub field: Option<&'static str>,
+                /// Name of the generated method.
+                pub method: &'static str,
+                /// Argument names and their rendered (possibly redacted) values.
+                pub args: Vec<(&'static str, String)>,
+                /// Time spent in the call.
+                pub duration: std::time::Duration,
+                /// `Ok` on success, or the rendered error.
+                pub result: Result<(), String>,
+                /// The value returned on success, for methods whose return type converts to
+                /// `ActionOutput` and whose values are not redacted.
+                pub output: Option<ActionOutput>,
+            }
+
+            /// The value returned by a generated action, in one type generic callers such as
+            /// `perform` and action sinks can handle uniformly.
+            #[derive(Debug, Clone, PartialEq)]
+            #serde_derive
+            pub enum ActionOutput {
+                /// The action returned nothing.
+                Unit,
+                /// A text value, such as an element's text.
+                Text(String),
+                /// A yes/no answer, such as whether an element is displayed.
+                Flag(bool),
+                /// An element, by its WebDriver element id.
+                Element(String),
+                /// Several elements, by their WebDriver element ids.
+                Elements(Vec<String>),
+                /// Several text values, such as a class list.
+                Texts(Vec<String>),
+                /// The state gathered by `get_element_state`.
+                State(ElementState),
+                /// The result of a form constraint validation check.
+                Validity { valid: bool, message: String },
+                /// An optional value that was absent.
+                Absent,
+            }
+
+            impl From<()> for ActionOutput {
+                fn from(_: ()) -> Self {
+                    Self::Unit
+                }
+            }
+
+            impl From<String> for ActionOutput {
+                fn from(text: String) -> Self {
+                    Self::Text(text)
+                }
+            }
+
+            impl From<bool> for ActionOutput {
+                fn from(flag: bool) -> Self {
+                    Self::Flag(flag)
+                }
+            }
+
+            impl From<#thirtyfour::WebElement> for ActionOutput {
+                fn from(element: #thirtyfour::WebElement) -> Self {
+                    Self::Element(element.element_id().to_string())
+                }
+            }
+
+            impl From<Vec<#thirtyfour::WebElement>> for ActionOutput {
+                fn from(elements: Vec<#thirtyfour::WebElement>) -> Self {
+                    Self::Elements(elements.iter().map(|element| element.element_id().to_string()).collect())
+                }
+            }
+
+            impl From<Vec<String>> for ActionOutput {
+                fn from(texts: Vec<String>) -> Self {
+                    Self::Texts(texts)
+                }
+            }
+
+            impl From<(bool, String)> for ActionOutput {
+                fn from((valid, message): (bool, String)) -> Self {
+                    Self::Validity { valid, message }
+                }
+            }
+
+            impl From<ElementState> for ActionOutput {
+                fn from(state: ElementState) -> Self {
+                    Self::State(state)
+                }
+            }
+
+            impl<T> From<Option<T>> for ActionOutput
+            where
+                ActionOutput: From<T>,
+            {
+                fn from(value: Option<T>) -> Self {
+                    value.map_or(Self::Absent, Self::from)
+                }
+            }
+
+            /// State of an element as read by `get_element_state_<field>`, for debugging and
+            /// assertion helpers.
+            #[derive(Debug, Clone, Default, PartialEq)]
+            #serde_derive
+            pub struct ElementState {
+                /// Whether the selector matched an element; the other fields keep their
+                /// defaults when it did not.
+                pub exists: bool,
+                pub displayed: bool,
+                pub enabled: bool,
+                /// Whether a checkbox or radio button is checked, or an `<option>` selected.
+                pub selected: bool,
+                /// Position relative to the document and size.
+                pub rect: Option<ElementBounds>,
+                /// The start of the element's rendered text, with whitespace collapsed.
+                pub text: String,
+            }
+
+            /// Position and size of an element, in CSS pixels.
+            #[derive(Debug, Clone, Copy, Default, PartialEq)]
+            #serde_derive
+            pub struct ElementBounds {
+                pub x: f64,
+                pub y: f64,
+                pub width: f64,
+                pub height: f64,
+            }
+
+            /// Kind of failure of a generated method on structs with `structured_errors`.
+            ///
+            /// Generated errors are `anyhow::Error`s wrapping this kind, with the usual message as
+            /// context; get the kind back with `downcast_ref`.
+            #[derive(Debug)]
+            pub enum ThirtyfourActionsError {
+                /// The element's selector matched nothing.
+                NotFound {
+                    element: String,
+                    selector: String,
+                },
+                /// The element did not reach the awaited state in time.
+                Timeout {
+                    element: String,
+                    waited: std::time::Duration,
+                },
+                /// The element was detached from the DOM after it was found.
+                Stale(#thirtyfour::error::WebDriverError),
+                /// Any other WebDriver failure.
+                DriverError(#thirtyfour::error::WebDriverError),
+                /// A failure outside WebDriver, such as an HTTP request or image encoding.
+                Other(String),
+            }
+
+            impl ThirtyfourActionsError {
+                /// Classify an error raised while performing an action.
+                pub fn classify<E: std::fmt::Display + 'static>(error: E) -> Self {
+                    let message = error.to_string();
+                    match (Box::new(error) as Box<dyn std::any::Any>).downcast::<#thirtyfour::error::WebDriverError>() {
+                        Ok(error) => Self::from(*error),
+                        Err(_) => Self::Other(message),
+                    }
+                }
+
+                /// Classify `error` and wrap it in an `anyhow::Error` with `message` as context.
+                pub fn with_context<E: std::fmt::Display + 'static>(message: String, error: E) -> #anyhow::Error {
+                    #anyhow::Error::new(Self::classify(error)).context(message)
+                }
+            }
+
+            impl From<#thirtyfour::error::WebDriverError> for ThirtyfourActionsError {
+                fn from(error: #thirtyfour::error::WebDriverError) -> Self {
+                    match error {
+                        error @ #thirtyfour::error::WebDriverError::StaleElementReference(_) => Self::Stale(error),
+                        error => Self::DriverError(error),
+                    }
+                }
+            }
+
+            impl std::fmt::Display for ThirtyfourActionsError {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        Self::NotFound { element, selector } => write!(f, "Element {} not found ({})", element, selector),
+                        Self::Timeout { element, waited } => write!(f, "Timed out after {:?} waiting for {}", waited, element),
+                        Self::Stale(error) => write!(f, "Stale element reference: {}", error),
+                        Self::DriverError(error) => write!(f, "{}", error),
+                        Self::Other(message) => write!(f, "{}", message),
+                    }
+                }
+            }
+
+            impl std::error::Error for ThirtyfourActionsError {
+                fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                    match self {
+                        Self::Stale(error) | Self::DriverError(error) => Some(error),
+                        _ => None,
+                    }
+                }
+            }
+
+            /// Receives a record of every action call on structs with `#[thirtyfour_actions(sink = "...")]`.
+            pub trait ActionSink: Send + Sync {
+                /// Handle a single action record.
+                fn record(&self, record: ActionRecord);
+            }
+
+            /// Failures collected by soft assertions on structs with
+            /// `#[thirtyfour_actions(soft_assert = "...")]`, reported together by `finish`.
+            #[derive(Debug, Default)]
+            pub struct SoftAssertions {
+                failures: std::sync::Mutex<Vec<String>>,
+            }
+
+            impl SoftAssertions {
+                /// Create an empty collector.
+                pub fn new() -> Self {
+                    Self::default()
+                }
+
+                /// Record a failure.
+                pub fn record(&self, failure: String) {
+                    self.failures.lock().unwrap_or_else(|e| e.into_inner()).push(failure);
+                }
+
+                /// The failures recorded so far.
+                pub fn failures(&self) -> Vec<String> {
+                    self.failures.lock().unwrap_or_else(|e| e.into_inner()).clone()
+                }
+
+                /// Fail with every failure recorded since the last call, if there were any.
+                ///
+                /// The error is a `WebDriverError::CustomError` listing them, which `?` converts into
+                /// `anyhow::Error` or any error type configured with `error = "..."`.
+                pub fn finish(&self) -> Result<(), #thirtyfour::error::WebDriverError> {
+                    let failures = std::mem::take(
+                        &mut *self.failures.lock().unwrap_or_else(|e| e.into_inner()),
+                    );
+                    if failures.is_empty() {
+                        return Ok(());
+                    }
+                    Err(#thirtyfour::error::WebDriverError::CustomError(format!(
+                        "{} soft assertion(s) failed:\n{}",
+                        failures.len(),
+                        failures.iter().map(|failure| format!("  - {}", failure)).collect::<Vec<_>>().join("\n")
+                    )))
+                }
+            }
+
+            /// A selector loaded at runtime, written like the inline field options,
+            /// e.g. `css: "#login"` in YAML or `{ "xpath": "//form" }` in JSON.
+            #[derive(Debug, Clone, PartialEq)]
+            #serde_derive
+            #serde_rename_all
+            pub enum SelectorSpec {
+                /// A CSS selector.
+                Css(String),
+                /// An XPath expression.
+                Xpath(String),
+                /// An element id.
+                Id(String),
+                /// A `name` attribute.
+                Name(String),
+                /// A class name.
+                ClassName(String),
+                /// A tag name.
+                Tag(String),
+                /// The exact text of a link.
+                LinkText(String),
+            }
+
+            impl From<SelectorSpec> for #thirtyfour::By {
+                fn from(spec: SelectorSpec) -> Self {
+                    match spec {
+                        SelectorSpec::Css(selector) => #thirtyfour::By::Css(selector),
+                        SelectorSpec::Xpath(selector) => #thirtyfour::By::XPath(selector),
+                        SelectorSpec::Id(id) => #thirtyfour::By::Id(id),
+                        SelectorSpec::Name(name) => #thirtyfour::By::Name(name),
+                        SelectorSpec::ClassName(class) => #thirtyfour::By::ClassName(class),
+                        SelectorSpec::Tag(tag) => #thirtyfour::By::Tag(tag),
+                        SelectorSpec::LinkText(text) => #thirtyfour::By::LinkText(text),
+                    }
+                }
+            }
+
+            /// Selectors keyed by field name, consumed by the `from_selectors` constructor of
+            /// structs with `#[thirtyfour_actions(from_selectors)]`.
+            ///
+            /// With the `serde` feature it deserializes from a map of field names to selectors,
+            /// so selectors can be kept in JSON, YAML or TOML files.
+            #[derive(Debug, Clone, Default, PartialEq)]
+            #serde_derive
+            #serde_transparent
+            pub struct SelectorConfig {
+                selectors: std::collections::HashMap<String, SelectorSpec>,
+            }
+
+            impl SelectorConfig {
+                /// Create an empty configuration.
+                pub fn new() -> Self {
+                    Self::default()
+                }
+
+                /// Set the selector of `field`, replacing any previous one.
+                pub fn insert(&mut self, field: impl Into<String>, selector: SelectorSpec) {
+                    self.selectors.insert(field.into(), selector);
+                }
+
+                /// The selector of `field`, if configured.
+                pub fn get(&self, field: &str) -> Option<#thirtyfour::By> {
+                    self.selectors.get(field).cloned().map(#thirtyfour::By::from)
+                }
+            }
+
+            impl FromIterator<(String, SelectorSpec)> for SelectorConfig {
+                fn from_iter<I: IntoIterator<Item = (String, SelectorSpec)>>(iter: I) -> Self {
+                    Self {
+                        selectors: iter.into_iter().collect(),
+                    }
+                }
+            }
+
+            #tokio::task_local! {
+                static HELD_SESSIONS: std::collections::HashSet<String>;
+            }
+
+            /// Run `action` while holding the lock for `session_id`.
+            ///
+            /// Locks are shared by all page objects; nested calls on the same task re-use the held lock.
+            pub async fn with_session_lock<F: std::future::Future>(session_id: String, action: F) -> F::Output {
+                static LOCKS: std::sync::OnceLock<
+                    std::sync::Mutex<std::collections::HashMap<String, std::sync::Arc<#tokio::sync::Mutex<()>>>>,
+                > = std::sync::OnceLock::new();
+
+                let mut held = HELD_SESSIONS.try_with(|held| held.clone()).unwrap_or_default();
+                if held.contains(&session_id) {
+                    return action.await;
+                }
+
+                let lock = LOCKS
+                    .get_or_init(Default::default)
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .entry(session_id.clone())
+                    .or_default()
+                    .clone();
+                let _guard = lock.lock().await;
+                held.insert(session_id);
+                HELD_SESSIONS.scope(held, action).await
+            }
+
+            /// Dry-run switch of a page object, recording the interactions skipped while it is on.
+            pub struct DryRun {
+                enabled: std::sync::atomic::AtomicBool,
+                actions: std::sync::Mutex<Vec<String>>,
+            }
+
+            impl DryRun {
+                /// Create a switch, initially on when `enabled` is true.
+                pub const fn new(enabled: bool) -> Self {
+                    DryRun {
+                        enabled: std::sync::atomic::AtomicBool::new(enabled),
+                        actions: std::sync::Mutex::new(Vec::new()),
+                    }
+                }
+
+                /// Turn dry-run mode on or off.
+                pub fn set_enabled(&self, enabled: bool) {
+                    self.enabled.store(enabled, std::sync::atomic::Ordering::Relaxed);
+                }
+
+                /// Whether interactions are currently skipped.
+                pub fn is_enabled(&self) -> bool {
+                    self.enabled.load(std::sync::atomic::Ordering::Relaxed)
+                }
+
+                /// Record a skipped interaction, e.g. `click submit`.
+                pub fn record(&self, action: String) {
+                    self.actions.lock().unwrap_or_else(|e| e.into_inner()).push(action);
+                }
+
+                /// The interactions skipped so far, oldest first.
+                pub fn actions(&self) -> Vec<String> {
+                    self.actions.lock().unwrap_or_else(|e| e.into_inner()).clone()
+                }
+
+                /// Return the interactions skipped so far and forget them.
+                pub fn take_actions(&self) -> Vec<String> {
+                    std::mem::take(&mut *self.actions.lock().unwrap_or_else(|e| e.into_inner()))
+                }
+            }
+
+            /// Spaces out the actions of one page object instance, for
+            /// `#[thirtyfour_actions(wait_between_actions(...))]`.
+            #[derive(Debug, Default)]
+            pub struct ActionPacer {
+                last_finished: std::sync::Mutex<Option<std::time::Instant>>,
+            }
+
+            impl ActionPacer {
+                /// Create a pacer with no action recorded yet.
+                pub fn new() -> Self {
+                    Self::default()
+                }
+
+                /// Run `action` once `interval` has passed since the previous action finished,
+                /// then record when it finished.
+                ///
+                /// Actions started concurrently are not serialized; each only waits for the
+                /// last one to have finished before it started.
+                pub async fn pace<T>(
+                    &self,
+                    interval: std::time::Duration,
+                    action: impl std::future::Future<Output = T>,
+                ) -> T {
+                    let last_finished = *self.last_finished.lock().unwrap_or_else(|e| e.into_inner());
+                    if let Some(last_finished) = last_finished {
+                        let wait = (last_finished + interval)
+                            .saturating_duration_since(std::time::Instant::now());
+                        if !wait.is_zero() {
+                            #tokio::time::sleep(wait).await;
+                        }
+                    }
+                    let output = action.await;
+                    *self.last_finished.lock().unwrap_or_else(|e| e.into_inner()) =
+                        Some(std::time::Instant::now());
+                    output
+                }
+            }
+
+            /// Headless switch of a page object, for `#[thirtyfour_actions(headless_aware)]`.
+            pub struct HeadlessSwitch {
+                /// 0 until known, then 1 when off and 2 when on
+                state: std::sync::atomic::AtomicU8,
+            }
+
+            impl HeadlessSwitch {
+                /// Create a switch, detected from the `CI` environment variable on first use
+                /// unless `enabled` forces it.
+                pub const fn new(enabled: Option<bool>) -> Self {
+                    let state = match enabled {
+                        None => 0,
+                        Some(false) => 1,
+                        Some(true) => 2,
+                    };
+                    HeadlessSwitch {
+                        state: std::sync::atomic::AtomicU8::new(state),
+                    }
+                }
+
+                /// Turn headless behavior on or off.
+                pub fn set_enabled(&self, enabled: bool) {
+                    self.state
+                        .store(if enabled { 2 } else { 1 }, std::sync::atomic::Ordering::Relaxed);
+                }
+
+                /// Whether pacing, rate limiting and hover dwell are currently skipped.
+                pub fn is_enabled(&self) -> bool {
+                    match self.state.load(std::sync::atomic::Ordering::Relaxed) {
+                        0 => {
+                            let enabled = std::env::var("CI")
+                                .is_ok_and(|ci| !matches!(ci.as_str(), "" | "0" | "false"));
+                            self.set_enabled(enabled);
+                            enabled
+                        }
+                        state => state == 2,
+                    }
+                }
+
+                /// Turn the switch on when `driver`'s browser reports a headless user agent, and
+                /// return whether it is on.
+                pub async fn detect(&self, driver: &#thirtyfour::WebDriver) -> bool {
+                    let headless = match driver.execute("return navigator.userAgent", Vec::new()).await {
+                        Ok(ret) => ret.convert::<String>().is_ok_and(|agent| agent.contains("Headless")),
+                        Err(_) => false,
+                    };
+                    if headless {
+                        self.set_enabled(true);
+                    }
+                    self.is_enabled()
+                }
+            }
+
+            /// Remembers the driver session a page object instance is used with, for
+            /// `#[thirtyfour_actions(session_audit = "...")]`.
+            #[derive(Debug, Default)]
+            pub struct SessionAudit {
+                bound: std::sync::Mutex<Option<String>>,
+            }
+
+            impl SessionAudit {
+                /// Create an audit not bound to any session yet.
+                pub fn new() -> Self {
+                    Self::default()
+                }
+
+                /// Bind to `session_id`, replacing any session bound before.
+                pub fn bind(&self, session_id: impl Into<String>) {
+                    *self.bound.lock().unwrap_or_else(|e| e.into_inner()) = Some(session_id.into());
+                }
+
+                /// The session bound, if any.
+                pub fn bound(&self) -> Option<String> {
+                    self.bound.lock().unwrap_or_else(|e| e.into_inner()).clone()
+                }
+
+                /// Check `session_id` is the session bound, binding to it when none is.
+                pub fn check(&self, session_id: &str) -> Result<(), String> {
+                    let mut bound = self.bound.lock().unwrap_or_else(|e| e.into_inner());
+                    match bound.as_deref() {
+                        None => {
+                            *bound = Some(session_id.to_string());
+                            Ok(())
+                        }
+                        Some(expected) if expected == session_id => Ok(()),
+                        Some(expected) => Err(format!(
+                            "called with driver session {} but this page object is bound to session {}",
+                            session_id, expected
+                        )),
+                    }
+                }
+            }
+
+            /// Limits how many actions per second a page object performs.
+            pub struct RateLimiter {
+                state: std::sync::Mutex<RateLimiterState>,
+            }
+
+            struct RateLimiterState {
+                max_per_second: Option<f64>,
+                next_slot: Option<std::time::Instant>,
+            }
+
+            impl RateLimiter {
+                /// Create a rate limiter, unlimited when `max_per_second` is `None`.
+                pub const fn new(max_per_second: Option<f64>) -> Self {
+                    RateLimiter {
+                        state: std::sync::Mutex::new(RateLimiterState {
+                            max_per_second,
+                            next_slot: None,
+                        }),
+                    }
+                }
+
+                /// Change the limit, or remove it with `None`.
+                pub fn set_max_per_second(&self, max_per_second: Option<f64>) {
+                    let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                    state.max_per_second = max_per_second;
+                    state.next_slot = None;
+                }
+
+                /// The current limit.
+                pub fn max_per_second(&self) -> Option<f64> {
+                    self.state.lock().unwrap_or_else(|e| e.into_inner()).max_per_second
+                }
+
+                /// Wait until the next action is allowed to run.
+                pub async fn acquire(&self) {
+                    let wait = {
+                        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                        let max_per_second = match state.max_per_second {
+                            Some(max_per_second) if max_per_second > 0.0 => max_per_second,
+                            _ => return,
+                        };
+                        let now = std::time::Instant::now();
+                        let slot = state.next_slot.map_or(now, |next| next.max(now));
+                        state.next_slot =
+                            Some(slot + std::time::Duration::from_secs_f64(1.0 / max_per_second));
+                        slot - now
+                    };
+                    if !wait.is_zero() {
+                        #tokio::time::sleep(wait).await;
+                    }
+                }
+            }
+
+            #image_support
+
+            #ocr_support
+        }
+    }
+}
@@ -0,0 +1,464 @@
+//! Lightweight syntax checks for selector literals, run at expansion time so typos in
+//! inline selectors become compile errors instead of WebDriver errors.
+
+/// Check that `selector` is a syntactically valid CSS selector list.
+///
+/// Returns a description of the first problem found.
+pub(crate) fn validate_css(selector: &str) -> Result<(), String> {
+    let mut parser = CssParser {
+        chars: selector.chars().collect(),
+        pos: 0,
+    };
+    parser.selector_list(false)?;
+    match parser.peek() {
+        None => Ok(()),
+        Some(c) => Err(parser.error(&format!("unexpected '{}'", c))),
+    }
+}
+
+/// Axis names allowed before `::` in an XPath location step
+const XPATH_AXES: &[&str] = &[
+    "ancestor",
+    "ancestor-or-self",
+    "attribute",
+    "child",
+    "descendant",
+    "descendant-or-self",
+    "following",
+    "following-sibling",
+    "namespace",
+    "parent",
+    "preceding",
+    "preceding-sibling",
+    "self",
+];
+
+/// Check that `selector` is a plausible XPath expression.
+///
+/// This is not a full XPath parser: it catches unterminated strings, unbalanced brackets,
+/// empty predicates, unknown axes and dangling path separators.
+pub(crate) fn validate_xpath(selector: &str) -> Result<(), String> {
+    let chars: Vec<char> = selector.chars().collect();
+    if chars.iter().all(|c| c.is_whitespace()) {
+        return Err("empty expression".to_string());
+    }
+
+    // Open brackets and parentheses with their offsets
+    let mut open: Vec<(char, usize)> = Vec::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        match chars[pos] {
+            quote @ ('"' | '\'') => match chars[pos + 1..].iter().position(|&c| c == quote) {
+                Some(len) => pos += len + 1,
+                None => return Err(format!("unterminated string at offset {}", pos)),
+            },
+            c @ ('[' | '(') => open.push((c, pos)),
+            c @ (']' | ')') => {
+                let expected = if c == ']' { '[' } else { '(' };
+                match open.pop() {
+                    Some((opened, start)) if opened == expected => {
+                        if c == ']' && chars[start + 1..pos].iter().all(|c| c.is_whitespace()) {
+                            return Err(format!("empty predicate at offset {}", start));
+                        }
+                    }
+                    Some((opened, start)) => {
+                        return Err(format!(
+                            "'{}' at offset {} closed by '{}' at offset {}",
+                            opened, start, c, pos
+                        ));
+                    }
+                    None => return Err(format!("unmatched '{}' at offset {}", c, pos)),
+                }
+            }
+            '/' if chars.get(pos + 1) == Some(&'/') && chars.get(pos + 2) == Some(&'/') => {
+                return Err(format!("unexpected '/' at offset {}", pos + 2));
+            }
+            ':' if chars.get(pos + 1) == Some(&':') => {
+                let name_end = chars[..pos]
+                    .iter()
+                    .rposition(|c| !c.is_whitespace())
+                    .map_or(0, |last| last + 1);
+                let name_start = chars[..name_end]
+                    .iter()
+                    .rposition(|&c| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+                    .map_or(0, |last| last + 1);
+                let name: String = chars[name_start..name_end].iter().collect();
+                if name.is_empty() {
+                    return Err(format!(
+                        "expected an axis name before '::' at offset {}",
+                        pos
+                    ));
+                }
+                if !XPATH_AXES.contains(&name.as_str()) {
+                    return Err(format!("unknown axis '{}' at offset {}", name, name_start));
+                }
+                pos += 2;
+                while chars.get(pos).is_some_and(|c| c.is_whitespace()) {
+                    pos += 1;
+                }
+                match chars.get(pos) {
+                    Some(&c) if c == '*' || c == '_' || c.is_alphabetic() => continue,
+                    Some(c) => {
+                        return Err(format!(
+                            "expected a node test after '{}::', found '{}' at offset {}",
+                            name, c, pos
+                        ));
+                    }
+                    None => return Err(format!("expected a node test after '{}::'", name)),
+                }
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+
+    if let Some((opened, start)) = open.pop() {
+        return Err(format!("unclosed '{}' at offset {}", opened, start));
+    }
+    let trimmed = selector.trim_end();
+    if trimmed.ends_with('/') && trimmed.trim_start() != "/" {
+        return Err(format!(
+            "expected a location step after '/' at offset {}",
+            trimmed.chars().count()
+        ));
+    }
+    Ok(())
+}
+
+struct CssParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl CssParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{} at offset {}", message, self.pos)
+    }
+
+    fn skip_whitespace(&mut self) -> bool {
+        let start = self.pos;
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+        self.pos > start
+    }
+
+    /// `complex (',' complex)*`; with `relative`, each selector may start with a combinator
+    fn selector_list(&mut self, relative: bool) -> Result<(), String> {
+        loop {
+            self.skip_whitespace();
+            self.complex_selector(relative)?;
+            self.skip_whitespace();
+            if self.peek() == Some(',') {
+                self.pos += 1;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// `compound (combinator compound)*`
+    fn complex_selector(&mut self, relative: bool) -> Result<(), String> {
+        if relative && matches!(self.peek(), Some('>' | '+' | '~')) {
+            self.pos += 1;
+            self.skip_whitespace();
+        }
+        self.compound_selector()?;
+        loop {
+            let had_whitespace = self.skip_whitespace();
+            match self.peek() {
+                Some('>' | '+' | '~') => {
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    self.compound_selector()?;
+                }
+                Some(',' | ')') | None => return Ok(()),
+                Some(_) if had_whitespace => self.compound_selector()?,
+                Some(c) => return Err(self.error(&format!("unexpected '{}'", c))),
+            }
+        }
+    }
+
+    /// Optional type or universal selector followed by id, class, attribute and pseudo parts
+    fn compound_selector(&mut self) -> Result<(), String> {
+        let start = self.pos;
+        if self.peek() == Some('*') {
+            self.pos += 1;
+        } else if self.at_ident_start() {
+            self.ident()?;
+        }
+        // Namespaced type selector, e.g. `svg|rect`
+        if self.pos > start && self.peek() == Some('|') && self.peek_at(1) != Some('=') {
+            self.pos += 1;
+            if self.peek() == Some('*') {
+                self.pos += 1;
+            } else {
+                self.expect_ident("an element name after '|'")?;
+            }
+        }
+        loop {
+            match self.peek() {
+                Some('#') => {
+                    self.pos += 1;
+                    self.expect_ident("an id after '#'")?;
+                }
+                Some('.') => {
+                    self.pos += 1;
+                    self.expect_ident("a class name after '.'")?;
+                }
+                Some('[') => self.attribute()?,
+                Some(':') => self.pseudo()?,
+                _ => break,
+            }
+        }
+        if self.pos == start {
+            return Err(match self.peek() {
+                Some(c) => self.error(&format!("expected a selector, found '{}'", c)),
+                None => self.error("expected a selector"),
+            });
+        }
+        Ok(())
+    }
+
+    /// `[name]` or `[name op value flag?]`
+    fn attribute(&mut self) -> Result<(), String> {
+        self.pos += 1;
+        self.skip_whitespace();
+        self.expect_ident("an attribute name")?;
+        self.skip_whitespace();
+        match self.peek() {
+            Some(']') => {
+                self.pos += 1;
+                return Ok(());
+            }
+            Some('=') => self.pos += 1,
+            Some('~' | '|' | '^' | '$' | '*') if self.peek_at(1) == Some('=') => self.pos += 2,
+            Some(c) => return Err(self.error(&format!("unexpected '{}' in attribute selector", c))),
+            None => return Err(self.error("unterminated attribute selector, expected ']'")),
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"' | '\'') => self.string()?,
+            _ => {
+                self.expect_ident("an attribute value")?;
+            }
+        }
+        self.skip_whitespace();
+        if matches!(self.peek(), Some('i' | 'I' | 's' | 'S')) {
+            self.pos += 1;
+            self.skip_whitespace();
+        }
+        match self.peek() {
+            Some(']') => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(self.error(&format!("unexpected '{}' in attribute selector", c))),
+            None => Err(self.error("unterminated attribute selector, expected ']'")),
+        }
+    }
+
+    /// `:name`, `::name` or `:name(...)`
+    fn pseudo(&mut self) -> Result<(), String> {
+        self.pos += 1;
+        if self.peek() == Some(':') {
+            self.pos += 1;
+        }
+        let name = self.expect_ident("a pseudo-class name after ':'")?;
+        if self.peek() != Some('(') {
+            return Ok(());
+        }
+        self.pos += 1;
+        match name.to_ascii_lowercase().as_str() {
+            "not" | "is" | "where" | "matches" => self.selector_list(false)?,
+            "has" => self.selector_list(true)?,
+            _ => self.balanced_arguments()?,
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some(')') => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(self.error(&format!("unexpected '{}' in :{}(...)", c, name))),
+            None => Err(self.error(&format!("unterminated :{}(...), expected ')'", name))),
+        }
+    }
+
+    /// Free-form pseudo-class arguments such as `2n+1`, stopping before the closing ')'
+    fn balanced_arguments(&mut self) -> Result<(), String> {
+        let start = self.pos;
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                '"' | '\'' => {
+                    self.string()?;
+                    continue;
+                }
+                '(' => depth += 1,
+                ')' if depth == 0 => break,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        if self.chars[start..self.pos]
+            .iter()
+            .all(|c| c.is_whitespace())
+        {
+            return Err(self.error("empty pseudo-class arguments"));
+        }
+        Ok(())
+    }
+
+    /// A quoted string, with backslash escapes
+    fn string(&mut self) -> Result<(), String> {
+        let quote = self.chars[self.pos];
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '\\' => self.pos += 1,
+                c if c == quote => return Ok(()),
+                _ => {}
+            }
+        }
+        self.pos = start;
+        Err(self.error("unterminated string"))
+    }
+
+    fn at_ident_start(&self) -> bool {
+        match self.peek() {
+            Some('-') => {
+                matches!(self.peek_at(1), Some(c) if c == '-' || c == '_' || c == '\\' || c.is_alphabetic() || !c.is_ascii())
+            }
+            Some(c) => c == '_' || c == '\\' || c.is_alphabetic() || !c.is_ascii(),
+            None => false,
+        }
+    }
+
+    fn expect_ident(&mut self, expected: &str) -> Result<String, String> {
+        if !self.at_ident_start() {
+            return Err(match self.peek() {
+                Some(c) => self.error(&format!("expected {}, found '{}'", expected, c)),
+                None => self.error(&format!("expected {}", expected)),
+            });
+        }
+        self.ident()
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c == '\\' {
+                if self.peek_at(1).is_none() {
+                    return Err(self.error("unterminated escape"));
+                }
+                self.pos += 2;
+            } else if c == '-' || c == '_' || c.is_alphanumeric() || !c.is_ascii() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_valid_css() {
+        for selector in [
+            "button",
+            "#login > .form input[type='submit']",
+            "ul li:nth-child(2n+1)",
+            ":nth-child(2n+1 of .x)",
+            "a:has(> img)",
+            ":has(> a)",
+            "svg|rect",
+            "*|*",
+            "[a=\"x\" i]",
+            "[data-id^=item-]",
+            "input:not([disabled]), select",
+            "p::first-line",
+            "div + p ~ span",
+        ] {
+            assert_eq!(validate_css(selector), Ok(()), "{}", selector);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_css() {
+        for selector in [
+            "",
+            "[]",
+            "[a=]",
+            "div >",
+            "a,",
+            "#",
+            ".1x",
+            ":nth-child(",
+            "a[href='x'",
+            "div)",
+        ] {
+            assert!(
+                validate_css(selector).is_err(),
+                "{:?} should be rejected",
+                selector
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_valid_xpath() {
+        for selector in [
+            "//a",
+            "//a[@b='c/']",
+            "(//a)[1]",
+            "//div[@id=\"main\"]//button[contains(text(), 'Save')]",
+            "./following-sibling::li[last()]",
+            "//input[@type='text' and not(@disabled)]",
+            "//*[local-name()='svg']",
+            "..",
+            "//tr[position() mod 2 = 0]",
+            "//a | //b",
+        ] {
+            assert_eq!(validate_xpath(selector), Ok(()), "{}", selector);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_xpath() {
+        for selector in [
+            "",
+            "///",
+            "foo::bar",
+            "//a[",
+            "//a[@b='c]",
+            "(//a",
+            "//a]",
+            "//a[ ]",
+            "//a[(@b])",
+            "//a/",
+            "child::",
+        ] {
+            assert!(
+                validate_xpath(selector).is_err(),
+                "{:?} should be rejected",
+                selector
+            );
+        }
+    }
+}
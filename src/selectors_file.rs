@@ -0,0 +1,354 @@
+//! Reader for `selectors_file`, a TOML file mapping field names to selectors, e.g.
+//!
+//! ```toml
+//! username = { css = "input[name='user']" }
+//! submit.xpath = "//button[@type='submit']"
+//!
+//! [password]
+//! id = "password"
+//! ```
+//!
+//! Only the subset of TOML needed for that is understood: comments, `[field]` tables,
+//! dotted keys, inline tables and single-line basic or literal strings.
+
+/// A selector read from the file
+pub(crate) struct FileSelector {
+    /// Field the selector belongs to
+    pub(crate) field: String,
+    /// Selector kind, the name of the matching inline option (`css`, `xpath`, ...)
+    pub(crate) kind: String,
+    pub(crate) value: String,
+    /// 1-based line the selector was declared on
+    pub(crate) line: usize,
+}
+
+/// Parse the file's selectors, in declaration order.
+///
+/// Returns a description of the first problem found, prefixed with its line.
+pub(crate) fn parse(source: &str) -> Result<Vec<FileSelector>, String> {
+    let mut selectors = Vec::new();
+    let mut table: Option<String> = None;
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        parse_line(line, line_number, &mut table, &mut selectors)
+            .map_err(|e| format!("line {}: {}", line_number, e))?;
+    }
+
+    let mut fields: Vec<&str> = Vec::new();
+    for selector in &selectors {
+        if fields.contains(&selector.field.as_str()) {
+            return Err(format!(
+                "line {}: more than one selector for '{}'",
+                selector.line, selector.field
+            ));
+        }
+        fields.push(&selector.field);
+    }
+    Ok(selectors)
+}
+
+fn parse_line(
+    line: &str,
+    line_number: usize,
+    table: &mut Option<String>,
+    selectors: &mut Vec<FileSelector>,
+) -> Result<(), String> {
+    let mut cursor = Cursor {
+        chars: line.chars().collect(),
+        pos: 0,
+    };
+    cursor.skip_whitespace();
+    match cursor.peek() {
+        None | Some('#') => return Ok(()),
+        Some('[') => {
+            cursor.pos += 1;
+            if cursor.peek() == Some('[') {
+                return Err("arrays of tables are not supported".to_string());
+            }
+            let keys = cursor.dotted_key()?;
+            if keys.len() != 1 {
+                return Err("expected a field name as table header".to_string());
+            }
+            cursor.expect(']')?;
+            cursor.end_of_line()?;
+            *table = keys.into_iter().next();
+            return Ok(());
+        }
+        Some(_) => {}
+    }
+
+    let mut keys = cursor.dotted_key()?;
+    cursor.expect('=')?;
+    cursor.skip_whitespace();
+    if let Some(field) = table {
+        keys.insert(0, field.clone());
+    }
+    match keys.len() {
+        // `field = { kind = "..." }`
+        1 => {
+            cursor.expect('{')?;
+            loop {
+                let kind = cursor.dotted_key()?;
+                if kind.len() != 1 {
+                    return Err("nested tables are not supported".to_string());
+                }
+                cursor.expect('=')?;
+                cursor.skip_whitespace();
+                let value = cursor.string()?;
+                selectors.push(FileSelector {
+                    field: keys[0].clone(),
+                    kind: kind.into_iter().next().expect("one key"),
+                    value,
+                    line: line_number,
+                });
+                cursor.skip_whitespace();
+                match cursor.next() {
+                    Some(',') => continue,
+                    Some('}') => break,
+                    Some(c) => return Err(format!("unexpected '{}' in inline table", c)),
+                    None => return Err("unterminated inline table, expected '}'".to_string()),
+                }
+            }
+        }
+        // `field.kind = "..."`, or `kind = "..."` inside a `[field]` table
+        2 => {
+            let value = cursor.string()?;
+            let mut keys = keys.into_iter();
+            selectors.push(FileSelector {
+                field: keys.next().expect("two keys"),
+                kind: keys.next().expect("two keys"),
+                value,
+                line: line_number,
+            });
+        }
+        _ => return Err("nested tables are not supported".to_string()),
+    }
+    cursor.end_of_line()
+}
+
+struct Cursor {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Cursor {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected '{}', found '{}'", expected, c)),
+            None => Err(format!("expected '{}'", expected)),
+        }
+    }
+
+    /// Nothing but whitespace and a comment may follow
+    fn end_of_line(&mut self) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.peek() {
+            None | Some('#') => Ok(()),
+            Some(c) => Err(format!("unexpected '{}'", c)),
+        }
+    }
+
+    /// `key(.key)*`, each bare or quoted
+    fn dotted_key(&mut self) -> Result<Vec<String>, String> {
+        let mut keys = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let key = match self.peek() {
+                Some('"' | '\'') => self.string()?,
+                _ => {
+                    let start = self.pos;
+                    while self
+                        .peek()
+                        .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                    {
+                        self.pos += 1;
+                    }
+                    if self.pos == start {
+                        return Err(match self.peek() {
+                            Some(c) => format!("expected a key, found '{}'", c),
+                            None => "expected a key".to_string(),
+                        });
+                    }
+                    self.chars[start..self.pos].iter().collect()
+                }
+            };
+            keys.push(key);
+            self.skip_whitespace();
+            if self.peek() == Some('.') {
+                self.pos += 1;
+            } else {
+                return Ok(keys);
+            }
+        }
+    }
+
+    /// A single-line basic (`"..."`, with escapes) or literal (`'...'`) string
+    fn string(&mut self) -> Result<String, String> {
+        let quote = match self.next() {
+            Some(quote @ ('"' | '\'')) => quote,
+            Some(c) => return Err(format!("expected a string, found '{}'", c)),
+            None => return Err("expected a string".to_string()),
+        };
+        if self.peek() == Some(quote) && self.chars.get(self.pos + 1) == Some(&quote) {
+            return Err("multi-line strings are not supported".to_string());
+        }
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some(c) if c == quote => return Ok(value),
+                Some('\\') if quote == '"' => value.push(self.escape()?),
+                Some(c) => value.push(c),
+                None => return Err("unterminated string".to_string()),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, String> {
+        match self.next() {
+            Some('"') => Ok('"'),
+            Some('\\') => Ok('\\'),
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('r') => Ok('\r'),
+            Some(kind @ ('u' | 'U')) => {
+                let len = if kind == 'u' { 4 } else { 8 };
+                let digits: String = self.chars.iter().skip(self.pos).take(len).collect();
+                self.pos += len;
+                u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .filter(|_| digits.len() == len)
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("invalid unicode escape '\\{}{}'", kind, digits))
+            }
+            Some(c) => Err(format!("unknown escape '\\{}'", c)),
+            None => Err("unterminated string".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(field, kind, value, line)` of each selector parsed from `source`
+    fn selectors(source: &str) -> Vec<(String, String, String, usize)> {
+        parse(source)
+            .unwrap_or_else(|e| panic!("{}", e))
+            .into_iter()
+            .map(|selector| (selector.field, selector.kind, selector.value, selector.line))
+            .collect()
+    }
+
+    fn entry(field: &str, kind: &str, value: &str, line: usize) -> (String, String, String, usize) {
+        (field.to_string(), kind.to_string(), value.to_string(), line)
+    }
+
+    #[test]
+    fn parses_each_form() {
+        let source = r#"
+# Login page
+username = { css = "input[name='user']" }
+submit.xpath = "//button[@type='submit']"  # trailing comment
+"quoted-field".id = 'literal'
+
+[password]
+id = "password"
+"#;
+        assert_eq!(
+            selectors(source),
+            vec![
+                entry("username", "css", "input[name='user']", 3),
+                entry("submit", "xpath", "//button[@type='submit']", 4),
+                entry("quoted-field", "id", "literal", 5),
+                entry("password", "id", "password", 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn unescapes_basic_strings_only() {
+        assert_eq!(
+            selectors(r#"a.css = "x\"y\\z\t\u00e9\U0001F600""#),
+            vec![entry("a", "css", "x\"y\\z\t\u{e9}\u{1F600}", 1)]
+        );
+        assert_eq!(
+            selectors(r#"a.xpath = '//a[@b="\n"]'"#),
+            vec![entry("a", "xpath", r#"//a[@b="\n"]"#, 1)]
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_fields() {
+        let error = parse("a.css = \"x\"\n\n[a]\nid = \"y\"").err();
+        assert_eq!(
+            error.as_deref(),
+            Some("line 4: more than one selector for 'a'")
+        );
+        let error = parse(r##"a = { css = "#a", id = "a" }"##).err();
+        assert_eq!(
+            error.as_deref(),
+            Some("line 1: more than one selector for 'a'")
+        );
+    }
+
+    #[test]
+    fn keeps_the_table_for_following_keys() {
+        let error = parse("[a]\ncss = \"x\"\nb.id = \"y\"").err();
+        assert_eq!(
+            error.as_deref(),
+            Some("line 3: nested tables are not supported")
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_toml() {
+        for (source, message) in [
+            (
+                r#"a.css = """x""""#,
+                "line 1: multi-line strings are not supported",
+            ),
+            ("[[a]]", "line 1: arrays of tables are not supported"),
+            (
+                r#"a.b.css = "x""#,
+                "line 1: nested tables are not supported",
+            ),
+            (
+                r#"a = { b.css = "x" }"#,
+                "line 1: nested tables are not supported",
+            ),
+            ("[a.b]", "line 1: expected a field name as table header"),
+            (r#"a.css = "x"#, "line 1: unterminated string"),
+            (r#"a.css = "\q""#, "line 1: unknown escape '\\q'"),
+            (
+                r#"a.css = "\u12""#,
+                "line 1: invalid unicode escape '\\u12\"'",
+            ),
+            (r#"a.css = "x" y"#, "line 1: unexpected 'y'"),
+            (
+                r#"a = { css = "x""#,
+                "line 1: unterminated inline table, expected '}'",
+            ),
+            ("a.css = 1", "line 1: expected a string, found '1'"),
+        ] {
+            assert_eq!(parse(source).err().as_deref(), Some(message), "{}", source);
+        }
+    }
+}
@@ -0,0 +1,1927 @@
+//! Expansion tests: the derive is run on sample structs and its output compared to the
+//! snapshots in `tests/snapshots`, or checked for the names and paths it should contain.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to rewrite the snapshots after an intended change.
+
+use super::*;
+use std::path::PathBuf;
+
+/// Expand the derive on `input`, formatted for comparison
+fn expand_to_string(input: TokenStream2) -> String {
+    pretty(expand(syn::parse2(input).expect("derive input")))
+}
+
+/// Format `expanded` as Rust source when it parses as a file
+fn pretty(expanded: TokenStream2) -> String {
+    match syn::parse2::<syn::File>(expanded.clone()) {
+        Ok(file) => prettyplease::unparse(&file),
+        Err(_) => expanded.to_string(),
+    }
+}
+
+/// Compare `actual` to the snapshot `name`, or rewrite it with `UPDATE_SNAPSHOTS` set
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.rs", name));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(path.parent().expect("snapshot directory"))
+            .expect("create snapshot directory");
+        std::fs::write(&path, actual).expect("write snapshot");
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}; run with UPDATE_SNAPSHOTS=1 to create it",
+            path.display()
+        )
+    });
+    assert!(
+        expected == actual,
+        "expansion of {} differs from {}; run with UPDATE_SNAPSHOTS=1 if the change is intended\n\n{}",
+        name,
+        path.display(),
+        actual
+    );
+}
+
+/// Whether `expanded` contains `code`, ignoring whitespace
+fn contains_code(expanded: &str, code: &str) -> bool {
+    let compact = |text: &str| text.split_whitespace().collect::<String>();
+    compact(expanded).contains(&compact(code))
+}
+
+/// Names of the functions defined in `expanded`
+fn fn_names(expanded: &str) -> Vec<String> {
+    expanded
+        .split("fn ")
+        .skip(1)
+        .filter_map(|rest| rest.split(['(', '<', ' ']).next())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Assert that `expanded` defines each function in `names`
+fn assert_defines(expanded: &str, names: &[&str]) {
+    let defined = fn_names(expanded);
+    for name in names {
+        assert!(
+            defined.iter().any(|defined| defined == name),
+            "`{}` is not generated:\n{}",
+            name,
+            expanded
+        );
+    }
+}
+
+#[test]
+fn expands_basic_page() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(global(is_displayed))]
+        struct LoginPage {
+            #[thirtyfour_actions(methods(enter_keys, clear))]
+            username: By,
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert_snapshot("basic_page", &expanded);
+}
+
+#[test]
+fn expands_storage_helpers() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(storage)]
+        struct Page {
+            #[thirtyfour_actions(methods(click))]
+            button: By,
+        }
+    });
+    assert_defines(
+        &expanded,
+        &[
+            "get_local_storage",
+            "set_local_storage",
+            "get_session_storage",
+            "set_session_storage",
+        ],
+    );
+}
+
+#[test]
+fn expands_alert_helpers() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(alerts)]
+        struct Page {
+            #[thirtyfour_actions(methods(click_and_answer_prompt))]
+            rename: By,
+        }
+    });
+    assert_defines(
+        &expanded,
+        &[
+            "get_alert_text",
+            "accept_alert",
+            "dismiss_alert",
+            "answer_prompt",
+            "click_and_answer_prompt_rename",
+        ],
+    );
+}
+
+#[test]
+fn expands_field_actions() {
+    // (action, method generated for it on a field named `field`)
+    let actions = [
+        ("get_validity", "get_validity_field"),
+        ("find_within", "find_in_field"),
+        ("get_children", "get_children_field"),
+        ("get_parent", "get_parent_field"),
+        ("is_focused", "is_focused_field"),
+        ("draw_path", "draw_path_field"),
+        ("get_canvas_data_url", "get_canvas_data_url_field"),
+        (
+            "wait_for_attribute_to_contain",
+            "wait_for_attribute_to_contain_field",
+        ),
+        ("get_class_list", "get_class_list_field"),
+        ("toggle_class", "toggle_class_field"),
+        ("add_class", "add_class_field"),
+        ("check_focus_trap", "check_focus_trap_field"),
+        ("assert_above_fold", "assert_above_fold_field"),
+        ("get_shadow_root", "get_shadow_root_field"),
+        (
+            "wait_for_enabled_then_click",
+            "wait_for_enabled_then_click_field",
+        ),
+        ("custom_dropdown", "pick_field"),
+        ("dump_subtree", "dump_subtree_field"),
+        ("wait_for_value", "wait_for_value_field"),
+        ("wait_for_value", "wait_for_value_to_contain_field"),
+        ("get_attributes_all", "get_attributes_all_field"),
+        ("get_element_state", "get_element_state_field"),
+    ];
+    for (action, method) in actions {
+        let action = Ident::new(action, proc_macro2::Span::call_site());
+        let expanded = expand_to_string(quote! {
+            struct Page {
+                #[thirtyfour_actions(methods(#action))]
+                field: By,
+            }
+        });
+        assert_defines(&expanded, &[method]);
+    }
+}
+
+#[test]
+fn check_link_needs_the_reqwest_feature() {
+    let expanded = expand_to_string(quote! {
+        struct Page {
+            #[thirtyfour_actions(methods(check_link))]
+            docs: By,
+        }
+    });
+    if cfg!(feature = "reqwest") {
+        assert_defines(&expanded, &["check_link_docs"]);
+    } else {
+        assert!(
+            expanded.contains("requires the `reqwest` feature"),
+            "{}",
+            expanded
+        );
+    }
+}
+
+#[test]
+fn expands_role_helpers() {
+    let expanded = expand_to_string(quote! {
+        struct LoginForm {
+            #[thirtyfour_actions(role = "error_message")]
+            error: By,
+            #[thirtyfour_actions(role = "submit")]
+            submit: By,
+        }
+    });
+    assert_defines(&expanded, &["collect_errors", "submit_and_collect_errors"]);
+}
+
+#[test]
+fn expands_load_and_overlay_helpers() {
+    let expanded = expand_to_string(quote! {
+        struct Page {
+            #[thirtyfour_actions(role = "load_marker")]
+            content: By,
+            #[thirtyfour_actions(role = "overlay")]
+            cookie_banner: By,
+        }
+    });
+    assert_defines(&expanded, &["wait_until_ready", "dismiss_overlays"]);
+}
+
+#[test]
+fn unique_fields_reject_ambiguous_matches() {
+    let expanded = expand_to_string(quote! {
+        struct Page {
+            #[thirtyfour_actions(unique, methods(click))]
+            submit: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "all_from_selector().await")
+            && expanded.contains("expected exactly one"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn runtime_module_parses() {
+    syn::parse2::<syn::File>(runtime_module(&CratePaths::default())).expect("runtime module");
+}
+
+#[test]
+fn sink_redacts_sensitive_arguments() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(sink = "audit")]
+        struct LoginPage {
+            #[thirtyfour_actions(sensitive, methods(enter_keys))]
+            password: By,
+            audit: std::sync::Arc<dyn ActionSink>,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            r#"("keys", crate::thirtyfour_actions_runtime::REDACTED.to_string())"#
+        ) && contains_code(
+            &expanded,
+            ".record(crate::thirtyfour_actions_runtime::ActionRecord {"
+        ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn masks_sensitive_values_in_errors() {
+    let expanded = expand_to_string(quote! {
+        struct LoginPage {
+            #[thirtyfour_actions(sensitive, methods(enter_keys))]
+            password: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, r#"detail.replace(keys, "***")"#),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn rate_limited_actions_acquire_first() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(rate_limit = 5)]
+        struct Page {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert_defines(&expanded, &["rate_limiter"]);
+    assert!(
+        contains_code(&expanded, "Self::rate_limiter().acquire().await;"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn session_lock_wraps_actions() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(session_lock)]
+        struct Page {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "crate::thirtyfour_actions_runtime::with_session_lock(driver.session_id().to_string(),"
+        ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn no_query_keeps_the_query_private() {
+    let expanded = expand_to_string(quote! {
+        struct Page {
+            #[thirtyfour_actions(no_query, methods(click))]
+            submit: By,
+        }
+    });
+    assert_defines(&expanded, &["query_submit"]);
+    assert!(
+        !contains_code(&expanded, "pub async fn query_submit("),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn highlight_cleanup_failure_keeps_the_screenshot() {
+    let expanded = expand_to_string(quote! {
+        struct Page {
+            #[thirtyfour_actions(methods(screenshot_with_highlight))]
+            chart: By,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            r#"log::warn!("Failed to remove highlight from {}: {}", "chart", e);"#
+        ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn expands_field_enum() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(field_enum)]
+        struct LoginPage {
+            #[thirtyfour_actions(methods(click))]
+            user_name: By,
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "enum LoginPageField { UserName, Submit, }"),
+        "{}",
+        expanded
+    );
+    assert_defines(&expanded, &["locator", "wait_for_any", "wait_for_all"]);
+}
+
+#[test]
+fn expands_race_helpers() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(race(success_toast, error_banner))]
+        struct CheckoutPage {
+            success_toast: By,
+            error_banner: By,
+        }
+    });
+    assert_defines(&expanded, &["race_success_toast_vs_error_banner"]);
+    assert!(
+        expanded.contains("enum CheckoutPageSuccessToastOrErrorBanner"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn screenshot_options_need_the_image_feature() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(screenshot(format = "jpeg", quality = 70, max_width = 1280))]
+        struct Page {
+            #[thirtyfour_actions(methods(screenshot_with_highlight))]
+            chart: By,
+        }
+    });
+    if cfg!(feature = "image") {
+        assert!(expanded.contains("encode_screenshot"), "{}", expanded);
+    } else {
+        assert!(
+            expanded.contains("requires the `image` feature"),
+            "{}",
+            expanded
+        );
+    }
+}
+
+#[test]
+fn read_text_ocr_needs_the_ocr_feature() {
+    let expanded = expand_to_string(quote! {
+        struct Page {
+            #[thirtyfour_actions(methods(read_text_ocr))]
+            captcha: By,
+        }
+    });
+    if cfg!(feature = "ocr") {
+        assert_defines(&expanded, &["read_text_ocr_captcha"]);
+    } else {
+        assert!(
+            expanded.contains("requires the `ocr` feature"),
+            "{}",
+            expanded
+        );
+    }
+}
+
+#[test]
+fn open_applies_context_overrides() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(
+            url = "https://example.com/store",
+            geolocation(lat = 52.52, lon = 13.40),
+            timezone = "Europe/Berlin",
+            locale = "de-DE"
+        )]
+        struct StorePage {
+            #[thirtyfour_actions(methods(click))]
+            basket: By,
+        }
+    });
+    assert_defines(&expanded, &["apply_context", "open"]);
+    assert!(
+        contains_code(&expanded, "self.apply_context(driver).await?;")
+            && expanded.contains("\"Europe/Berlin\""),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn required_cookies_are_checked() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(requires_cookie = "session", requires_cookie = "csrf_token")]
+        struct AccountPage {
+            #[thirtyfour_actions(methods(click))]
+            logout: By,
+        }
+    });
+    assert_defines(&expanded, &["ensure_preconditions"]);
+    assert!(
+        expanded.contains("\"session\"") && expanded.contains("\"csrf_token\""),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn expected_url_pattern_needs_the_regex_feature() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(expected_url_pattern = "^https://example\\.com/account")]
+        struct AccountPage {
+            #[thirtyfour_actions(methods(click))]
+            logout: By,
+        }
+    });
+    if cfg!(feature = "regex") {
+        assert_defines(&expanded, &["assert_on_page"]);
+    } else {
+        assert!(
+            expanded.contains("requires the `regex` feature"),
+            "{}",
+            expanded
+        );
+    }
+}
+
+#[test]
+fn debug_menu_follows_the_debug_tools_feature() {
+    let expanded = expand_to_string(quote! {
+        struct Page {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert_eq!(
+        fn_names(&expanded).contains(&"debug_menu".to_string()),
+        cfg!(feature = "debug-tools"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn display_summarizes_configuration() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(display, url = "https://example.com/login")]
+        struct LoginPage {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "impl std::fmt::Display for LoginPage")
+            && expanded.contains("url: https://example.com/login"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn return_element_interactions_return_the_element() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(return_element)]
+        struct Page {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "-> std::result::Result<thirtyfour::WebElement, anyhow::Error>"
+        ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn all_except_expands_every_other_action() {
+    let expanded = expand_to_string(quote! {
+        struct Page {
+            #[thirtyfour_actions(all_except(click, hover))]
+            field: By,
+        }
+    });
+    assert_defines(&expanded, &["double_click_field", "get_text_field"]);
+    let names = fn_names(&expanded);
+    assert!(
+        !names.contains(&"click_field".to_string()) && !names.contains(&"hover_field".to_string()),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn inline_selector_generates_description_constant() {
+    let expanded = expand_to_string(quote! {
+        struct Page {
+            #[thirtyfour_actions(css = "#login > button", methods(click))]
+            submit: By,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            r##"pub const SUBMIT_SELECTOR_DESC: &'static str = "By::Css(\"#login > button\")";"##
+        ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn no_defaults_skips_default_methods() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(default_methods(is_displayed))]
+        struct Page {
+            #[thirtyfour_actions(methods(click))]
+            username: By,
+            #[thirtyfour_actions(no_defaults, methods(click))]
+            submit: By,
+        }
+    });
+    assert_defines(&expanded, &["is_displayed_username", "click_submit"]);
+    assert!(
+        !fn_names(&expanded).contains(&"is_displayed_submit".to_string()),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn skipped_fields_get_no_methods() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(global(is_displayed))]
+        struct Page {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+            #[thirtyfour_actions(skip)]
+            base_url: String,
+        }
+    });
+    assert_defines(&expanded, &["click_submit"]);
+    assert!(!expanded.contains("base_url"), "{}", expanded);
+}
+
+#[test]
+fn inline_selectors_generate_new_and_default() {
+    let expanded = expand_to_string(quote! {
+        struct Page {
+            #[thirtyfour_actions(id = "user", methods(enter_keys))]
+            username: By,
+            #[thirtyfour_actions(css = "button.submit")]
+            submit: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "pub fn new() -> Self")
+            && contains_code(&expanded, r#"username: thirtyfour::By::Id("user"),"#)
+            && contains_code(&expanded, "impl Default for Page"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn slow_threshold_wraps_methods_in_timing() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(slow_threshold_ms = 2000)]
+        struct Page {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert!(
+        expanded.contains("Slow action {} on {}: took {:?} (threshold {} ms)")
+            && expanded.contains("\"Page.submit\""),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn expands_custom_error_type() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(error = "crate::UiError")]
+        struct LoginPage {
+            #[thirtyfour_actions(id = "username", methods(click, get_text))]
+            username: By,
+        }
+    });
+    assert!(
+        !expanded.contains("anyhow"),
+        "no anyhow path should be left:\n{}",
+        expanded
+    );
+    assert_snapshot("custom_error_type", &expanded);
+}
+
+#[test]
+fn get_texts_of_reads_through_query_fns() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(field_enum)]
+        struct LoginPage {
+            #[thirtyfour_actions(unique)]
+            user_name: By,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "LoginPageField::UserName => { self.query_user_name(driver).await }"
+        ) && !expanded.contains("tokio::task::JoinSet"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn masks_sensitive_send_key() {
+    let expanded = expand_to_string(quote! {
+        struct LoginPage {
+            #[thirtyfour_actions(sensitive, methods(send_key))]
+            password: By,
+        }
+    });
+    assert!(
+        expanded.contains("replace(typed.as_str(), \"***\")"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn expands_structured_errors() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(structured_errors, field_enum)]
+        struct LoginPage {
+            #[thirtyfour_actions(id = "username", methods(click, get_text))]
+            username: By,
+        }
+    });
+    assert!(
+        !expanded.contains("map_err(|e| anyhow::Error::msg"),
+        "every driver error should be classified:\n{}",
+        expanded
+    );
+    assert_snapshot("structured_errors", &expanded);
+}
+
+#[test]
+fn query_events_follow_the_tracing_feature() {
+    let expanded = expand_to_string(quote! {
+        struct Page {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert_eq!(
+        expanded.contains("tracing::error!"),
+        cfg!(feature = "tracing"),
+        "{}",
+        expanded
+    );
+    assert_eq!(
+        expanded.contains("log::error!"),
+        !cfg!(feature = "tracing"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn timeout_secs_adds_default_variants() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(timeout_secs = 15, poll_ms = 250)]
+        struct Page {
+            #[thirtyfour_actions(methods(wait_for_attribute_to_contain))]
+            status: By,
+        }
+    });
+    assert_defines(&expanded, &["wait_for_attribute_to_contain_status_default"]);
+    assert!(
+        contains_code(
+            &expanded,
+            "self.wait_for_attribute_to_contain_status(driver, attribute, needle, 15u64).await"
+        ) && expanded.contains("Duration::from_millis(250u64)"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn url_placeholders_generate_resolve_url() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(url = "{base}/login")]
+        struct LoginPage {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert_defines(&expanded, &["resolve_url", "open_with", "open"]);
+    assert!(expanded.contains("[(\"base\", \"BASE\")]"), "{}", expanded);
+}
+
+#[test]
+fn field_timeout_makes_queries_wait() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(timeout_secs = 15)]
+        struct Page {
+            #[thirtyfour_actions(timeout_secs = 60, methods(wait_for_attribute_to_contain))]
+            status: By,
+        }
+    });
+    assert!(
+        expanded.contains("std::time::Duration::from_secs(60u64)")
+            && contains_code(
+                &expanded,
+                "self.wait_for_attribute_to_contain_status(driver, attribute, needle, 60u64).await"
+            ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn done_when_generates_once_methods() {
+    let expanded = expand_to_string(quote! {
+        struct Page {
+            #[thirtyfour_actions(done_when = "confirmation", methods(click))]
+            submit: By,
+            confirmation: By,
+        }
+    });
+    assert_defines(&expanded, &["click_submit_once"]);
+    assert!(
+        contains_code(&expanded, "self.click_submit(driver).await?;"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn optional_fields_return_absent_values() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(error = "crate::UiError")]
+        struct Page {
+            #[thirtyfour_actions(optional, methods(get_text, is_displayed))]
+            banner: By,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "-> std::result::Result<Option<String>, crate::UiError>"
+        ) && contains_code(&expanded, "None => Ok(None)")
+            && contains_code(&expanded, "None => Ok(false)")
+            && !expanded.contains("element_absent"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn root_scopes_other_fields() {
+    let expanded = expand_to_string(quote! {
+        struct Header {
+            #[thirtyfour_actions(root)]
+            container: By,
+            #[thirtyfour_actions(methods(click))]
+            logout: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "match self.query_container(driver).await")
+            && contains_code(
+                &expanded,
+                "Some(scope) => { scope.query(thirtyfour::By::clone(&self.logout))"
+            ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn component_fields_are_not_locators() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(global(click))]
+        struct Page {
+            #[thirtyfour_actions(component)]
+            header: Header,
+            submit: By,
+        }
+    });
+    assert_defines(&expanded, &["click_submit"]);
+    assert!(
+        !fn_names(&expanded).contains(&"query_header".to_string()),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn resolve_all_skips_lazy_fields_without_tokio() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(field_enum)]
+        struct Page {
+            submit: By,
+            #[thirtyfour_actions(lazy)]
+            last_row: By,
+        }
+    });
+    assert_defines(&expanded, &["resolve_all", "resolve_all_include_lazy"]);
+    assert!(
+        contains_code(&expanded, "[PageField::Submit].into_iter()")
+            && contains_code(
+                &expanded,
+                "[PageField::Submit, PageField::LastRow].into_iter()"
+            )
+            && !expanded.contains("tokio::join!"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn within_scopes_a_field_under_a_sibling() {
+    let expanded = expand_to_string(quote! {
+        struct Cart {
+            cart_row: By,
+            #[thirtyfour_actions(within = "cart_row", methods(click))]
+            remove: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "match self.query_cart_row(driver).await")
+            && contains_code(
+                &expanded,
+                "Some(scope) => { scope.query(thirtyfour::By::clone(&self.remove))"
+            ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn alias_generates_delegating_methods() {
+    let expanded = expand_to_string(quote! {
+        struct Login {
+            #[thirtyfour_actions(alias = "sign_in", methods(click))]
+            submit: By,
+        }
+    });
+    assert_defines(&expanded, &["click_submit", "click_sign_in"]);
+    assert!(
+        contains_code(&expanded, "self.click_submit(driver).await"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn shadow_root_queries_inside_the_host() {
+    let expanded = expand_to_string(quote! {
+        struct Calendar {
+            date_picker: By,
+            #[thirtyfour_actions(shadow_root = "date_picker", methods(click))]
+            today: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "match self.query_date_picker(driver).await")
+            && contains_code(
+                &expanded,
+                "Some(host) => { match host.get_shadow_root().await"
+            ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn cfg_feature_gates_field_actions() {
+    let expanded = expand_to_string(quote! {
+        struct Dashboard {
+            #[thirtyfour_actions(cfg_feature = "admin-ui", methods(click))]
+            admin_panel: By,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "#[cfg(feature = \"admin-ui\")] /// Click on the web element.\n pub async fn click_admin_panel"
+        ) && !contains_code(
+            &expanded,
+            "#[cfg(feature = \"admin-ui\")] async fn query_admin_panel"
+        ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn fallbacks_are_tried_in_order() {
+    let expanded = expand_to_string(quote! {
+        struct Checkout {
+            #[thirtyfour_actions(fallbacks(css = ".legacy-checkout", xpath = "//button[text()='Pay']"), methods(click))]
+            pay: By,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "let locators = [thirtyfour::By::clone(&self.pay), thirtyfour::By::Css(\".legacy-checkout\"), thirtyfour::By::XPath(\"//button[text()='Pay']\"),];"
+        ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn nth_picks_a_match_by_index() {
+    let expanded = expand_to_string(quote! {
+        struct Results {
+            #[thirtyfour_actions(nth = 2, methods(click, exists))]
+            row: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "self.query_row(driver).await.is_some()")
+            && expanded.contains("2usize"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn broadcast_runs_an_action_on_every_driver() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(broadcast)]
+        struct Login {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert_defines(&expanded, &["broadcast"]);
+}
+
+#[test]
+fn with_text_and_with_attribute_filter_queries() {
+    let expanded = expand_to_string(quote! {
+        struct Form {
+            #[thirtyfour_actions(css = "button", with_text = "Submit", with_attribute("data-state", "ready"), methods(click))]
+            submit: By,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            ".with_text(\"Submit\").with_attribute(\"data-state\", \"ready\")"
+        ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn strict_structs_query_through_try_query() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(strict)]
+        struct Form {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+            #[thirtyfour_actions(nth = 1, methods(click))]
+            row: By,
+        }
+    });
+    assert_defines(&expanded, &["try_query_submit"]);
+    assert!(
+        contains_code(&expanded, "match self.try_query_submit(driver).await?")
+            && !fn_names(&expanded).contains(&"try_query_row".to_string()),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn optional_unique_fields_return_absent_values() {
+    let expanded = expand_to_string(quote! {
+        struct Banner {
+            #[thirtyfour_actions(optional, unique, methods(click))]
+            dismiss: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "match self.try_query_dismiss(driver).await?")
+            && contains_code(&expanded, "None => Ok(None)"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn bound_driver_methods_take_only_self() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(driver = "driver")]
+        struct Login {
+            driver: std::sync::Arc<WebDriver>,
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "pub async fn click_submit(&self)")
+            && contains_code(
+                &expanded,
+                "let driver: &thirtyfour::WebDriver = std::borrow::Borrow::borrow(&self.driver);"
+            ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn actions_get_with_timeout_variants() {
+    let expanded = expand_to_string(quote! {
+        struct Login {
+            #[thirtyfour_actions(methods(click, wait_for))]
+            submit: By,
+        }
+    });
+    assert_defines(
+        &expanded,
+        &["query_submit_with_timeout", "click_submit_with_timeout"],
+    );
+    assert!(
+        !fn_names(&expanded).contains(&"wait_for_submit_with_timeout".to_string())
+            && contains_code(
+                &expanded,
+                "match self.query_submit_with_timeout(driver, timeout_secs).await"
+            ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn field_combinators_use_field_queries() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(field_enum)]
+        struct LoginPage {
+            #[thirtyfour_actions(css = "form.login", methods(click))]
+            form: By,
+            #[thirtyfour_actions(css = "#user", within = "form", nth = 1, methods(click))]
+            username: By,
+        }
+    });
+    let call = "self.query_username_with_timeout(driver, 0).await";
+    assert!(
+        contains_code(&expanded, call),
+        "missing `{}`:\n{}",
+        call,
+        expanded
+    );
+    assert_snapshot("field_combinators", &expanded);
+}
+
+#[test]
+fn as_trait_delegates_to_inherent_methods() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(as_trait = "LoginActions")]
+        struct Login {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "impl LoginActions for Login")
+            && contains_code(
+                &expanded,
+                "Box::pin(async move { Login::click_submit(self, driver).await })"
+            ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn perform_dispatches_zero_argument_actions() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(perform)]
+        struct Login {
+            #[thirtyfour_actions(methods(click, send_key))]
+            submit: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "(LoginField::Submit, \"click\") =>")
+            && contains_code(
+                &expanded,
+                "ActionOutput::from(self.click_submit(driver).await?"
+            )
+            && !expanded.contains("\"send_key\") =>"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn resolver_fields_resolve_through_their_component() {
+    let expanded = expand_to_string(quote! {
+        struct Login {
+            #[thirtyfour_actions(resolver, methods(click))]
+            submit: ElementResolver<WebElement>,
+        }
+    });
+    assert_defines(&expanded, &["resolve_submit", "click_submit"]);
+    assert!(
+        contains_code(&expanded, "match self.submit.resolve_present().await"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn wait_for_no_overlap_scrolls_the_element_into_view() {
+    let expanded = expand_to_string(quote! {
+        struct Checkout {
+            #[thirtyfour_actions(methods(wait_for_no_overlap))]
+            pay: By,
+        }
+    });
+    assert_defines(&expanded, &["wait_for_no_overlap_pay"]);
+    assert!(
+        expanded.contains("element.scrollIntoView({ block: \"center\", inline: \"center\" });")
+            && expanded.contains("if (hit === null) {"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn soft_assert_records_failures_of_assertions() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(soft_assert = "checks")]
+        struct Home {
+            checks: SoftAssertions,
+            #[thirtyfour_actions(methods(assert_above_fold, click))]
+            banner: By,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "self.checks.record(format!(\"{}::{}: {}\", \"Home\", \"assert_above_fold_banner\", e));"
+        ) && !expanded.contains("\"click_banner\", e)"),
+        "{}",
+        expanded
+    );
+    let runtime = runtime_module(&CratePaths::default()).to_string();
+    assert!(
+        contains_code(
+            &runtime,
+            "pub fn finish(&self) -> Result<(), thirtyfour::error::WebDriverError>"
+        ),
+        "{}",
+        runtime
+    );
+}
+
+#[test]
+fn selector_map_lists_locator_fields() {
+    let expanded = expand_to_string(quote! {
+        struct Login {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+            #[thirtyfour_actions(resolver, methods(click))]
+            banner: ElementResolver<WebElement>,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "(\"submit\", thirtyfour::By::clone(&self.submit))"
+        ) && !expanded.contains("(\"banner\","),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn from_selectors_builds_from_a_selector_config() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(from_selectors)]
+        struct Login {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "pub fn from_selectors(config: &crate::thirtyfour_actions_runtime::SelectorConfig,)"
+        ) && contains_code(&expanded, "match (config.get(\"submit\"),)"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn shared_locator_fields_are_read_through_by_clone() {
+    let expanded = expand_to_string(quote! {
+        struct Login {
+            #[thirtyfour_actions(css = "#submit", methods(click))]
+            submit: std::sync::Arc<thirtyfour::By>,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "driver.query(thirtyfour::By::clone(&self.submit))"
+        ) && contains_code(
+            &expanded,
+            "submit: std::convert::From::from(thirtyfour::By::Css(\"#submit\"))"
+        ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn expands_headless_aware_pacing() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(
+            headless_aware,
+            rate_limit = 5,
+            hover_dwell_ms = 400,
+            wait_between_actions(ms = 300, field = "pacer")
+        )]
+        struct MenuPage {
+            #[thirtyfour_actions(css = "nav .menu", methods(hover))]
+            menu: By,
+            pacer: ActionPacer,
+        }
+    });
+    assert_defines(&expanded, &["headless"]);
+    for skipped in [
+        "if !Self::headless().is_enabled() { Self::rate_limiter().acquire().await; }",
+        "if Self::headless().is_enabled() { std::time::Duration::ZERO }",
+        "if !Self::headless().is_enabled() { tokio::time::sleep",
+    ] {
+        assert!(
+            contains_code(&expanded, skipped),
+            "missing `{}`:\n{}",
+            skipped,
+            expanded
+        );
+    }
+    assert_snapshot("headless_aware_pacing", &expanded);
+    let runtime = runtime_module(&CratePaths::default()).to_string();
+    assert!(runtime.contains("pub struct HeadlessSwitch"), "{}", runtime);
+}
+
+#[test]
+fn selector_placeholders_become_parameters() {
+    let expanded = expand_to_string(quote! {
+        struct Orders {
+            #[thirtyfour_actions(xpath = "//tr[@data-id='{id}']", methods(click))]
+            row_by_id: By,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "pub async fn click_row_by_id(&self, driver: &thirtyfour::WebDriver, id: &str,)"
+        ) && contains_code(&expanded, "match self.query_row_by_id(driver, id).await")
+            && contains_code(&expanded, "format!(\"//tr[@data-id='{id}']\", id = id)"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn selector_map_fields_take_a_key() {
+    let expanded = expand_to_string(quote! {
+        struct Settings {
+            #[thirtyfour_actions(methods(click))]
+            tabs: HashMap<String, By>,
+        }
+    });
+    assert_defines(&expanded, &["locator_tabs", "query_tabs", "click_tabs"]);
+    assert!(
+        contains_code(
+            &expanded,
+            "pub async fn click_tabs(&self, driver: &thirtyfour::WebDriver, key: &str,)"
+        ) && contains_code(&expanded, "has no selector for key '{}' (known keys: {})"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn log_success_logs_successful_actions_behind_a_flag() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(log_success = false)]
+        struct Checkout {
+            #[thirtyfour_actions(css = "#pay", methods(click))]
+            pay: By,
+        }
+    });
+    assert_defines(&expanded, &["success_logging"]);
+    for expected in [
+        "std::sync::atomic::AtomicBool::new(false,)",
+        "if result.is_ok() && Self::success_logging().load(std::sync::atomic::Ordering::Relaxed)",
+        "\"Action {} on {} succeeded in {:?}\", \"click_pay\", \"Checkout.pay\"",
+    ] {
+        assert!(
+            contains_code(&expanded, expected),
+            "missing `{}`:\n{}",
+            expected,
+            expanded
+        );
+    }
+}
+
+#[test]
+fn web_element_fields_are_checked_for_staleness() {
+    let expanded = expand_to_string(quote! {
+        struct Row {
+            #[thirtyfour_actions(methods(click))]
+            cell: WebElement,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "thirtyfour::WebElement::clone(&self.cell)")
+            && contains_code(&expanded, "match element.is_present().await"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn before_open_hook_runs_before_navigating() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(url = "https://example.com/orders", before_open = "Self::seed_orders")]
+        struct Orders {
+            #[thirtyfour_actions(css = "#orders")]
+            list: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "Self::seed_orders(self, driver).await?;"),
+        "{}",
+        expanded
+    );
+    let missing_url = expand_to_string(quote! {
+        #[thirtyfour_actions(before_open = "Self::seed_orders")]
+        struct Orders {
+            #[thirtyfour_actions(css = "#orders")]
+            list: By,
+        }
+    });
+    assert!(
+        missing_url.contains("before_open requires url"),
+        "{}",
+        missing_url
+    );
+}
+
+#[test]
+fn tuple_struct_fields_are_addressed_by_index() {
+    let expanded = expand_to_string(quote! {
+        struct Toolbar(
+            #[thirtyfour_actions(css = "#save", methods(click))] By,
+            #[thirtyfour_actions(field_name = "close", css = "#close", methods(click))] By,
+        );
+    });
+    assert_defines(&expanded, &["click_field_0", "click_close"]);
+    assert!(
+        contains_code(&expanded, "thirtyfour::By::clone(&self.0)")
+            && contains_code(&expanded, "thirtyfour::By::clone(&self.1)")
+            && !expanded.contains("self.close"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn generic_structs_keep_their_generics_on_impls() {
+    let expanded = expand_to_string(quote! {
+        struct Dialog<'a, T: Clone> where T: Send {
+            #[thirtyfour_actions(css = "#ok", methods(click))]
+            ok: By,
+            #[thirtyfour_actions(skip)]
+            context: &'a T,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "impl<'a, T: Clone> Dialog<'a, T> where T: Send, {"
+        ),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn wait_between_actions_paces_through_its_field() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(wait_between_actions(ms = 250, field = "pacer"))]
+        struct Search {
+            #[thirtyfour_actions(css = "#query", methods(click))]
+            query: By,
+            pacer: ActionPacer,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "self.pacer.pace(std::time::Duration::from_millis(250u64), async {"
+        ) && contains_code(&expanded, "pacer: Default::default()"),
+        "{}",
+        expanded
+    );
+    let missing_field = expand_to_string(quote! {
+        #[thirtyfour_actions(wait_between_actions(ms = 250, field = "pacer"))]
+        struct Search {
+            #[thirtyfour_actions(css = "#query", methods(click))]
+            query: By,
+        }
+    });
+    assert!(
+        missing_field.contains("wait_between_actions field 'pacer' not found in struct"),
+        "{}",
+        missing_field
+    );
+}
+
+#[test]
+fn expands_state_enum() {
+    let expanded = expand_to_string(quote! {
+        enum Checkout {
+            #[thirtyfour_actions(anchor = "total")]
+            Cart {
+                #[thirtyfour_actions(css = ".cart-item", methods(click))]
+                item: By,
+                #[thirtyfour_actions(css = ".total")]
+                total: By,
+            },
+            Done {
+                #[thirtyfour_actions(css = ".thanks")]
+                thanks: By,
+            },
+        }
+    });
+    for probe in [
+        "CheckoutCart::new()",
+        "state.query_total_with_timeout(driver, 0)",
+        "state.query_thanks_with_timeout(driver, 0)",
+    ] {
+        assert!(
+            contains_code(&expanded, probe),
+            "missing `{}`:\n{}",
+            probe,
+            expanded
+        );
+    }
+    assert_snapshot("state_enum", &expanded);
+}
+
+#[test]
+fn rejects_state_fields_detect_state_cannot_build() {
+    let cases = [
+        (
+            quote!(#[thirtyfour_actions(skip)] retries: u32),
+            "field 'retries' of state 'Cart' is `skip`",
+        ),
+        (
+            quote!(#[thirtyfour_actions(component)] header: Header),
+            "field 'header' of state 'Cart' is a `component`",
+        ),
+        (
+            quote!(#[thirtyfour_actions(resolver)] grid: GridResolver),
+            "field 'grid' of state 'Cart' is a `resolver`",
+        ),
+        (
+            quote!(row: std::collections::HashMap<String, By>),
+            "field 'row' of state 'Cart' is keyed",
+        ),
+        (
+            quote!(banner: By),
+            "field 'banner' of state 'Cart' has no selector",
+        ),
+    ];
+    for (field, message) in cases {
+        let expanded = expand_to_string(quote! {
+            enum Checkout {
+                #[thirtyfour_actions(anchor = "total")]
+                Cart {
+                    #[thirtyfour_actions(css = ".total")]
+                    total: By,
+                    #field,
+                },
+            }
+        });
+        assert!(
+            expanded.contains(message),
+            "expected `{}`, got {}",
+            message,
+            expanded
+        );
+    }
+
+    let expanded = expand_to_string(quote! {
+        enum Checkout {
+            Cart {
+                #[thirtyfour_actions(css = ".item[data-id='{id}']")]
+                item: By,
+            },
+        }
+    });
+    assert!(
+        expanded.contains("anchor field 'item' of state 'Cart' has a templated selector"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn dry_run_guards_every_interaction() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(dry_run)]
+        struct MenuPage {
+            #[thirtyfour_actions(
+                css = "nav .menu",
+                methods(hover, add_class, remove_class, toggle_class, scroll_to)
+            )]
+            menu: By,
+            #[thirtyfour_actions(css = "#pin", sensitive, methods(send_key))]
+            pin: By,
+        }
+    });
+    for action in [
+        "hover_menu",
+        "add_class_menu",
+        "remove_class_menu",
+        "toggle_class_menu",
+        "scroll_to_menu",
+        "send_key_pin",
+    ] {
+        let method = expanded
+            .split("pub async fn ")
+            .find(|method| method.starts_with(&format!("{}(", action)))
+            .unwrap_or_else(|| panic!("missing `{}`:\n{}", action, expanded));
+        assert!(
+            method.contains("Self::dry_run().is_enabled()"),
+            "`{}` should check the dry-run switch:\n{}",
+            action,
+            method
+        );
+    }
+    assert!(
+        contains_code(&expanded, "format!(\"send_key {} {:?}\", \"pin\", \"***\")"),
+        "{}",
+        expanded
+    );
+    assert_snapshot("dry_run_interactions", &expanded);
+}
+
+#[test]
+fn expands_raw_field_names() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(field_enum)]
+        struct SearchForm {
+            #[thirtyfour_actions(css = "select.type", methods(click, send_key))]
+            r#type: By,
+            #[thirtyfour_actions(css = "input.ref", methods(click, send_key))]
+            r#ref: By,
+            #[thirtyfour_actions(css = "button.fn", methods(click, send_key))]
+            r#fn: By,
+        }
+    });
+    let names = fn_names(&expanded);
+    for field in ["type", "ref", "fn"] {
+        for method in ["query", "click", "send_key"] {
+            let name = format!("{}_{}", method, field);
+            assert!(names.contains(&name), "missing `{}` in {:?}", name, names);
+        }
+    }
+    assert!(
+        !expanded.contains("_r#"),
+        "raw prefix left in a name:\n{}",
+        expanded
+    );
+    assert!(expanded.contains("&self.r#type"), "{}", expanded);
+    for (variant, name) in [("Type", "type"), ("Ref", "ref"), ("Fn", "fn")] {
+        let arm = format!("Self::{} => \"{}\"", variant, name);
+        assert!(expanded.contains(&arm), "missing `{}`:\n{}", arm, expanded);
+    }
+    assert_snapshot("raw_field_names", &expanded);
+}
+
+#[test]
+fn expect_count_is_checked_by_validate_counts() {
+    let expanded = expand_to_string(quote! {
+        struct Cart {
+            #[thirtyfour_actions(css = ".cart-total", expect_count = 1)]
+            total: By,
+            #[thirtyfour_actions(css = ".cart-row", expect_count = 1..)]
+            rows: By,
+        }
+    });
+    assert_defines(&expanded, &["validate_counts"]);
+    assert!(
+        contains_code(&expanded, "(1usize..=1usize).contains(&elements.len())")
+            && contains_code(&expanded, "elements.len() >= 1usize")
+            && contains_code(&expanded, "\"exactly 1\"")
+            && contains_code(&expanded, "\"at least 1\""),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn renaming_resolves_method_collisions() {
+    let expanded = expand_to_string(quote! {
+        struct CartPage {
+            #[thirtyfour_actions(css = "#total", methods(wait_for_value))]
+            total: By,
+            #[thirtyfour_actions(rename = "grand_total", css = "#value-total", methods(wait_for))]
+            value_total: By,
+        }
+    });
+    assert!(!expanded.contains("compile_error"), "{}", expanded);
+    let names = fn_names(&expanded);
+    for name in ["wait_for_value_total", "wait_for_grand_total"] {
+        assert!(
+            names.iter().any(|n| n == name),
+            "missing `{}` in {:?}",
+            name,
+            names
+        );
+    }
+    assert!(
+        contains_code(&expanded, "thirtyfour::By::clone(&self.value_total)"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn locator_builder_builds_string_fields() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(locator_builder = "crate::locators::by_testid")]
+        struct Login {
+            #[thirtyfour_actions(locator = "login-submit", methods(click))]
+            submit: String,
+        }
+    });
+    assert!(
+        contains_code(
+            &expanded,
+            "driver.query(crate::locators::by_testid(&self.submit))"
+        ) && contains_code(&expanded, "submit: \"login-submit\".into()"),
+        "{}",
+        expanded
+    );
+    let state = expand_to_string(quote! {
+        #[thirtyfour_actions(locator_builder = "crate::locators::by_testid")]
+        enum Login {
+            Form {
+                submit: String,
+            },
+        }
+    });
+    assert!(
+        state.contains(
+            "field 'submit' of state 'Form' is built by `locator_builder` without a `locator`"
+        ),
+        "{}",
+        state
+    );
+}
+
+#[test]
+fn naming_applies_to_generated_methods() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(naming = "field_verb")]
+        struct Login {
+            #[thirtyfour_actions(alias = "login", optional, methods(click))]
+            username: By,
+        }
+    });
+    assert_defines(
+        &expanded,
+        &[
+            "username_click",
+            "username_query",
+            "username_query_with_timeout",
+            "login_click",
+        ],
+    );
+    assert!(
+        !fn_names(&expanded).iter().any(|n| n == "click_username")
+            && contains_code(&expanded, "self.username_query(driver)"),
+        "{}",
+        expanded
+    );
+
+    let templated = expand_to_string(quote! {
+        #[thirtyfour_actions(naming = "ui_{field}_{verb}")]
+        struct Login {
+            #[thirtyfour_actions(methods(click))]
+            username: By,
+        }
+    });
+    assert_defines(&templated, &["ui_username_click"]);
+}
+
+#[test]
+fn session_audit_checks_result_returning_methods() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(session_audit = "session", error = "crate::UiError")]
+        struct Login {
+            #[thirtyfour_actions(methods(click, exists))]
+            submit: By,
+            session: SessionAudit,
+        }
+    });
+    let click = expanded
+        .split("fn click_submit(")
+        .nth(1)
+        .unwrap_or_default();
+    let click = click.split("\n    pub ").next().unwrap_or_default();
+    let exists = expanded
+        .split("fn exists_submit(")
+        .nth(1)
+        .unwrap_or_default();
+    let exists = exists.split("\n    pub ").next().unwrap_or_default();
+    assert!(
+        click.contains("self.session.check(&driver.session_id().to_string())")
+            && click.contains("Self::thirtyfour_actions_error(")
+            && !exists.is_empty()
+            && !exists.contains("self.session.check(")
+            && !expanded.contains("panic!")
+            && !expanded.contains("anyhow::anyhow!"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn expands_group_structs() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(url = "https://example.com/login", alerts, dry_run, timeout_secs = 5)]
+        struct LoginPage {
+            #[thirtyfour_actions(css = "header .logo", methods(click, wait_for), group = "header")]
+            logo: By,
+            #[thirtyfour_actions(id = "username", methods(enter_keys))]
+            username: By,
+        }
+    });
+    // The page's own methods, then the group struct's, then the accessor
+    let (page, group) = expanded
+        .split_once("struct LoginPageHeader")
+        .expect("group struct");
+    let (group, _) = group.split_once("fn header(").expect("group accessor");
+    for method in ["open", "accept_alert", "click_logo", "enter_keys_username"] {
+        assert!(
+            fn_names(page).iter().any(|n| n == method),
+            "page lacks `{}`",
+            method
+        );
+    }
+    let group_methods = fn_names(group);
+    for method in ["click_logo", "wait_for_logo_default", "dry_run"] {
+        assert!(
+            group_methods.iter().any(|n| n == method),
+            "group lacks `{}`",
+            method
+        );
+    }
+    for method in ["open", "accept_alert", "enter_keys_username"] {
+        assert!(
+            !group_methods.iter().any(|n| n == method),
+            "group has `{}`",
+            method
+        );
+    }
+    assert!(
+        contains_code(&expanded, "logo: Clone::clone(&self.logo)"),
+        "{}",
+        expanded
+    );
+    assert_snapshot("group_structs", &expanded);
+}
+
+#[test]
+fn vis_restricts_generated_methods() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(vis = "pub(crate)")]
+        struct Login {
+            #[thirtyfour_actions(methods(click))]
+            submit: By,
+            #[thirtyfour_actions(methods(click), vis = "pub(super)")]
+            help: By,
+        }
+    });
+    assert!(
+        contains_code(&expanded, "pub(crate) async fn click_submit(")
+            && contains_code(&expanded, "pub(super) async fn click_help(")
+            && !contains_code(&expanded, "pub async fn click_"),
+        "{}",
+        expanded
+    );
+}
+
+#[test]
+fn expands_crate_paths() {
+    let expanded = expand_to_string(quote! {
+        #[thirtyfour_actions(
+            field_enum,
+            crate = "::facade::thirtyfour",
+            anyhow = "::facade::anyhow",
+            log = "::facade::log",
+            tokio = "::facade::tokio",
+            serde_json = "::facade::serde_json"
+        )]
+        struct LoginPage {
+            #[thirtyfour_actions(id = "username", methods(click, wait_for, get_attributes_all))]
+            username: By,
+        }
+    });
+    assert_facade_paths(&expanded);
+    assert_snapshot("crate_paths", &expanded);
+}
+
+#[test]
+fn runtime_uses_crate_paths() {
+    let paths = CratePaths {
+        thirtyfour: syn::parse_quote!(::facade::thirtyfour),
+        anyhow: syn::parse_quote!(::facade::anyhow),
+        log: syn::parse_quote!(::facade::log),
+        tokio: syn::parse_quote!(::facade::tokio),
+        serde_json: syn::parse_quote!(::facade::serde_json),
+    };
+    let runtime = pretty(runtime_module(&paths));
+    let code: Vec<&str> = runtime
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("///"))
+        .collect();
+    assert_facade_paths(&code.join("\n"));
+}
+
+/// Check no path in `expanded` names a dependency other than through `::facade`
+fn assert_facade_paths(expanded: &str) {
+    let compact: String = expanded.split_whitespace().collect();
+    for krate in ["thirtyfour", "anyhow", "log", "tokio", "serde_json"] {
+        let bare = compact.replace(&format!("::facade::{}::", krate), "");
+        assert!(
+            !bare.contains(&format!("{}::", krate)),
+            "`{}::` should go through the facade:\n{}",
+            krate,
+            expanded
+        );
+    }
+}
@@ -0,0 +1,1150 @@
+//! Passes over generated methods: wrappers adding behavior around their bodies (sinks,
+//! timing, pacing, locks, ...) and the variants, aliases and checks derived from them.
+
+use super::*;
+
+/// Parse the items (methods and constants) of one generated entry
+pub(crate) fn parse_items(method: &TokenStream2) -> syn::Result<Vec<syn::ImplItem>> {
+    syn::parse::Parser::parse2(
+        |input: ParseStream| {
+            let mut items = Vec::new();
+            while !input.is_empty() {
+                items.push(input.parse::<syn::ImplItem>()?);
+            }
+            Ok(items)
+        },
+        method.clone(),
+    )
+}
+
+/// Parse the `fn` items of one generated entry
+pub(crate) fn parse_methods(method: &TokenStream2) -> syn::Result<Vec<syn::ImplItemFn>> {
+    syn::parse::Parser::parse2(
+        |input: ParseStream| {
+            let mut fns = Vec::new();
+            while !input.is_empty() {
+                fns.push(input.parse::<syn::ImplItemFn>()?);
+            }
+            Ok(fns)
+        },
+        method.clone(),
+    )
+}
+
+/// Rewrite every method in `methods` with `f`.
+///
+/// Each entry may hold several generated `fn` items.
+pub(crate) fn map_methods(
+    methods: &mut [TokenStream2],
+    mut f: impl FnMut(syn::ImplItemFn) -> syn::ImplItemFn,
+) -> syn::Result<()> {
+    for method in methods.iter_mut() {
+        let fns = parse_methods(method)?;
+
+        let mut rewritten = TokenStream2::new();
+        for item_fn in fns {
+            let item_fn = f(item_fn);
+            rewritten.extend(quote!(#item_fn));
+        }
+        *method = rewritten;
+    }
+    Ok(())
+}
+
+/// Wrap generated methods so every call is reported to the struct's action sink.
+///
+/// Arguments other than the driver are recorded with their `Debug` rendering,
+/// or as `REDACTED` when `redact` is set.
+pub(crate) fn instrument_methods(
+    methods: &mut [TokenStream2],
+    sink_field: &Ident,
+    page: &str,
+    field: Option<&str>,
+    redact: bool,
+) -> syn::Result<()> {
+    let runtime = runtime_path();
+    let field = match field {
+        Some(name) => quote!(Some(#name)),
+        None => quote!(None),
+    };
+
+    map_methods(methods, |mut item_fn| {
+        let method_name = item_fn.sig.ident.to_string();
+        let output = match &item_fn.sig.output {
+            syn::ReturnType::Type(_, ty) => quote!(#ty),
+            syn::ReturnType::Default => quote!(()),
+        };
+        let returns_result = match &item_fn.sig.output {
+            syn::ReturnType::Type(_, ty) => matches!(&**ty, syn::Type::Path(path)
+                if path.path.segments.last().is_some_and(|seg| seg.ident == "Result")),
+            syn::ReturnType::Default => false,
+        };
+
+        let args = item_fn.sig.inputs.iter().filter_map(|arg| match arg {
+            syn::FnArg::Typed(pat_type) => match &*pat_type.pat {
+                syn::Pat::Ident(pat) if pat.ident != "driver" => {
+                    let arg_ident = &pat.ident;
+                    let arg_name = arg_ident.to_string();
+                    Some(if redact {
+                        quote!((#arg_name, #runtime::REDACTED.to_string()))
+                    } else {
+                        quote!((#arg_name, format!("{:?}", #arg_ident)))
+                    })
+                }
+                _ => None,
+            },
+            syn::FnArg::Receiver(_) => None,
+        });
+        let result = if returns_result {
+            quote!(result.as_ref().map(|_| ()).map_err(|e| e.to_string()))
+        } else {
+            quote!(Ok(()))
+        };
+        // Only values of types `ActionOutput` converts from are recorded, and never redacted ones
+        let value_ty = match &item_fn.sig.output {
+            syn::ReturnType::Type(_, ty) if returns_result => result_ok_type(ty),
+            syn::ReturnType::Type(_, ty) => Some(&**ty),
+            syn::ReturnType::Default => None,
+        };
+        let recorded_output = match value_ty {
+            Some(ty) if !redact && converts_to_action_output(ty) => {
+                if returns_result {
+                    quote!(result.as_ref().ok().map(|value| #runtime::ActionOutput::from(value.clone())))
+                } else {
+                    quote!(Some(#runtime::ActionOutput::from(result.clone())))
+                }
+            }
+            _ => quote!(None),
+        };
+
+        let block = &item_fn.block;
+        item_fn.block = syn::parse_quote! {{
+            let args = vec![#(#args),*];
+            let start = std::time::Instant::now();
+            let result: #output = async #block.await;
+            self.#sink_field.record(#runtime::ActionRecord {
+                page: #page,
+                field: #field,
+                method: #method_name,
+                args,
+                duration: start.elapsed(),
+                result: #result,
+                output: #recorded_output,
+            });
+            result
+        }};
+        item_fn
+    })
+}
+
+/// Record failures of generated methods returning `Result<()>` in the struct's soft assertion
+/// collector, returning `Ok(())` instead.
+///
+/// Only methods for which `soften` holds on their name are changed; returns how many were.
+pub(crate) fn soften_methods(
+    methods: &mut [TokenStream2],
+    collector: &Ident,
+    page: &str,
+    soften: impl Fn(&str) -> bool,
+) -> syn::Result<usize> {
+    let mut softened = 0;
+    map_methods(methods, |mut item_fn| {
+        let method_name = item_fn.sig.ident.to_string();
+        let returns_unit_result = match &item_fn.sig.output {
+            syn::ReturnType::Type(_, ty) => matches!(result_ok_type(ty),
+                Some(syn::Type::Tuple(tuple)) if tuple.elems.is_empty()),
+            syn::ReturnType::Default => false,
+        };
+        if !returns_unit_result || !soften(&method_name) {
+            return item_fn;
+        }
+        softened += 1;
+
+        let output = &item_fn.sig.output;
+        let syn::ReturnType::Type(_, output) = output else {
+            return item_fn;
+        };
+        let block = &item_fn.block;
+        item_fn.block = syn::parse_quote! {{
+            let result: #output = async #block.await;
+            if let Err(e) = result {
+                self.#collector.record(format!("{}::{}: {}", #page, #method_name, e));
+            }
+            Ok(())
+        }};
+        item_fn
+    })?;
+    Ok(softened)
+}
+
+/// The `T` of a `Result<T, ..>` type
+pub(crate) fn result_ok_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether the runtime's `ActionOutput` has a `From` conversion for `ty`
+pub(crate) fn converts_to_action_output(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Tuple(tuple) => {
+            let elems: Vec<String> = tuple
+                .elems
+                .iter()
+                .map(|elem| quote!(#elem).to_string())
+                .collect();
+            elems.is_empty() || elems == ["bool", "String"]
+        }
+        syn::Type::Path(path) => {
+            let Some(segment) = path.path.segments.last() else {
+                return false;
+            };
+            let inner = match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+                    Some(syn::GenericArgument::Type(inner)) => Some(inner),
+                    _ => None,
+                },
+                _ => None,
+            };
+            match (segment.ident.to_string().as_str(), inner) {
+                ("String" | "bool" | "WebElement" | "ElementState", None) => true,
+                ("Vec", Some(inner)) => matches!(inner, syn::Type::Path(inner)
+                    if inner.path.segments.last().is_some_and(|seg| seg.ident == "String" || seg.ident == "WebElement")),
+                ("Option", Some(inner)) => converts_to_action_output(inner),
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Make generated methods log a warning when they take longer than `threshold_ms`.
+pub(crate) fn time_methods(
+    methods: &mut [TokenStream2],
+    threshold_ms: u64,
+    page: &str,
+    field: Option<&str>,
+    log: &syn::Path,
+) -> syn::Result<()> {
+    let target = match field {
+        Some(field) => format!("{}.{}", page, field.trim_start_matches("r#")),
+        None => page.to_string(),
+    };
+    map_methods(methods, |mut item_fn| {
+        let method_name = item_fn.sig.ident.to_string();
+        let output = match &item_fn.sig.output {
+            syn::ReturnType::Type(_, ty) => quote!(#ty),
+            syn::ReturnType::Default => quote!(()),
+        };
+        let warning = if cfg!(feature = "tracing") {
+            quote! {
+                tracing::warn!(
+                    action = #method_name, target = #target, elapsed = ?elapsed, threshold_ms = #threshold_ms,
+                    "Slow action"
+                );
+            }
+        } else {
+            quote! {
+                #log::warn!(
+                    "Slow action {} on {}: took {:?} (threshold {} ms)",
+                    #method_name, #target, elapsed, #threshold_ms
+                );
+            }
+        };
+        let block = &item_fn.block;
+        item_fn.block = syn::parse_quote! {{
+            let start = std::time::Instant::now();
+            let result: #output = async #block.await;
+            let elapsed = start.elapsed();
+            if elapsed > std::time::Duration::from_millis(#threshold_ms) {
+                #warning
+            }
+            result
+        }};
+        item_fn
+    })
+}
+
+/// Make generated methods log at debug level when they succeed, naming the action, field and
+/// duration, while the struct's `success_logging()` flag is set.
+///
+/// Methods not returning a `Result` always count as successful.
+pub(crate) fn success_log_methods(
+    methods: &mut [TokenStream2],
+    page: &str,
+    field: Option<&str>,
+    log: &syn::Path,
+) -> syn::Result<()> {
+    let target = match field {
+        Some(field) => format!("{}.{}", page, field.trim_start_matches("r#")),
+        None => page.to_string(),
+    };
+    map_methods(methods, |mut item_fn| {
+        let method_name = item_fn.sig.ident.to_string();
+        let enabled = quote!(Self::success_logging().load(std::sync::atomic::Ordering::Relaxed));
+        let (output, should_log) = match &item_fn.sig.output {
+            syn::ReturnType::Type(_, ty) => {
+                let is_result = matches!(&**ty, syn::Type::Path(path)
+                    if path.path.segments.last().is_some_and(|seg| seg.ident == "Result"));
+                let should_log = if is_result {
+                    quote!(result.is_ok() && #enabled)
+                } else {
+                    enabled
+                };
+                (quote!(#ty), should_log)
+            }
+            syn::ReturnType::Default => (quote!(()), enabled),
+        };
+        let message = if cfg!(feature = "tracing") {
+            quote! {
+                tracing::debug!(action = #method_name, target = #target, elapsed = ?elapsed, "Action succeeded");
+            }
+        } else {
+            quote! {
+                #log::debug!("Action {} on {} succeeded in {:?}", #method_name, #target, elapsed);
+            }
+        };
+        let block = &item_fn.block;
+        item_fn.block = syn::parse_quote! {{
+            let start = std::time::Instant::now();
+            let result: #output = async #block.await;
+            let elapsed = start.elapsed();
+            if #should_log {
+                #message
+            }
+            result
+        }};
+        item_fn
+    })
+}
+
+/// Generate a `<method>_default` variant of every method in `methods` taking `timeout_secs`,
+/// which calls it with the configured default instead.
+pub(crate) fn default_timeout_methods(
+    methods: &[TokenStream2],
+    timeout_secs: u64,
+) -> syn::Result<Vec<TokenStream2>> {
+    let is_timeout = |arg: &syn::FnArg| {
+        matches!(arg, syn::FnArg::Typed(pat_type)
+            if matches!(&*pat_type.pat, syn::Pat::Ident(pat) if pat.ident == "timeout_secs"))
+    };
+
+    let mut defaults = Vec::new();
+    for method in methods {
+        for item_fn in parse_methods(method)? {
+            if !item_fn.sig.inputs.iter().any(is_timeout) {
+                continue;
+            }
+            let name = &item_fn.sig.ident;
+            let args = item_fn.sig.inputs.iter().filter_map(|arg| match arg {
+                _ if is_timeout(arg) => Some(quote!(#timeout_secs)),
+                syn::FnArg::Typed(pat_type) => match &*pat_type.pat {
+                    syn::Pat::Ident(pat) => {
+                        let arg_ident = &pat.ident;
+                        Some(quote!(#arg_ident))
+                    }
+                    _ => None,
+                },
+                syn::FnArg::Receiver(_) => None,
+            });
+            let args: Vec<_> = args.collect();
+
+            let mut sig = item_fn.sig.clone();
+            sig.ident = syn::Ident::new(&format!("{}_default", name), name.span());
+            sig.inputs = sig
+                .inputs
+                .into_iter()
+                .filter(|arg| !is_timeout(arg))
+                .collect();
+            let vis = &item_fn.vis;
+            let doc = format!(
+                " Like [`Self::{}`], waiting up to the default of {} seconds.",
+                name, timeout_secs
+            );
+            defaults.push(quote! {
+                #[doc = #doc]
+                #vis #sig {
+                    self.#name(#(#args),*).await
+                }
+            });
+        }
+    }
+    Ok(defaults)
+}
+
+/// Generate a `<method>_with_timeout` variant of every method in `methods` that looks its element
+/// up through one of `query_fns` without taking a `timeout_secs` argument, which waits up to
+/// `timeout_secs` for the element instead.
+pub(crate) fn timeout_override_methods(
+    methods: &[TokenStream2],
+    query_fns: &[Ident],
+) -> syn::Result<Vec<TokenStream2>> {
+    let is_timeout = |arg: &syn::FnArg| {
+        matches!(arg, syn::FnArg::Typed(pat_type)
+            if matches!(&*pat_type.pat, syn::Pat::Ident(pat) if pat.ident == "timeout_secs"))
+    };
+
+    let mut variants = Vec::new();
+    for method in methods {
+        for mut item_fn in parse_methods(method)? {
+            let block = &item_fn.block;
+            let block = quote!(#block);
+            if item_fn.sig.inputs.iter().any(is_timeout)
+                || !query_fns
+                    .iter()
+                    .any(|query_fn| contains_ident(block.clone(), &query_fn.to_string()))
+            {
+                continue;
+            }
+            let name = item_fn.sig.ident.clone();
+            let doc = format!(
+                " Like [`Self::{}`], waiting up to `timeout_secs` for the element instead.",
+                name
+            );
+            item_fn.attrs.retain(|attr| !attr.path().is_ident("doc"));
+            item_fn.attrs.insert(0, syn::parse_quote!(#[doc = #doc]));
+            item_fn.sig.ident = syn::Ident::new(&format!("{}_with_timeout", name), name.span());
+            item_fn
+                .sig
+                .inputs
+                .push(syn::parse_quote!(timeout_secs: u64));
+            item_fn.block = syn::parse2(with_timeout_queries(block, query_fns))?;
+            variants.push(quote!(#item_fn));
+        }
+    }
+    Ok(variants)
+}
+
+/// Rewrite calls to `query_fns` into calls to their `_with_timeout` variants passing `timeout_secs`
+pub(crate) fn with_timeout_queries(tokens: TokenStream2, query_fns: &[Ident]) -> TokenStream2 {
+    use proc_macro2::TokenTree;
+
+    let mut out = TokenStream2::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident)
+                if query_fns.contains(&ident)
+                    && matches!(tokens.peek(), Some(TokenTree::Group(group))
+                        if group.delimiter() == proc_macro2::Delimiter::Parenthesis) =>
+            {
+                let Some(TokenTree::Group(args)) = tokens.next() else {
+                    unreachable!()
+                };
+                let variant = syn::Ident::new(&format!("{}_with_timeout", ident), ident.span());
+                let args = args.stream();
+                out.extend(quote!(#variant(#args, timeout_secs)));
+            }
+            TokenTree::Group(group) => {
+                let mut rewritten = proc_macro2::Group::new(
+                    group.delimiter(),
+                    with_timeout_queries(group.stream(), query_fns),
+                );
+                rewritten.set_span(group.span());
+                out.extend([TokenTree::Group(rewritten)]);
+            }
+            token => out.extend([token]),
+        }
+    }
+    out
+}
+
+/// Generate a copy of every public method and constant in `methods` with `field` in its name
+/// replaced by `alias`, delegating to the original; method names follow `naming`.
+pub(crate) fn alias_methods(
+    methods: &[TokenStream2],
+    field: &Ident,
+    alias: &Ident,
+    naming: &MethodNaming,
+) -> syn::Result<Vec<TokenStream2>> {
+    let field_name = field.unraw().to_string();
+    let alias_name = alias.unraw().to_string();
+    let field_upper = field_name.to_uppercase();
+    let alias_upper = alias_name.to_uppercase();
+
+    let mut aliases = Vec::new();
+    for method in methods {
+        for item in parse_items(method)? {
+            match item {
+                syn::ImplItem::Fn(item_fn) if matches!(item_fn.vis, syn::Visibility::Public(_)) => {
+                    let name = item_fn.sig.ident.to_string();
+                    let Some((verb, suffix)) = naming.split(&name, &field_name) else {
+                        continue;
+                    };
+                    let alias_fn = naming.method_name(verb, &alias_name, suffix);
+
+                    let args = item_fn.sig.inputs.iter().filter_map(|arg| match arg {
+                        syn::FnArg::Typed(pat_type) => match &*pat_type.pat {
+                            syn::Pat::Ident(pat) => {
+                                let arg_ident = &pat.ident;
+                                Some(quote!(#arg_ident))
+                            }
+                            _ => None,
+                        },
+                        syn::FnArg::Receiver(_) => None,
+                    });
+                    let original = &item_fn.sig.ident;
+                    let call = if item_fn.sig.asyncness.is_some() {
+                        quote!(self.#original(#(#args),*).await)
+                    } else {
+                        quote!(self.#original(#(#args),*))
+                    };
+                    let mut sig = item_fn.sig.clone();
+                    sig.ident = syn::Ident::new(&alias_fn, alias.span());
+                    let doc = format!(" Alias of [`Self::{}`].", original);
+                    aliases.push(quote! {
+                        #[doc = #doc]
+                        pub #sig {
+                            #call
+                        }
+                    });
+                }
+                syn::ImplItem::Const(item_const) => {
+                    let name = item_const.ident.to_string();
+                    let Some(rest) = name.strip_prefix(&field_upper) else {
+                        continue;
+                    };
+                    let original = &item_const.ident;
+                    let alias_const =
+                        syn::Ident::new(&format!("{}{}", alias_upper, rest), alias.span());
+                    let ty = &item_const.ty;
+                    let doc = format!(" Alias of [`Self::{}`].", original);
+                    aliases.push(quote! {
+                        #[doc = #doc]
+                        pub const #alias_const: #ty = Self::#original;
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(aliases)
+}
+
+/// Give the public methods and constants in `methods` the visibility `vis` instead, e.g.
+/// `pub(crate)` to keep them out of a crate's public API.
+pub(crate) fn restrict_methods(
+    methods: &mut [TokenStream2],
+    vis: &syn::Visibility,
+) -> syn::Result<()> {
+    for method in methods {
+        let mut items = parse_items(method)?;
+        for item in &mut items {
+            let item_vis = match item {
+                syn::ImplItem::Fn(item_fn) => &mut item_fn.vis,
+                syn::ImplItem::Const(item_const) => &mut item_const.vis,
+                _ => continue,
+            };
+            if matches!(item_vis, syn::Visibility::Public(_)) {
+                *item_vis = vis.clone();
+            }
+        }
+        *method = quote!(#(#items)*);
+    }
+    Ok(())
+}
+
+/// Compile generated items only in consumer builds enabling the cargo `feature`.
+pub(crate) fn cfg_methods(methods: &mut [TokenStream2], feature: &syn::LitStr) -> syn::Result<()> {
+    for method in methods {
+        let items = parse_items(method)?;
+        *method = quote!(#(#[cfg(feature = #feature)] #items)*);
+    }
+    Ok(())
+}
+
+/// Make the actions of an `optional` field succeed when the element is absent.
+///
+/// The `None` arm of the match on the field's query returns `Ok(false)` from methods returning
+/// `bool` and `Ok(None)` from methods returning an `Option`; any other `T` becomes `Option<T>`,
+/// `None` when the element is absent.
+pub(crate) fn optional_methods(
+    methods: &mut [TokenStream2],
+    query_fn_ident: &Ident,
+    try_query_fn_ident: &Ident,
+    error_type: &TokenStream2,
+) -> syn::Result<()> {
+    map_methods(methods, |mut item_fn| {
+        let ok_ty = match &item_fn.sig.output {
+            syn::ReturnType::Type(_, ty) => match &**ty {
+                syn::Type::Path(path) => {
+                    match path.path.segments.last().map(|seg| &seg.arguments) {
+                        Some(syn::PathArguments::AngleBracketed(args)) => match args.args.first() {
+                            Some(syn::GenericArgument::Type(ty)) => Some(ty.clone()),
+                            _ => None,
+                        },
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            syn::ReturnType::Default => None,
+        };
+        // Actions end with a match on the field's query; other methods are left alone
+        let query_match = match item_fn.block.stmts.last_mut() {
+            Some(syn::Stmt::Expr(syn::Expr::Match(query_match), None)) => query_match,
+            _ => return item_fn,
+        };
+        // `unique` fields match on `self.try_query_<field>(driver).await?` instead
+        let query = match &*query_match.expr {
+            syn::Expr::Try(query) => &*query.expr,
+            query => query,
+        };
+        let queries_field = matches!(query, syn::Expr::Await(query)
+            if matches!(&*query.base, syn::Expr::MethodCall(call)
+                if call.method == *query_fn_ident || call.method == *try_query_fn_ident));
+        let (Some(ok_ty), true) = (ok_ty, queries_field) else {
+            return item_fn;
+        };
+        let is_type = |name: &str| {
+            matches!(&ok_ty, syn::Type::Path(path)
+                if path.path.segments.last().is_some_and(|seg| seg.ident == name))
+        };
+        let (absent, wrap) = if is_type("bool") {
+            (quote!(false), false)
+        } else {
+            (quote!(None), !is_type("Option"))
+        };
+
+        for arm in &mut query_match.arms {
+            match &arm.pat {
+                syn::Pat::Ident(pat) if pat.ident == "None" => {
+                    arm.body = syn::parse_quote!(Ok(#absent));
+                }
+                _ if wrap => {
+                    let body = match &*arm.body {
+                        body @ syn::Expr::Block(_) => quote!(#body),
+                        body => quote!({ #body }),
+                    };
+                    arm.body = syn::parse_quote!({
+                        let result: std::result::Result<#ok_ty, #error_type> = async #body.await;
+                        result.map(Some)
+                    });
+                }
+                _ => {}
+            }
+        }
+        if wrap {
+            item_fn.sig.output =
+                syn::parse_quote!(-> std::result::Result<Option<#ok_ty>, #error_type>);
+        }
+        item_fn
+    })
+}
+
+/// Fail with an error spanning both definitions when two generated methods or constants share a
+/// name, e.g. an action of one field and an alias of another, instead of leaving rustc to report
+/// the duplicate deep in the expansion.
+pub(crate) fn check_name_collisions(methods: &[TokenStream2]) -> syn::Result<()> {
+    let mut defined: HashMap<String, proc_macro2::Span> = HashMap::new();
+    for method in methods {
+        for item in parse_items(method)? {
+            let ident = match &item {
+                syn::ImplItem::Fn(item_fn) => &item_fn.sig.ident,
+                syn::ImplItem::Const(item_const) => &item_const.ident,
+                _ => continue,
+            };
+            let name = ident.to_string();
+            if let Some(first) = defined.get(&name) {
+                let mut error = syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "generated method `{}` is defined more than once; give one of the fields \
+                         another name in generated methods with `rename = \"...\"`",
+                        name
+                    ),
+                );
+                error.combine(syn::Error::new(
+                    *first,
+                    format!("`{}` is also generated here", name),
+                ));
+                return Err(error);
+            }
+            defined.insert(name, ident.span());
+        }
+    }
+    Ok(())
+}
+
+/// Make generated methods hold their driver session's lock while acting.
+pub(crate) fn lock_methods(methods: &mut [TokenStream2]) -> syn::Result<()> {
+    let runtime = runtime_path();
+    map_methods(methods, |mut item_fn| {
+        let block = &item_fn.block;
+        item_fn.block = syn::parse_quote! {{
+            #runtime::with_session_lock(driver.session_id().to_string(), async #block).await
+        }};
+        item_fn
+    })
+}
+
+/// Make generated methods wait out the struct's minimum interval between actions, tracked by the
+/// `ActionPacer` stored in `pacing.field`, with no wait while the `headless()` switch is on when
+/// `headless_aware`.
+pub(crate) fn pace_methods(
+    methods: &mut [TokenStream2],
+    pacing: &ActionPacing,
+    headless_aware: bool,
+) -> syn::Result<()> {
+    let ActionPacing { interval_ms, field } = pacing;
+    let interval = if headless_aware {
+        quote! {
+            if Self::headless().is_enabled() {
+                std::time::Duration::ZERO
+            } else {
+                std::time::Duration::from_millis(#interval_ms)
+            }
+        }
+    } else {
+        quote!(std::time::Duration::from_millis(#interval_ms))
+    };
+    map_methods(methods, |mut item_fn| {
+        let block = &item_fn.block;
+        item_fn.block = syn::parse_quote! {{
+            self.#field.pace(#interval, async #block).await
+        }};
+        item_fn
+    })
+}
+
+/// Make generated methods check they are called with the driver session recorded by the
+/// `SessionAudit` in `audit_field`, failing with the mismatch before acting otherwise.
+///
+/// Only methods returning a `Result` are audited, as others have no way to report the mismatch.
+pub(crate) fn audit_methods(
+    methods: &mut [TokenStream2],
+    audit_field: &Ident,
+    message_error: &TokenStream2,
+) -> syn::Result<()> {
+    map_methods(methods, |mut item_fn| {
+        let returns_result = match &item_fn.sig.output {
+            syn::ReturnType::Type(_, ty) => matches!(&**ty, syn::Type::Path(path)
+                if path.path.segments.last().is_some_and(|seg| seg.ident == "Result")),
+            syn::ReturnType::Default => false,
+        };
+        if !returns_result {
+            return item_fn;
+        }
+        let method_name = item_fn.sig.ident.to_string();
+        item_fn.block.stmts.insert(
+            0,
+            syn::parse_quote! {
+                if let Err(mismatch) = self.#audit_field.check(&driver.session_id().to_string()) {
+                    return Err(#message_error(format!("{}: {}", #method_name, mismatch)));
+                }
+            },
+        );
+        item_fn
+    })
+}
+
+/// Make generated methods wait for the struct's rate limiter before acting, unless the
+/// `headless()` switch is on when `headless_aware`.
+pub(crate) fn throttle_methods(
+    methods: &mut [TokenStream2],
+    headless_aware: bool,
+) -> syn::Result<()> {
+    let acquire: syn::Stmt = if headless_aware {
+        syn::parse_quote! {
+            if !Self::headless().is_enabled() {
+                Self::rate_limiter().acquire().await;
+            }
+        }
+    } else {
+        syn::parse_quote!(Self::rate_limiter().acquire().await;)
+    };
+    map_methods(methods, |mut item_fn| {
+        item_fn.block.stmts.insert(0, acquire.clone());
+        item_fn
+    })
+}
+
+/// Make generated methods use the driver stored in `driver_field` instead of taking a `driver`
+/// parameter, dropping the driver argument from calls between them.
+///
+/// The field may hold a `WebDriver` or anything borrowing as one, such as `Arc<WebDriver>`.
+pub(crate) fn bind_driver_methods(
+    methods: &mut [TokenStream2],
+    driver_field: &Ident,
+    thirtyfour: &syn::Path,
+) -> syn::Result<()> {
+    let is_driver_param = |input: &syn::FnArg| {
+        matches!(input, syn::FnArg::Typed(param)
+            if matches!(&*param.pat, syn::Pat::Ident(pat) if pat.ident == "driver"))
+    };
+
+    let mut bound = Vec::new();
+    for method in methods.iter() {
+        for item in parse_items(method)? {
+            if let syn::ImplItem::Fn(item_fn) = item
+                && item_fn.sig.receiver().is_some()
+                && item_fn.sig.inputs.iter().any(is_driver_param)
+            {
+                bound.push(item_fn.sig.ident.to_string());
+            }
+        }
+    }
+    for method in methods.iter_mut() {
+        let mut rewritten = TokenStream2::new();
+        for mut item in parse_items(&drop_driver_arguments(method.clone(), &bound))? {
+            if let syn::ImplItem::Fn(item_fn) = &mut item
+                && item_fn.sig.receiver().is_some()
+                && item_fn.sig.inputs.iter().any(is_driver_param)
+            {
+                item_fn.sig.inputs = std::mem::take(&mut item_fn.sig.inputs)
+                    .into_iter()
+                    .filter(|input| !is_driver_param(input))
+                    .collect();
+                // Methods that only delegate no longer use the driver themselves
+                let block = &item_fn.block;
+                if contains_ident(quote!(#block), "driver") {
+                    item_fn.block.stmts.insert(
+                        0,
+                        syn::parse_quote! {
+                            let driver: &#thirtyfour::WebDriver = std::borrow::Borrow::borrow(&self.#driver_field);
+                        },
+                    );
+                }
+            }
+            rewritten.extend(quote!(#item));
+        }
+        *method = rewritten;
+    }
+    Ok(())
+}
+
+/// Declarations and implementations of trait methods delegating to the public methods in
+/// `methods` that take `&self`.
+///
+/// Async methods return boxed `Send` futures so the trait stays usable as a trait object;
+/// generic methods cannot be part of such a trait and are left out.
+pub(crate) fn trait_methods(
+    methods: &[TokenStream2],
+    struct_name: &Ident,
+) -> syn::Result<(Vec<TokenStream2>, Vec<TokenStream2>)> {
+    let mut decls = Vec::new();
+    let mut impls = Vec::new();
+    for method in methods {
+        for item in parse_items(method)? {
+            let syn::ImplItem::Fn(item_fn) = item else {
+                continue;
+            };
+            let sig = &item_fn.sig;
+            if !matches!(item_fn.vis, syn::Visibility::Public(_))
+                || !matches!(sig.receiver(), Some(receiver) if receiver.reference.is_some() && receiver.mutability.is_none())
+                || !sig.generics.params.is_empty()
+            {
+                continue;
+            }
+            let mut args = Vec::new();
+            let mut params = Vec::new();
+            for input in sig.inputs.iter().skip(1) {
+                let syn::FnArg::Typed(pat_type) = input else {
+                    continue;
+                };
+                let syn::Pat::Ident(pat) = &*pat_type.pat else {
+                    continue;
+                };
+                let arg_ident = &pat.ident;
+                args.push(quote!(#arg_ident));
+                params.push((arg_ident, &*pat_type.ty));
+            }
+            let attrs = item_fn
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("doc") || attr.path().is_ident("cfg"));
+            let impl_attrs = item_fn
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("cfg"));
+            let name = &sig.ident;
+            if sig.asyncness.is_some() {
+                // Every elided reference borrows for the lifetime of the returned future
+                let params = params.iter().map(|(arg_ident, ty)| {
+                    let ty = with_lifetime(quote!(#ty));
+                    quote!(#arg_ident: #ty)
+                });
+                let output = match &sig.output {
+                    syn::ReturnType::Default => quote!(()),
+                    syn::ReturnType::Type(_, ty) => quote!(#ty),
+                };
+                let trait_sig = quote! {
+                    fn #name<'page>(&'page self, #(#params),*)
+                        -> std::pin::Pin<Box<dyn std::future::Future<Output = #output> + Send + 'page>>
+                };
+                decls.push(quote!(#(#attrs)* #trait_sig;));
+                impls.push(quote! {
+                    #(#impl_attrs)*
+                    #trait_sig {
+                        Box::pin(async move { #struct_name::#name(self, #(#args),*).await })
+                    }
+                });
+            } else {
+                let params = params.iter().map(|(arg_ident, ty)| quote!(#arg_ident: #ty));
+                let output = &sig.output;
+                let trait_sig = quote!(fn #name(&self, #(#params),*) #output);
+                decls.push(quote!(#(#attrs)* #trait_sig;));
+                impls.push(quote! {
+                    #(#impl_attrs)*
+                    #trait_sig {
+                        #struct_name::#name(self, #(#args),*)
+                    }
+                });
+            }
+        }
+    }
+    Ok((decls, impls))
+}
+
+/// Give every reference in `ty` without a lifetime the `'page` lifetime
+pub(crate) fn with_lifetime(ty: TokenStream2) -> TokenStream2 {
+    use proc_macro2::TokenTree;
+
+    let mut out = TokenStream2::new();
+    let mut tokens = ty.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '&' => {
+                out.extend([TokenTree::Punct(punct)]);
+                if !matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '\'') {
+                    out.extend(quote!('page));
+                }
+            }
+            TokenTree::Group(group) => {
+                let mut rewritten =
+                    proc_macro2::Group::new(group.delimiter(), with_lifetime(group.stream()));
+                rewritten.set_span(group.span());
+                out.extend([TokenTree::Group(rewritten)]);
+            }
+            token => out.extend([token]),
+        }
+    }
+    out
+}
+
+/// Remove the leading `driver` argument from `self.<method>(driver, ...)` calls to `bound` methods
+pub(crate) fn drop_driver_arguments(tokens: TokenStream2, bound: &[String]) -> TokenStream2 {
+    use proc_macro2::TokenTree;
+
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut out = TokenStream2::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Group(group) => {
+                let mut stream = group.stream();
+                let is_bound_call = i >= 3
+                    && matches!(&tokens[i - 3], TokenTree::Ident(ident) if ident == "self")
+                    && matches!(&tokens[i - 2], TokenTree::Punct(p) if p.as_char() == '.')
+                    && matches!(&tokens[i - 1], TokenTree::Ident(ident) if bound.iter().any(|name| ident == name))
+                    && group.delimiter() == proc_macro2::Delimiter::Parenthesis;
+                if is_bound_call {
+                    let args: Vec<TokenTree> = stream.clone().into_iter().collect();
+                    if matches!(args.first(), Some(TokenTree::Ident(ident)) if ident == "driver") {
+                        let skip = if matches!(args.get(1), Some(TokenTree::Punct(p)) if p.as_char() == ',')
+                        {
+                            2
+                        } else {
+                            1
+                        };
+                        stream = args.into_iter().skip(skip).collect();
+                    }
+                }
+                let mut rewritten = proc_macro2::Group::new(
+                    group.delimiter(),
+                    drop_driver_arguments(stream, bound),
+                );
+                rewritten.set_span(group.span());
+                out.extend([TokenTree::Group(rewritten)]);
+            }
+            token => out.extend([token.clone()]),
+        }
+    }
+    out
+}
+
+/// Whether `tokens` contain the identifier `name`, looking into groups
+pub(crate) fn contains_ident(tokens: TokenStream2, name: &str) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => ident == name,
+        proc_macro2::TokenTree::Group(group) => contains_ident(group.stream(), name),
+        _ => false,
+    })
+}
+
+/// Thread the `{placeholder}` values of a field's selector through the field's methods.
+///
+/// Every method building the field's locator with `locator_fn`, directly or through another of
+/// the field's methods, takes the values as `&str` parameters right after `driver`, and calls
+/// between those methods pass them on.
+pub(crate) fn placeholder_methods(
+    methods: &mut [TokenStream2],
+    locator_fn: &Ident,
+    placeholders: &[Ident],
+) -> syn::Result<()> {
+    let is_driver_param = |input: &syn::FnArg| {
+        matches!(input, syn::FnArg::Typed(param)
+            if matches!(&*param.pat, syn::Pat::Ident(pat) if pat.ident == "driver"))
+    };
+
+    // Grown until no further method calls one needing the values
+    let mut templated = vec![locator_fn.to_string()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for method in methods.iter() {
+            for item in parse_items(method)? {
+                let syn::ImplItem::Fn(item_fn) = item else {
+                    continue;
+                };
+                let name = item_fn.sig.ident.to_string();
+                let block = &item_fn.block;
+                if !templated.contains(&name)
+                    && item_fn.sig.inputs.iter().any(is_driver_param)
+                    && templated
+                        .iter()
+                        .any(|callee| contains_ident(quote!(#block), callee))
+                {
+                    templated.push(name);
+                    changed = true;
+                }
+            }
+        }
+    }
+    let callers = &templated[1..];
+
+    for method in methods.iter_mut() {
+        let mut rewritten = TokenStream2::new();
+        for mut item in parse_items(method)? {
+            if let syn::ImplItem::Fn(item_fn) = &mut item {
+                let block = &item_fn.block;
+                let block = pass_placeholder_arguments(quote!(#block), callers, placeholders);
+                item_fn.block = syn::parse_quote!(#block);
+                if callers.contains(&item_fn.sig.ident.to_string()) {
+                    let clash = item_fn.sig.inputs.iter().find_map(|input| match input {
+                        syn::FnArg::Typed(param) => match &*param.pat {
+                            syn::Pat::Ident(pat) if placeholders.contains(&pat.ident) => {
+                                Some(pat.ident.clone())
+                            }
+                            _ => None,
+                        },
+                        syn::FnArg::Receiver(_) => None,
+                    });
+                    if let Some(clash) = clash {
+                        return Err(syn::Error::new(
+                            clash.span(),
+                            format!(
+                                "selector placeholder '{}' clashes with a parameter of `{}`",
+                                clash, item_fn.sig.ident
+                            ),
+                        ));
+                    }
+                    let driver_index = item_fn
+                        .sig
+                        .inputs
+                        .iter()
+                        .position(is_driver_param)
+                        .expect("templated methods take a driver");
+                    let mut inputs: Vec<syn::FnArg> = std::mem::take(&mut item_fn.sig.inputs)
+                        .into_iter()
+                        .collect();
+                    for (offset, placeholder) in placeholders.iter().enumerate() {
+                        inputs.insert(
+                            driver_index + 1 + offset,
+                            syn::parse_quote!(#placeholder: &str),
+                        );
+                    }
+                    item_fn.sig.inputs = inputs.into_iter().collect();
+                }
+            }
+            rewritten.extend(quote!(#item));
+        }
+        *method = rewritten;
+    }
+    Ok(())
+}
+
+/// Add `placeholders` after the leading `driver` argument of `self.<method>(driver, ...)` calls
+/// to `callers`
+pub(crate) fn pass_placeholder_arguments(
+    tokens: TokenStream2,
+    callers: &[String],
+    placeholders: &[Ident],
+) -> TokenStream2 {
+    use proc_macro2::TokenTree;
+
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut out = TokenStream2::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Group(group) => {
+                let mut stream = pass_placeholder_arguments(group.stream(), callers, placeholders);
+                let is_caller_call = i >= 3
+                    && matches!(&tokens[i - 3], TokenTree::Ident(ident) if ident == "self")
+                    && matches!(&tokens[i - 2], TokenTree::Punct(p) if p.as_char() == '.')
+                    && matches!(&tokens[i - 1], TokenTree::Ident(ident) if callers.iter().any(|name| ident == name))
+                    && group.delimiter() == proc_macro2::Delimiter::Parenthesis;
+                let args: Vec<TokenTree> = stream.clone().into_iter().collect();
+                if is_caller_call
+                    && matches!(args.first(), Some(TokenTree::Ident(ident)) if ident == "driver")
+                {
+                    let driver = &args[0];
+                    let rest: TokenStream2 = args.iter().skip(2).cloned().collect();
+                    stream = if rest.is_empty() {
+                        quote!(#driver, #(#placeholders),*)
+                    } else {
+                        quote!(#driver, #(#placeholders,)* #rest)
+                    };
+                }
+                let mut rewritten = proc_macro2::Group::new(group.delimiter(), stream);
+                rewritten.set_span(group.span());
+                out.extend([TokenTree::Group(rewritten)]);
+            }
+            token => out.extend([token.clone()]),
+        }
+    }
+    out
+}
+
+/// Apply the enabled struct-level wrappers to generated methods.
+///
+/// The sink, slow-action warnings and success logs see only the action itself, and pacing and rate
+/// limiting happen before the session lock is taken.
+pub(crate) fn wrap_methods(
+    methods: &mut [TokenStream2],
+    options: &StructOptions,
+    page: &str,
+    field: Option<&str>,
+    sensitive: bool,
+) -> syn::Result<()> {
+    if let Some(audit_field) = &options.session_audit {
+        let (_, message_error) = options.error_tokens();
+        audit_methods(methods, audit_field, &message_error)?;
+    }
+    if let Some(sink_field) = &options.sink {
+        instrument_methods(methods, sink_field, page, field, sensitive)?;
+    }
+    if let Some(threshold_ms) = options.slow_threshold_ms {
+        time_methods(methods, threshold_ms, page, field, &options.crate_paths.log)?;
+    }
+    if options.log_success.is_some() {
+        success_log_methods(methods, page, field, &options.crate_paths.log)?;
+    }
+    if let Some(pacing) = &options.wait_between_actions {
+        pace_methods(methods, pacing, options.headless_aware.is_some())?;
+    }
+    if options.session_lock {
+        lock_methods(methods)?;
+    }
+    if options.rate_limit.is_some() {
+        throttle_methods(methods, options.headless_aware.is_some())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,287 @@
+impl LoginPage {
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_username(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.username)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "username", self.username);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "username", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_username`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_username_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.username))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "username", self.username);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "username", e);
+                None
+            }
+        }
+    }
+    /// Clear input field content.
+    pub async fn clear_username(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_username(driver).await {
+            Some(element) => {
+                element
+                    .clear()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to clear {}: {}", "username", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "username"))),
+        }
+    }
+    /// Enter text into the web element.
+    pub async fn enter_keys_username(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        keys: &str,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_username(driver).await {
+            Some(element) => {
+                element
+                    .send_keys(keys)
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to send keys to {}: {}", "username", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "username"))),
+        }
+    }
+    /// Check if the web element is displayed.
+    pub async fn is_displayed_username(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<bool, anyhow::Error> {
+        match self.query_username(driver).await {
+            Some(element) => {
+                element
+                    .is_displayed()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to check if {} is displayed: {}", "username", e),
+                    ))
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "username"))),
+        }
+    }
+    /// Like [`Self::clear_username`], waiting up to `timeout_secs` for the element instead.
+    pub async fn clear_username_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_username_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .clear()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to clear {}: {}", "username", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "username"))),
+        }
+    }
+    /// Like [`Self::enter_keys_username`], waiting up to `timeout_secs` for the element instead.
+    pub async fn enter_keys_username_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        keys: &str,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_username_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .send_keys(keys)
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to send keys to {}: {}", "username", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "username"))),
+        }
+    }
+    /// Like [`Self::is_displayed_username`], waiting up to `timeout_secs` for the element instead.
+    pub async fn is_displayed_username_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<bool, anyhow::Error> {
+        match self.query_username_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .is_displayed()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to check if {} is displayed: {}", "username", e),
+                    ))
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "username"))),
+        }
+    }
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_submit(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.submit)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "submit", self.submit);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "submit", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_submit`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_submit_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.submit))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "submit", self.submit);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "submit", e);
+                None
+            }
+        }
+    }
+    /// Click on the web element.
+    pub async fn click_submit(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_submit(driver).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "submit", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "submit"))),
+        }
+    }
+    /// Check if the web element is displayed.
+    pub async fn is_displayed_submit(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<bool, anyhow::Error> {
+        match self.query_submit(driver).await {
+            Some(element) => {
+                element
+                    .is_displayed()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to check if {} is displayed: {}", "submit", e),
+                    ))
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "submit"))),
+        }
+    }
+    /// Like [`Self::click_submit`], waiting up to `timeout_secs` for the element instead.
+    pub async fn click_submit_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_submit_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "submit", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "submit"))),
+        }
+    }
+    /// Like [`Self::is_displayed_submit`], waiting up to `timeout_secs` for the element instead.
+    pub async fn is_displayed_submit_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<bool, anyhow::Error> {
+        match self.query_submit_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .is_displayed()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to check if {} is displayed: {}", "submit", e),
+                    ))
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "submit"))),
+        }
+    }
+    /// Map the name of each locator field to its selector, e.g. for reporting which
+    /// locators a page uses.
+    ///
+    /// `resolver`, `skip` and `component` fields have no selector of their own and are left out.
+    pub fn selector_map(
+        &self,
+    ) -> std::collections::HashMap<&'static str, thirtyfour::By> {
+        std::collections::HashMap::from([
+            ("username", thirtyfour::By::clone(&self.username)),
+            ("submit", thirtyfour::By::clone(&self.submit)),
+        ])
+    }
+}
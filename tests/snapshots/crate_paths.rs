@@ -0,0 +1,437 @@
+impl Default for LoginPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+/// Fields of [`LoginPage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum LoginPageField {
+    Username,
+}
+impl LoginPageField {
+    /// Every field, in declaration order.
+    pub const ALL: &'static [Self] = &[Self::Username];
+    /// The field's name as declared in the struct.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Username => "username",
+        }
+    }
+}
+impl LoginPage {
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_username(
+        &self,
+        driver: &::facade::thirtyfour::WebDriver,
+    ) -> Option<::facade::thirtyfour::WebElement> {
+        match driver
+            .query(::facade::thirtyfour::By::clone(&self.username))
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                ::facade::log::debug!(
+                    "Element {} not found ({:?})", "username", self.username
+                );
+                None
+            }
+            Err(e) => {
+                ::facade::log::error!("Error querying element {}: {}", "username", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_username`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_username_with_timeout(
+        &self,
+        driver: &::facade::thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<::facade::thirtyfour::WebElement> {
+        match driver
+            .query(::facade::thirtyfour::By::clone(&self.username))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                ::facade::log::debug!(
+                    "Element {} not found ({:?})", "username", self.username
+                );
+                None
+            }
+            Err(e) => {
+                ::facade::log::error!("Error querying element {}: {}", "username", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const USERNAME_SELECTOR_DESC: &'static str = "By::Id(\"username\")";
+    /// Click on the web element.
+    pub async fn click_username(
+        &self,
+        driver: &::facade::thirtyfour::WebDriver,
+    ) -> std::result::Result<(), ::facade::anyhow::Error> {
+        match self.query_username(driver).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| ::facade::anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "username", e),
+                    ))?;
+                Ok(())
+            }
+            None => {
+                Err(
+                    ::facade::anyhow::Error::msg(
+                        format!("Element {} not found", "username"),
+                    ),
+                )
+            }
+        }
+    }
+    /// Get `attribute` of every element matching the selector, in document order, reading
+    /// them all in a single script call (e.g. every `href` of a list of links).
+    ///
+    /// Elements without the attribute give `None`; no matches give an empty list.
+    pub async fn get_attributes_all_username(
+        &self,
+        driver: &::facade::thirtyfour::WebDriver,
+        attribute: &str,
+    ) -> std::result::Result<Vec<Option<String>>, ::facade::anyhow::Error> {
+        let elements = driver
+            .query(::facade::thirtyfour::By::clone(&self.username))
+            .nowait()
+            .all_from_selector()
+            .await
+            .map_err(|e| ::facade::anyhow::Error::msg(
+                format!("Failed to find elements for {}: {}", "username", e),
+            ))?;
+        if elements.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ret = driver
+            .execute(
+                r#"return Array.from(arguments[0], element => element.getAttribute(arguments[1]));"#,
+                vec![
+                    ::facade::serde_json::Value::Array(elements.into_iter()
+                    .map(Into::into).collect()), attribute.into(),
+                ],
+            )
+            .await
+            .map_err(|e| ::facade::anyhow::Error::msg(
+                format!(
+                    "Failed to get attribute '{}' from {}: {}", attribute, "username", e
+                ),
+            ))?;
+        let values = ret
+            .json()
+            .as_array()
+            .ok_or_else(|| ::facade::anyhow::Error::msg(
+                format!("Unexpected attribute result for {}", "username"),
+            ))?
+            .iter()
+            .map(|value| value.as_str().map(|value| value.to_string()))
+            .collect();
+        Ok(values)
+    }
+    /// Wait for the element to be present and visible with timeout.
+    pub async fn wait_for_username(
+        &self,
+        driver: &::facade::thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<::facade::thirtyfour::WebElement, ::facade::anyhow::Error> {
+        use std::time::Duration;
+        let element = driver
+            .query(::facade::thirtyfour::By::clone(&self.username))
+            .wait(Duration::from_secs(timeout_secs), Duration::from_millis(500u64))
+            .visible()
+            .first()
+            .await
+            .map_err(|e| ::facade::anyhow::Error::msg(
+                format!("Timed out waiting for {} to be visible: {}", "username", e),
+            ))?;
+        Ok(element)
+    }
+    /// Like [`Self::click_username`], waiting up to `timeout_secs` for the element instead.
+    pub async fn click_username_with_timeout(
+        &self,
+        driver: &::facade::thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), ::facade::anyhow::Error> {
+        match self.query_username_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| ::facade::anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "username", e),
+                    ))?;
+                Ok(())
+            }
+            None => {
+                Err(
+                    ::facade::anyhow::Error::msg(
+                        format!("Element {} not found", "username"),
+                    ),
+                )
+            }
+        }
+    }
+    /// Create the page object from the selectors declared for its fields.
+    pub fn new() -> Self {
+        Self {
+            username: ::facade::thirtyfour::By::Id("username"),
+        }
+    }
+    /// Query every field not marked `lazy` concurrently, returning the elements found.
+    pub async fn resolve_all(
+        &self,
+        driver: &::facade::thirtyfour::WebDriver,
+    ) -> std::collections::HashMap<LoginPageField, ::facade::thirtyfour::WebElement> {
+        use std::task::Poll;
+        let mut queries: Vec<_> = [LoginPageField::Username]
+            .into_iter()
+            .map(|field| Some(
+                Box::pin(async move {
+                    (
+                        field,
+                        match field {
+                            LoginPageField::Username => self.query_username(driver).await,
+                        },
+                    )
+                }),
+            ))
+            .collect();
+        let mut elements = std::collections::HashMap::new();
+        std::future::poll_fn(|cx| {
+                let mut done = true;
+                for slot in queries.iter_mut() {
+                    if let Some(query) = slot {
+                        match std::future::Future::poll(query.as_mut(), cx) {
+                            Poll::Ready((field, element)) => {
+                                if let Some(element) = element {
+                                    elements.insert(field, element);
+                                }
+                                *slot = None;
+                            }
+                            Poll::Pending => done = false,
+                        }
+                    }
+                }
+                if done { Poll::Ready(()) } else { Poll::Pending }
+            })
+            .await;
+        elements
+    }
+    /// Query every field concurrently, including `lazy` ones, returning the elements found.
+    pub async fn resolve_all_include_lazy(
+        &self,
+        driver: &::facade::thirtyfour::WebDriver,
+    ) -> std::collections::HashMap<LoginPageField, ::facade::thirtyfour::WebElement> {
+        use std::task::Poll;
+        let mut queries: Vec<_> = [LoginPageField::Username]
+            .into_iter()
+            .map(|field| Some(
+                Box::pin(async move {
+                    (
+                        field,
+                        match field {
+                            LoginPageField::Username => self.query_username(driver).await,
+                        },
+                    )
+                }),
+            ))
+            .collect();
+        let mut elements = std::collections::HashMap::new();
+        std::future::poll_fn(|cx| {
+                let mut done = true;
+                for slot in queries.iter_mut() {
+                    if let Some(query) = slot {
+                        match std::future::Future::poll(query.as_mut(), cx) {
+                            Poll::Ready((field, element)) => {
+                                if let Some(element) = element {
+                                    elements.insert(field, element);
+                                }
+                                *slot = None;
+                            }
+                            Poll::Pending => done = false,
+                        }
+                    }
+                }
+                if done { Poll::Ready(()) } else { Poll::Pending }
+            })
+            .await;
+        elements
+    }
+    /// Wait until any of `fields` is visible, returning the first one found.
+    pub async fn wait_for_any(
+        &self,
+        driver: &::facade::thirtyfour::WebDriver,
+        fields: &[LoginPageField],
+        timeout_secs: u64,
+    ) -> std::result::Result<
+        (LoginPageField, ::facade::thirtyfour::WebElement),
+        ::facade::anyhow::Error,
+    > {
+        use std::time::{Duration, Instant};
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        loop {
+            for field in fields.iter().copied() {
+                if let Some(element) = match field {
+                    LoginPageField::Username => {
+                        self.query_username_with_timeout(driver, 0).await
+                    }
+                } {
+                    if element.is_displayed().await.unwrap_or(false) {
+                        return Ok((field, element));
+                    }
+                }
+            }
+            if Instant::now() >= deadline {
+                let names: Vec<&str> = fields.iter().map(|field| field.name()).collect();
+                return Err(
+                    ::facade::anyhow::Error::msg(
+                        format!(
+                            "Timed out waiting for any of [{}] to be visible", names
+                            .join(", ")
+                        ),
+                    ),
+                );
+            }
+            ::facade::tokio::time::sleep(Duration::from_millis(500u64)).await;
+        }
+    }
+    /// Wait until all of `fields` are visible, returning their elements in order.
+    pub async fn wait_for_all(
+        &self,
+        driver: &::facade::thirtyfour::WebDriver,
+        fields: &[LoginPageField],
+        timeout_secs: u64,
+    ) -> std::result::Result<
+        Vec<::facade::thirtyfour::WebElement>,
+        ::facade::anyhow::Error,
+    > {
+        use std::time::{Duration, Instant};
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let mut elements = Vec::with_capacity(fields.len());
+        for field in fields.iter().copied() {
+            let element = loop {
+                if let Some(element) = match field {
+                    LoginPageField::Username => {
+                        self.query_username_with_timeout(driver, 0).await
+                    }
+                } {
+                    if element.is_displayed().await.unwrap_or(false) {
+                        break element;
+                    }
+                }
+                if Instant::now() >= deadline {
+                    return Err(
+                        ::facade::anyhow::Error::msg(
+                            format!(
+                                "Timed out waiting for {} to be visible", field.name()
+                            ),
+                        ),
+                    );
+                }
+                ::facade::tokio::time::sleep(Duration::from_millis(500u64)).await;
+            };
+            elements.push(element);
+        }
+        Ok(elements)
+    }
+    /// Read the text of each of `fields` concurrently.
+    pub async fn get_texts_of(
+        &self,
+        driver: &::facade::thirtyfour::WebDriver,
+        fields: &[LoginPageField],
+    ) -> std::result::Result<
+        std::collections::HashMap<LoginPageField, String>,
+        ::facade::anyhow::Error,
+    > {
+        use std::task::Poll;
+        let mut reads: Vec<_> = fields
+            .iter()
+            .copied()
+            .map(|field| {
+                Some(
+                    Box::pin(async move {
+                        let element = match field {
+                            LoginPageField::Username => self.query_username(driver).await,
+                        };
+                        let text = match element {
+                            Some(element) => {
+                                element
+                                    .text()
+                                    .await
+                                    .map_err(|e| ::facade::anyhow::Error::msg(
+                                        format!("Failed to get text from {}: {}", field.name(), e),
+                                    ))
+                            }
+                            None => {
+                                Err(
+                                    ::facade::anyhow::Error::msg(
+                                        format!("Element {} not found", field.name()),
+                                    ),
+                                )
+                            }
+                        };
+                        (field, text)
+                    }),
+                )
+            })
+            .collect();
+        let mut texts = std::collections::HashMap::with_capacity(fields.len());
+        let mut failure = None;
+        std::future::poll_fn(|cx| {
+                let mut done = true;
+                for slot in reads.iter_mut() {
+                    if let Some(read) = slot {
+                        match std::future::Future::poll(read.as_mut(), cx) {
+                            Poll::Ready((field, Ok(text))) => {
+                                texts.insert(field, text);
+                                *slot = None;
+                            }
+                            Poll::Ready((_, Err(e))) => {
+                                failure.get_or_insert(e);
+                                *slot = None;
+                            }
+                            Poll::Pending => done = false,
+                        }
+                    }
+                }
+                if done { Poll::Ready(()) } else { Poll::Pending }
+            })
+            .await;
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(texts),
+        }
+    }
+    /// Map the name of each locator field to its selector, e.g. for reporting which
+    /// locators a page uses.
+    ///
+    /// `resolver`, `skip` and `component` fields have no selector of their own and are left out.
+    pub fn selector_map(
+        &self,
+    ) -> std::collections::HashMap<&'static str, ::facade::thirtyfour::By> {
+        std::collections::HashMap::from([
+            ("username", ::facade::thirtyfour::By::clone(&self.username)),
+        ])
+    }
+    /// Get the locator of a field.
+    pub fn locator(&self, field: LoginPageField) -> &::facade::thirtyfour::By {
+        match field {
+            LoginPageField::Username => &self.username,
+        }
+    }
+}
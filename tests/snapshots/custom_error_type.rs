@@ -0,0 +1,174 @@
+impl Default for LoginPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl LoginPage {
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_username(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.username)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "username", self.username);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "username", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_username`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_username_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.username))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "username", self.username);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "username", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const USERNAME_SELECTOR_DESC: &'static str = "By::Id(\"username\")";
+    /// Click on the web element.
+    pub async fn click_username(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), crate::UiError> {
+        match self.query_username(driver).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| Self::thirtyfour_actions_error(
+                        format!("Failed to click {}: {}", "username", e),
+                    ))?;
+                Ok(())
+            }
+            None => {
+                Err(
+                    Self::thirtyfour_actions_error(
+                        format!("Element {} not found", "username"),
+                    ),
+                )
+            }
+        }
+    }
+    /// Get the text content of the web element.
+    pub async fn get_text_username(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<String, crate::UiError> {
+        match self.query_username(driver).await {
+            Some(element) => {
+                element
+                    .text()
+                    .await
+                    .map_err(|e| Self::thirtyfour_actions_error(
+                        format!("Failed to get text from {}: {}", "username", e),
+                    ))
+            }
+            None => {
+                Err(
+                    Self::thirtyfour_actions_error(
+                        format!("Element {} not found", "username"),
+                    ),
+                )
+            }
+        }
+    }
+    /// Like [`Self::click_username`], waiting up to `timeout_secs` for the element instead.
+    pub async fn click_username_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), crate::UiError> {
+        match self.query_username_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| Self::thirtyfour_actions_error(
+                        format!("Failed to click {}: {}", "username", e),
+                    ))?;
+                Ok(())
+            }
+            None => {
+                Err(
+                    Self::thirtyfour_actions_error(
+                        format!("Element {} not found", "username"),
+                    ),
+                )
+            }
+        }
+    }
+    /// Like [`Self::get_text_username`], waiting up to `timeout_secs` for the element instead.
+    pub async fn get_text_username_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<String, crate::UiError> {
+        match self.query_username_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .text()
+                    .await
+                    .map_err(|e| Self::thirtyfour_actions_error(
+                        format!("Failed to get text from {}: {}", "username", e),
+                    ))
+            }
+            None => {
+                Err(
+                    Self::thirtyfour_actions_error(
+                        format!("Element {} not found", "username"),
+                    ),
+                )
+            }
+        }
+    }
+    /// Create the page object from the selectors declared for its fields.
+    pub fn new() -> Self {
+        Self {
+            username: thirtyfour::By::Id("username"),
+        }
+    }
+    /// Turn an error message of the generated methods into the configured error type.
+    #[allow(dead_code)]
+    fn thirtyfour_actions_error(message: String) -> crate::UiError {
+        <crate::UiError as From<
+            thirtyfour::error::WebDriverError,
+        >>::from(thirtyfour::error::WebDriverError::CustomError(message))
+    }
+    /// Map the name of each locator field to its selector, e.g. for reporting which
+    /// locators a page uses.
+    ///
+    /// `resolver`, `skip` and `component` fields have no selector of their own and are left out.
+    pub fn selector_map(
+        &self,
+    ) -> std::collections::HashMap<&'static str, thirtyfour::By> {
+        std::collections::HashMap::from([
+            ("username", thirtyfour::By::clone(&self.username)),
+        ])
+    }
+}
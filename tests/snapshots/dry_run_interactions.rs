@@ -0,0 +1,848 @@
+impl Default for MenuPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl MenuPage {
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_menu(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.menu)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "menu", self.menu);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "menu", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_menu`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_menu_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.menu))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "menu", self.menu);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "menu", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const MENU_SELECTOR_DESC: &'static str = "By::Css(\"nav .menu\")";
+    /// Add a CSS class to the element through its `classList`.
+    pub async fn add_class_menu(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        class_name: &str,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_menu(driver).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is not visible", "add_class", "menu"
+                                ),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is disabled", "add_class", "menu"),
+                            ),
+                        );
+                    }
+                    Self::dry_run()
+                        .record(format!("add_class {} {:?}", "menu", class_name));
+                    return Ok(());
+                }
+                driver
+                    .execute(
+                        "arguments[0].classList.add(arguments[1]);",
+                        vec![element.clone().into(), class_name.into()],
+                    )
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!(
+                            "Failed to {} '{}' on {}: {}", "add class", class_name,
+                            "menu", e
+                        ),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "menu"))),
+        }
+    }
+    /// Hover over the web element (move mouse to it).
+    pub async fn hover_menu(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_menu(driver).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is not visible", "hover", "menu"),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is disabled", "hover", "menu"),
+                            ),
+                        );
+                    }
+                    Self::dry_run().record(format!("{} {}", "hover", "menu"));
+                    return Ok(());
+                }
+                let actions = driver.action_chain();
+                actions
+                    .move_to_element(&element)
+                    .perform()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to hover over {}: {}", "menu", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "menu"))),
+        }
+    }
+    /// Remove a CSS class from the element through its `classList`.
+    pub async fn remove_class_menu(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        class_name: &str,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_menu(driver).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is not visible", "remove_class", "menu"
+                                ),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is disabled", "remove_class", "menu"
+                                ),
+                            ),
+                        );
+                    }
+                    Self::dry_run()
+                        .record(format!("remove_class {} {:?}", "menu", class_name));
+                    return Ok(());
+                }
+                driver
+                    .execute(
+                        "arguments[0].classList.remove(arguments[1]);",
+                        vec![element.clone().into(), class_name.into()],
+                    )
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!(
+                            "Failed to {} '{}' on {}: {}", "remove class", class_name,
+                            "menu", e
+                        ),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "menu"))),
+        }
+    }
+    /// Scroll the element into view.
+    pub async fn scroll_to_menu(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_menu(driver).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is not visible", "scroll_to", "menu"
+                                ),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is disabled", "scroll_to", "menu"),
+                            ),
+                        );
+                    }
+                    Self::dry_run().record(format!("{} {}", "scroll_to", "menu"));
+                    return Ok(());
+                }
+                driver
+                    .execute(
+                        r#"arguments[0].scrollIntoView();"#,
+                        vec![element.clone().into()],
+                    )
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to scroll to {}: {}", "menu", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "menu"))),
+        }
+    }
+    /// Toggle a CSS class on the element through its `classList`.
+    ///
+    /// Returns whether the class is present afterwards.
+    pub async fn toggle_class_menu(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        class_name: &str,
+    ) -> std::result::Result<bool, anyhow::Error> {
+        match self.query_menu(driver).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is not visible", "toggle_class", "menu"
+                                ),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is disabled", "toggle_class", "menu"
+                                ),
+                            ),
+                        );
+                    }
+                    Self::dry_run()
+                        .record(format!("toggle_class {} {:?}", "menu", class_name));
+                    return {
+                        let present = element
+                            .class_name()
+                            .await
+                            .map_err(|e| anyhow::Error::msg(
+                                format!("Failed to read classes of {}: {}", "menu", e),
+                            ))?
+                            .is_some_and(|classes| {
+                                classes.split_whitespace().any(|class| class == class_name)
+                            });
+                        Ok(!present)
+                    };
+                }
+                let ret = driver
+                    .execute(
+                        r#"return arguments[0].classList.toggle(arguments[1]);"#,
+                        vec![element.clone().into(), class_name.into()],
+                    )
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!(
+                            "Failed to toggle class '{}' on {}: {}", class_name, "menu",
+                            e
+                        ),
+                    ))?;
+                ret.json()
+                    .as_bool()
+                    .ok_or_else(|| anyhow::Error::msg(
+                        format!(
+                            "Unexpected result toggling class '{}' on {}", class_name,
+                            "menu"
+                        ),
+                    ))
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "menu"))),
+        }
+    }
+    /// Like [`Self::add_class_menu`], waiting up to `timeout_secs` for the element instead.
+    pub async fn add_class_menu_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        class_name: &str,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_menu_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is not visible", "add_class", "menu"
+                                ),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is disabled", "add_class", "menu"),
+                            ),
+                        );
+                    }
+                    Self::dry_run()
+                        .record(format!("add_class {} {:?}", "menu", class_name));
+                    return Ok(());
+                }
+                driver
+                    .execute(
+                        "arguments[0].classList.add(arguments[1]);",
+                        vec![element.clone().into(), class_name.into()],
+                    )
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!(
+                            "Failed to {} '{}' on {}: {}", "add class", class_name,
+                            "menu", e
+                        ),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "menu"))),
+        }
+    }
+    /// Like [`Self::hover_menu`], waiting up to `timeout_secs` for the element instead.
+    pub async fn hover_menu_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_menu_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is not visible", "hover", "menu"),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is disabled", "hover", "menu"),
+                            ),
+                        );
+                    }
+                    Self::dry_run().record(format!("{} {}", "hover", "menu"));
+                    return Ok(());
+                }
+                let actions = driver.action_chain();
+                actions
+                    .move_to_element(&element)
+                    .perform()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to hover over {}: {}", "menu", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "menu"))),
+        }
+    }
+    /// Like [`Self::remove_class_menu`], waiting up to `timeout_secs` for the element instead.
+    pub async fn remove_class_menu_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        class_name: &str,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_menu_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is not visible", "remove_class", "menu"
+                                ),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is disabled", "remove_class", "menu"
+                                ),
+                            ),
+                        );
+                    }
+                    Self::dry_run()
+                        .record(format!("remove_class {} {:?}", "menu", class_name));
+                    return Ok(());
+                }
+                driver
+                    .execute(
+                        "arguments[0].classList.remove(arguments[1]);",
+                        vec![element.clone().into(), class_name.into()],
+                    )
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!(
+                            "Failed to {} '{}' on {}: {}", "remove class", class_name,
+                            "menu", e
+                        ),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "menu"))),
+        }
+    }
+    /// Like [`Self::scroll_to_menu`], waiting up to `timeout_secs` for the element instead.
+    pub async fn scroll_to_menu_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_menu_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is not visible", "scroll_to", "menu"
+                                ),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is disabled", "scroll_to", "menu"),
+                            ),
+                        );
+                    }
+                    Self::dry_run().record(format!("{} {}", "scroll_to", "menu"));
+                    return Ok(());
+                }
+                driver
+                    .execute(
+                        r#"arguments[0].scrollIntoView();"#,
+                        vec![element.clone().into()],
+                    )
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to scroll to {}: {}", "menu", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "menu"))),
+        }
+    }
+    /// Like [`Self::toggle_class_menu`], waiting up to `timeout_secs` for the element instead.
+    pub async fn toggle_class_menu_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        class_name: &str,
+        timeout_secs: u64,
+    ) -> std::result::Result<bool, anyhow::Error> {
+        match self.query_menu_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is not visible", "toggle_class", "menu"
+                                ),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "menu", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is disabled", "toggle_class", "menu"
+                                ),
+                            ),
+                        );
+                    }
+                    Self::dry_run()
+                        .record(format!("toggle_class {} {:?}", "menu", class_name));
+                    return {
+                        let present = element
+                            .class_name()
+                            .await
+                            .map_err(|e| anyhow::Error::msg(
+                                format!("Failed to read classes of {}: {}", "menu", e),
+                            ))?
+                            .is_some_and(|classes| {
+                                classes.split_whitespace().any(|class| class == class_name)
+                            });
+                        Ok(!present)
+                    };
+                }
+                let ret = driver
+                    .execute(
+                        r#"return arguments[0].classList.toggle(arguments[1]);"#,
+                        vec![element.clone().into(), class_name.into()],
+                    )
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!(
+                            "Failed to toggle class '{}' on {}: {}", class_name, "menu",
+                            e
+                        ),
+                    ))?;
+                ret.json()
+                    .as_bool()
+                    .ok_or_else(|| anyhow::Error::msg(
+                        format!(
+                            "Unexpected result toggling class '{}' on {}", class_name,
+                            "menu"
+                        ),
+                    ))
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "menu"))),
+        }
+    }
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_pin(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.pin)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "pin", self.pin);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "pin", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_pin`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_pin_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.pin))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "pin", self.pin);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "pin", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const PIN_SELECTOR_DESC: &'static str = "By::Css(\"#pin\")";
+    /// Send special keys or key combinations to the web element.
+    ///
+    /// Accepts a `thirtyfour::Key`, a sequence such as `Key::Control + "a"`, or plain text.
+    pub async fn send_key_pin(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        keys: impl Into<thirtyfour::TypingData> + std::fmt::Debug,
+    ) -> std::result::Result<(), anyhow::Error> {
+        let keys: thirtyfour::TypingData = keys.into();
+        match self.query_pin(driver).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "pin", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is not visible", "send_key", "pin"),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "pin", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is disabled", "send_key", "pin"),
+                            ),
+                        );
+                    }
+                    Self::dry_run().record(format!("send_key {} {:?}", "pin", "***"));
+                    return Ok(());
+                }
+                let typed = keys.to_string();
+                element
+                    .send_keys(keys)
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!(
+                            "Failed to send keys to {}: {}", "pin", { let detail = e
+                            .to_string(); if typed.as_str().is_empty() { detail } else {
+                            detail.replace(typed.as_str(), "***") } }
+                        ),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "pin"))),
+        }
+    }
+    /// Like [`Self::send_key_pin`], waiting up to `timeout_secs` for the element instead.
+    pub async fn send_key_pin_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        keys: impl Into<thirtyfour::TypingData> + std::fmt::Debug,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        let keys: thirtyfour::TypingData = keys.into();
+        match self.query_pin_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "pin", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is not visible", "send_key", "pin"),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "pin", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is disabled", "send_key", "pin"),
+                            ),
+                        );
+                    }
+                    Self::dry_run().record(format!("send_key {} {:?}", "pin", "***"));
+                    return Ok(());
+                }
+                let typed = keys.to_string();
+                element
+                    .send_keys(keys)
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!(
+                            "Failed to send keys to {}: {}", "pin", { let detail = e
+                            .to_string(); if typed.as_str().is_empty() { detail } else {
+                            detail.replace(typed.as_str(), "***") } }
+                        ),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "pin"))),
+        }
+    }
+    /// Create the page object from the selectors declared for its fields.
+    pub fn new() -> Self {
+        Self {
+            menu: thirtyfour::By::Css("nav .menu"),
+            pin: thirtyfour::By::Css("#pin"),
+        }
+    }
+    /// The dry-run switch shared by every instance of this page object.
+    ///
+    /// While it is enabled, interactions check that their element exists, is visible and
+    /// is enabled, then record what they would have done instead of doing it.
+    pub fn dry_run() -> &'static crate::thirtyfour_actions_runtime::DryRun {
+        static DRY_RUN: crate::thirtyfour_actions_runtime::DryRun = crate::thirtyfour_actions_runtime::DryRun::new(
+            false,
+        );
+        &DRY_RUN
+    }
+    /// Map the name of each locator field to its selector, e.g. for reporting which
+    /// locators a page uses.
+    ///
+    /// `resolver`, `skip` and `component` fields have no selector of their own and are left out.
+    pub fn selector_map(
+        &self,
+    ) -> std::collections::HashMap<&'static str, thirtyfour::By> {
+        std::collections::HashMap::from([
+            ("menu", thirtyfour::By::clone(&self.menu)),
+            ("pin", thirtyfour::By::clone(&self.pin)),
+        ])
+    }
+}
@@ -0,0 +1,466 @@
+impl Default for LoginPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+/// Fields of [`LoginPage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum LoginPageField {
+    Form,
+    Username,
+}
+impl LoginPageField {
+    /// Every field, in declaration order.
+    pub const ALL: &'static [Self] = &[Self::Form, Self::Username];
+    /// The field's name as declared in the struct.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Form => "form",
+            Self::Username => "username",
+        }
+    }
+}
+impl LoginPage {
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_form(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.form)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "form", self.form);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "form", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_form`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_form_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.form))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "form", self.form);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "form", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const FORM_SELECTOR_DESC: &'static str = "By::Css(\"form.login\")";
+    /// Click on the web element.
+    pub async fn click_form(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_form(driver).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "form", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "form"))),
+        }
+    }
+    /// Like [`Self::click_form`], waiting up to `timeout_secs` for the element instead.
+    pub async fn click_form_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_form_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "form", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "form"))),
+        }
+    }
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_username(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match match self.query_form(driver).await {
+            Some(scope) => {
+                scope
+                    .query(thirtyfour::By::clone(&self.username))
+                    .all_from_selector()
+                    .await
+            }
+            None => Ok(Vec::new()),
+        } {
+            Ok(elements) => {
+                match elements.into_iter().nth(1usize) {
+                    Some(element) => Some(element),
+                    None => {
+                        log::debug!(
+                            "Element {} not found ({:?})", "username", self.username
+                        );
+                        None
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "username", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_username`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_username_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match match self.query_form(driver).await {
+            Some(scope) => {
+                scope
+                    .query(thirtyfour::By::clone(&self.username))
+                    .wait(
+                        std::time::Duration::from_secs(timeout_secs),
+                        std::time::Duration::from_millis(500u64),
+                    )
+                    .all_from_selector()
+                    .await
+            }
+            None => Ok(Vec::new()),
+        } {
+            Ok(elements) => {
+                match elements.into_iter().nth(1usize) {
+                    Some(element) => Some(element),
+                    None => {
+                        log::debug!(
+                            "Element {} not found ({:?})", "username", self.username
+                        );
+                        None
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "username", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const USERNAME_SELECTOR_DESC: &'static str = "By::Css(\"#user\")";
+    /// Click on the web element.
+    pub async fn click_username(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_username(driver).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "username", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "username"))),
+        }
+    }
+    /// Like [`Self::click_username`], waiting up to `timeout_secs` for the element instead.
+    pub async fn click_username_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_username_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "username", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "username"))),
+        }
+    }
+    /// Create the page object from the selectors declared for its fields.
+    pub fn new() -> Self {
+        Self {
+            form: thirtyfour::By::Css("form.login"),
+            username: thirtyfour::By::Css("#user"),
+        }
+    }
+    /// Query every field not marked `lazy` concurrently, returning the elements found.
+    pub async fn resolve_all(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::collections::HashMap<LoginPageField, thirtyfour::WebElement> {
+        use std::task::Poll;
+        let mut queries: Vec<_> = [LoginPageField::Form, LoginPageField::Username]
+            .into_iter()
+            .map(|field| Some(
+                Box::pin(async move {
+                    (
+                        field,
+                        match field {
+                            LoginPageField::Form => self.query_form(driver).await,
+                            LoginPageField::Username => self.query_username(driver).await,
+                        },
+                    )
+                }),
+            ))
+            .collect();
+        let mut elements = std::collections::HashMap::new();
+        std::future::poll_fn(|cx| {
+                let mut done = true;
+                for slot in queries.iter_mut() {
+                    if let Some(query) = slot {
+                        match std::future::Future::poll(query.as_mut(), cx) {
+                            Poll::Ready((field, element)) => {
+                                if let Some(element) = element {
+                                    elements.insert(field, element);
+                                }
+                                *slot = None;
+                            }
+                            Poll::Pending => done = false,
+                        }
+                    }
+                }
+                if done { Poll::Ready(()) } else { Poll::Pending }
+            })
+            .await;
+        elements
+    }
+    /// Query every field concurrently, including `lazy` ones, returning the elements found.
+    pub async fn resolve_all_include_lazy(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::collections::HashMap<LoginPageField, thirtyfour::WebElement> {
+        use std::task::Poll;
+        let mut queries: Vec<_> = [LoginPageField::Form, LoginPageField::Username]
+            .into_iter()
+            .map(|field| Some(
+                Box::pin(async move {
+                    (
+                        field,
+                        match field {
+                            LoginPageField::Form => self.query_form(driver).await,
+                            LoginPageField::Username => self.query_username(driver).await,
+                        },
+                    )
+                }),
+            ))
+            .collect();
+        let mut elements = std::collections::HashMap::new();
+        std::future::poll_fn(|cx| {
+                let mut done = true;
+                for slot in queries.iter_mut() {
+                    if let Some(query) = slot {
+                        match std::future::Future::poll(query.as_mut(), cx) {
+                            Poll::Ready((field, element)) => {
+                                if let Some(element) = element {
+                                    elements.insert(field, element);
+                                }
+                                *slot = None;
+                            }
+                            Poll::Pending => done = false,
+                        }
+                    }
+                }
+                if done { Poll::Ready(()) } else { Poll::Pending }
+            })
+            .await;
+        elements
+    }
+    /// Wait until any of `fields` is visible, returning the first one found.
+    pub async fn wait_for_any(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        fields: &[LoginPageField],
+        timeout_secs: u64,
+    ) -> std::result::Result<(LoginPageField, thirtyfour::WebElement), anyhow::Error> {
+        use std::time::{Duration, Instant};
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        loop {
+            for field in fields.iter().copied() {
+                if let Some(element) = match field {
+                    LoginPageField::Form => self.query_form_with_timeout(driver, 0).await,
+                    LoginPageField::Username => {
+                        self.query_username_with_timeout(driver, 0).await
+                    }
+                } {
+                    if element.is_displayed().await.unwrap_or(false) {
+                        return Ok((field, element));
+                    }
+                }
+            }
+            if Instant::now() >= deadline {
+                let names: Vec<&str> = fields.iter().map(|field| field.name()).collect();
+                return Err(
+                    anyhow::Error::msg(
+                        format!(
+                            "Timed out waiting for any of [{}] to be visible", names
+                            .join(", ")
+                        ),
+                    ),
+                );
+            }
+            tokio::time::sleep(Duration::from_millis(500u64)).await;
+        }
+    }
+    /// Wait until all of `fields` are visible, returning their elements in order.
+    pub async fn wait_for_all(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        fields: &[LoginPageField],
+        timeout_secs: u64,
+    ) -> std::result::Result<Vec<thirtyfour::WebElement>, anyhow::Error> {
+        use std::time::{Duration, Instant};
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let mut elements = Vec::with_capacity(fields.len());
+        for field in fields.iter().copied() {
+            let element = loop {
+                if let Some(element) = match field {
+                    LoginPageField::Form => self.query_form_with_timeout(driver, 0).await,
+                    LoginPageField::Username => {
+                        self.query_username_with_timeout(driver, 0).await
+                    }
+                } {
+                    if element.is_displayed().await.unwrap_or(false) {
+                        break element;
+                    }
+                }
+                if Instant::now() >= deadline {
+                    return Err(
+                        anyhow::Error::msg(
+                            format!(
+                                "Timed out waiting for {} to be visible", field.name()
+                            ),
+                        ),
+                    );
+                }
+                tokio::time::sleep(Duration::from_millis(500u64)).await;
+            };
+            elements.push(element);
+        }
+        Ok(elements)
+    }
+    /// Read the text of each of `fields` concurrently.
+    pub async fn get_texts_of(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        fields: &[LoginPageField],
+    ) -> std::result::Result<
+        std::collections::HashMap<LoginPageField, String>,
+        anyhow::Error,
+    > {
+        use std::task::Poll;
+        let mut reads: Vec<_> = fields
+            .iter()
+            .copied()
+            .map(|field| {
+                Some(
+                    Box::pin(async move {
+                        let element = match field {
+                            LoginPageField::Form => self.query_form(driver).await,
+                            LoginPageField::Username => self.query_username(driver).await,
+                        };
+                        let text = match element {
+                            Some(element) => {
+                                element
+                                    .text()
+                                    .await
+                                    .map_err(|e| anyhow::Error::msg(
+                                        format!("Failed to get text from {}: {}", field.name(), e),
+                                    ))
+                            }
+                            None => {
+                                Err(
+                                    anyhow::Error::msg(
+                                        format!("Element {} not found", field.name()),
+                                    ),
+                                )
+                            }
+                        };
+                        (field, text)
+                    }),
+                )
+            })
+            .collect();
+        let mut texts = std::collections::HashMap::with_capacity(fields.len());
+        let mut failure = None;
+        std::future::poll_fn(|cx| {
+                let mut done = true;
+                for slot in reads.iter_mut() {
+                    if let Some(read) = slot {
+                        match std::future::Future::poll(read.as_mut(), cx) {
+                            Poll::Ready((field, Ok(text))) => {
+                                texts.insert(field, text);
+                                *slot = None;
+                            }
+                            Poll::Ready((_, Err(e))) => {
+                                failure.get_or_insert(e);
+                                *slot = None;
+                            }
+                            Poll::Pending => done = false,
+                        }
+                    }
+                }
+                if done { Poll::Ready(()) } else { Poll::Pending }
+            })
+            .await;
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(texts),
+        }
+    }
+    /// Map the name of each locator field to its selector, e.g. for reporting which
+    /// locators a page uses.
+    ///
+    /// `resolver`, `skip` and `component` fields have no selector of their own and are left out.
+    pub fn selector_map(
+        &self,
+    ) -> std::collections::HashMap<&'static str, thirtyfour::By> {
+        std::collections::HashMap::from([
+            ("form", thirtyfour::By::clone(&self.form)),
+            ("username", thirtyfour::By::clone(&self.username)),
+        ])
+    }
+    /// Get the locator of a field.
+    pub fn locator(&self, field: LoginPageField) -> &thirtyfour::By {
+        match field {
+            LoginPageField::Form => &self.form,
+            LoginPageField::Username => &self.username,
+        }
+    }
+}
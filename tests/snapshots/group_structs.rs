@@ -0,0 +1,641 @@
+impl Default for LoginPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl LoginPage {
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_logo(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.logo)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "logo", self.logo);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "logo", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_logo`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_logo_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.logo))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "logo", self.logo);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "logo", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const LOGO_SELECTOR_DESC: &'static str = "By::Css(\"header .logo\")";
+    /// Click on the web element.
+    pub async fn click_logo(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_logo(driver).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "logo", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is not visible", "click", "logo"),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "logo", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is disabled", "click", "logo"),
+                            ),
+                        );
+                    }
+                    Self::dry_run().record(format!("{} {}", "click", "logo"));
+                    return Ok(());
+                }
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "logo", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "logo"))),
+        }
+    }
+    /// Wait for the element to be present and visible with timeout.
+    pub async fn wait_for_logo(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<thirtyfour::WebElement, anyhow::Error> {
+        use std::time::Duration;
+        let element = driver
+            .query(thirtyfour::By::clone(&self.logo))
+            .wait(Duration::from_secs(timeout_secs), Duration::from_millis(500u64))
+            .visible()
+            .first()
+            .await
+            .map_err(|e| anyhow::Error::msg(
+                format!("Timed out waiting for {} to be visible: {}", "logo", e),
+            ))?;
+        Ok(element)
+    }
+    /// Like [`Self::wait_for_logo`], waiting up to the default of 5 seconds.
+    pub async fn wait_for_logo_default(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<thirtyfour::WebElement, anyhow::Error> {
+        self.wait_for_logo(driver, 5u64).await
+    }
+    /// Like [`Self::click_logo`], waiting up to `timeout_secs` for the element instead.
+    pub async fn click_logo_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_logo_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "logo", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is not visible", "click", "logo"),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "logo", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is disabled", "click", "logo"),
+                            ),
+                        );
+                    }
+                    Self::dry_run().record(format!("{} {}", "click", "logo"));
+                    return Ok(());
+                }
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "logo", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "logo"))),
+        }
+    }
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_username(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.username)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "username", self.username);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "username", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_username`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_username_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.username))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "username", self.username);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "username", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const USERNAME_SELECTOR_DESC: &'static str = "By::Id(\"username\")";
+    /// Enter text into the web element.
+    pub async fn enter_keys_username(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        keys: &str,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_username(driver).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!(
+                                "Failed to check if {} is displayed: {}", "username", e
+                            ),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is not visible", "enter_keys", "username"
+                                ),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!(
+                                "Failed to check if {} is enabled: {}", "username", e
+                            ),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is disabled", "enter_keys", "username"
+                                ),
+                            ),
+                        );
+                    }
+                    Self::dry_run()
+                        .record(format!("enter_keys {} {:?}", "username", keys));
+                    return Ok(());
+                }
+                element
+                    .send_keys(keys)
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to send keys to {}: {}", "username", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "username"))),
+        }
+    }
+    /// Like [`Self::enter_keys_username`], waiting up to `timeout_secs` for the element instead.
+    pub async fn enter_keys_username_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        keys: &str,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_username_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!(
+                                "Failed to check if {} is displayed: {}", "username", e
+                            ),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is not visible", "enter_keys", "username"
+                                ),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!(
+                                "Failed to check if {} is enabled: {}", "username", e
+                            ),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!(
+                                    "Dry run: {} {} is disabled", "enter_keys", "username"
+                                ),
+                            ),
+                        );
+                    }
+                    Self::dry_run()
+                        .record(format!("enter_keys {} {:?}", "username", keys));
+                    return Ok(());
+                }
+                element
+                    .send_keys(keys)
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to send keys to {}: {}", "username", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "username"))),
+        }
+    }
+    /// Create the page object from the selectors declared for its fields.
+    pub fn new() -> Self {
+        Self {
+            logo: thirtyfour::By::Css("header .logo"),
+            username: thirtyfour::By::Id("username"),
+        }
+    }
+    /// Navigate to this page, running any `before_open` hook and applying any declared
+    /// context overrides first.
+    pub async fn open(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        driver
+            .goto("https://example.com/login")
+            .await
+            .map_err(|e| anyhow::Error::msg(
+                format!("Failed to open {}: {}", "https://example.com/login", e),
+            ))?;
+        Ok(())
+    }
+    /// Get the text of the currently open alert, confirm or prompt.
+    pub async fn get_alert_text(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<String, anyhow::Error> {
+        driver
+            .get_alert_text()
+            .await
+            .map_err(|e| anyhow::Error::msg(format!("Failed to get alert text: {}", e)))
+    }
+    /// Accept the currently open alert or confirm.
+    pub async fn accept_alert(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        driver
+            .accept_alert()
+            .await
+            .map_err(|e| anyhow::Error::msg(format!("Failed to accept alert: {}", e)))
+    }
+    /// Dismiss the currently open alert, confirm or prompt.
+    pub async fn dismiss_alert(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        driver
+            .dismiss_alert()
+            .await
+            .map_err(|e| anyhow::Error::msg(format!("Failed to dismiss alert: {}", e)))
+    }
+    /// Type `text` into the currently open `window.prompt` and accept it.
+    pub async fn answer_prompt(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        text: &str,
+    ) -> std::result::Result<(), anyhow::Error> {
+        driver
+            .send_alert_text(text)
+            .await
+            .map_err(|e| anyhow::Error::msg(format!("Failed to answer prompt: {}", e)))?;
+        driver
+            .accept_alert()
+            .await
+            .map_err(|e| anyhow::Error::msg(format!("Failed to accept prompt: {}", e)))
+    }
+    /// The dry-run switch shared by every instance of this page object.
+    ///
+    /// While it is enabled, interactions check that their element exists, is visible and
+    /// is enabled, then record what they would have done instead of doing it.
+    pub fn dry_run() -> &'static crate::thirtyfour_actions_runtime::DryRun {
+        static DRY_RUN: crate::thirtyfour_actions_runtime::DryRun = crate::thirtyfour_actions_runtime::DryRun::new(
+            false,
+        );
+        &DRY_RUN
+    }
+    /// Map the name of each locator field to its selector, e.g. for reporting which
+    /// locators a page uses.
+    ///
+    /// `resolver`, `skip` and `component` fields have no selector of their own and are left out.
+    pub fn selector_map(
+        &self,
+    ) -> std::collections::HashMap<&'static str, thirtyfour::By> {
+        std::collections::HashMap::from([
+            ("logo", thirtyfour::By::clone(&self.logo)),
+            ("username", thirtyfour::By::clone(&self.username)),
+        ])
+    }
+}
+/// The `header` fields of [`LoginPage`], with their locators and generated actions.
+struct LoginPageHeader {
+    logo: By,
+}
+impl Default for LoginPageHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl LoginPageHeader {
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_logo(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.logo)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "logo", self.logo);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "logo", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_logo`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_logo_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.logo))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "logo", self.logo);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "logo", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const LOGO_SELECTOR_DESC: &'static str = "By::Css(\"header .logo\")";
+    /// Click on the web element.
+    pub async fn click_logo(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_logo(driver).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "logo", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is not visible", "click", "logo"),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "logo", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is disabled", "click", "logo"),
+                            ),
+                        );
+                    }
+                    Self::dry_run().record(format!("{} {}", "click", "logo"));
+                    return Ok(());
+                }
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "logo", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "logo"))),
+        }
+    }
+    /// Wait for the element to be present and visible with timeout.
+    pub async fn wait_for_logo(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<thirtyfour::WebElement, anyhow::Error> {
+        use std::time::Duration;
+        let element = driver
+            .query(thirtyfour::By::clone(&self.logo))
+            .wait(Duration::from_secs(timeout_secs), Duration::from_millis(500u64))
+            .visible()
+            .first()
+            .await
+            .map_err(|e| anyhow::Error::msg(
+                format!("Timed out waiting for {} to be visible: {}", "logo", e),
+            ))?;
+        Ok(element)
+    }
+    /// Like [`Self::wait_for_logo`], waiting up to the default of 5 seconds.
+    pub async fn wait_for_logo_default(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<thirtyfour::WebElement, anyhow::Error> {
+        self.wait_for_logo(driver, 5u64).await
+    }
+    /// Like [`Self::click_logo`], waiting up to `timeout_secs` for the element instead.
+    pub async fn click_logo_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_logo_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                if Self::dry_run().is_enabled() {
+                    if !element
+                        .is_displayed()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is displayed: {}", "logo", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is not visible", "click", "logo"),
+                            ),
+                        );
+                    }
+                    if !element
+                        .is_enabled()
+                        .await
+                        .map_err(|e| anyhow::Error::msg(
+                            format!("Failed to check if {} is enabled: {}", "logo", e),
+                        ))?
+                    {
+                        return Err(
+                            anyhow::Error::msg(
+                                format!("Dry run: {} {} is disabled", "click", "logo"),
+                            ),
+                        );
+                    }
+                    Self::dry_run().record(format!("{} {}", "click", "logo"));
+                    return Ok(());
+                }
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "logo", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "logo"))),
+        }
+    }
+    /// Create the page object from the selectors declared for its fields.
+    pub fn new() -> Self {
+        Self {
+            logo: thirtyfour::By::Css("header .logo"),
+        }
+    }
+    /// The dry-run switch shared by every instance of this page object.
+    ///
+    /// While it is enabled, interactions check that their element exists, is visible and
+    /// is enabled, then record what they would have done instead of doing it.
+    pub fn dry_run() -> &'static crate::thirtyfour_actions_runtime::DryRun {
+        static DRY_RUN: crate::thirtyfour_actions_runtime::DryRun = crate::thirtyfour_actions_runtime::DryRun::new(
+            false,
+        );
+        &DRY_RUN
+    }
+    /// Map the name of each locator field to its selector, e.g. for reporting which
+    /// locators a page uses.
+    ///
+    /// `resolver`, `skip` and `component` fields have no selector of their own and are left out.
+    pub fn selector_map(
+        &self,
+    ) -> std::collections::HashMap<&'static str, thirtyfour::By> {
+        std::collections::HashMap::from([("logo", thirtyfour::By::clone(&self.logo))])
+    }
+}
+impl LoginPage {
+    /// Copy the `header` fields into a [`LoginPageHeader`], to pass them on without the rest of the page.
+    pub fn header(&self) -> LoginPageHeader {
+        LoginPageHeader {
+            logo: Clone::clone(&self.logo),
+        }
+    }
+}
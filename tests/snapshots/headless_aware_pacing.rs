@@ -0,0 +1,174 @@
+impl Default for MenuPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl MenuPage {
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_menu(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.menu)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "menu", self.menu);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "menu", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_menu`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_menu_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.menu))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "menu", self.menu);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "menu", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const MENU_SELECTOR_DESC: &'static str = "By::Css(\"nav .menu\")";
+    /// Hover over the web element (move mouse to it).
+    pub async fn hover_menu(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        if !Self::headless().is_enabled() {
+            Self::rate_limiter().acquire().await;
+        }
+        self.pacer
+            .pace(
+                if Self::headless().is_enabled() {
+                    std::time::Duration::ZERO
+                } else {
+                    std::time::Duration::from_millis(300u64)
+                },
+                async {
+                    match self.query_menu(driver).await {
+                        Some(element) => {
+                            let actions = driver.action_chain();
+                            actions
+                                .move_to_element(&element)
+                                .perform()
+                                .await
+                                .map_err(|e| anyhow::Error::msg(
+                                    format!("Failed to hover over {}: {}", "menu", e),
+                                ))?;
+                            if !Self::headless().is_enabled() {
+                                tokio::time::sleep(std::time::Duration::from_millis(400u64))
+                                    .await;
+                            }
+                            Ok(())
+                        }
+                        None => {
+                            Err(
+                                anyhow::Error::msg(format!("Element {} not found", "menu")),
+                            )
+                        }
+                    }
+                },
+            )
+            .await
+    }
+    /// Like [`Self::hover_menu`], waiting up to `timeout_secs` for the element instead.
+    pub async fn hover_menu_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        if !Self::headless().is_enabled() {
+            Self::rate_limiter().acquire().await;
+        }
+        self.pacer
+            .pace(
+                if Self::headless().is_enabled() {
+                    std::time::Duration::ZERO
+                } else {
+                    std::time::Duration::from_millis(300u64)
+                },
+                async {
+                    match self.query_menu_with_timeout(driver, timeout_secs).await {
+                        Some(element) => {
+                            let actions = driver.action_chain();
+                            actions
+                                .move_to_element(&element)
+                                .perform()
+                                .await
+                                .map_err(|e| anyhow::Error::msg(
+                                    format!("Failed to hover over {}: {}", "menu", e),
+                                ))?;
+                            if !Self::headless().is_enabled() {
+                                tokio::time::sleep(std::time::Duration::from_millis(400u64))
+                                    .await;
+                            }
+                            Ok(())
+                        }
+                        None => {
+                            Err(
+                                anyhow::Error::msg(format!("Element {} not found", "menu")),
+                            )
+                        }
+                    }
+                },
+            )
+            .await
+    }
+    /// Create the page object from the selectors declared for its fields.
+    pub fn new() -> Self {
+        Self {
+            menu: thirtyfour::By::Css("nav .menu"),
+            pacer: Default::default(),
+        }
+    }
+    /// The rate limiter shared by every instance of this page object.
+    ///
+    /// Use `set_max_per_second` to change the limit at runtime.
+    pub fn rate_limiter() -> &'static crate::thirtyfour_actions_runtime::RateLimiter {
+        static RATE_LIMITER: crate::thirtyfour_actions_runtime::RateLimiter = crate::thirtyfour_actions_runtime::RateLimiter::new(
+            Some(5f64),
+        );
+        &RATE_LIMITER
+    }
+    /// The headless switch shared by every instance of this page object.
+    ///
+    /// While it is on, actions skip pacing, rate limiting and hover dwell. Unless set, it
+    /// is on when the `CI` environment variable is set.
+    pub fn headless() -> &'static crate::thirtyfour_actions_runtime::HeadlessSwitch {
+        static HEADLESS: crate::thirtyfour_actions_runtime::HeadlessSwitch = crate::thirtyfour_actions_runtime::HeadlessSwitch::new(
+            None,
+        );
+        &HEADLESS
+    }
+    /// Map the name of each locator field to its selector, e.g. for reporting which
+    /// locators a page uses.
+    ///
+    /// `resolver`, `skip` and `component` fields have no selector of their own and are left out.
+    pub fn selector_map(
+        &self,
+    ) -> std::collections::HashMap<&'static str, thirtyfour::By> {
+        std::collections::HashMap::from([("menu", thirtyfour::By::clone(&self.menu))])
+    }
+}
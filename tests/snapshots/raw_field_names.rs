@@ -0,0 +1,673 @@
+impl Default for SearchForm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+/// Fields of [`SearchForm`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SearchFormField {
+    Type,
+    Ref,
+    Fn,
+}
+impl SearchFormField {
+    /// Every field, in declaration order.
+    pub const ALL: &'static [Self] = &[Self::Type, Self::Ref, Self::Fn];
+    /// The field's name as declared in the struct.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Type => "type",
+            Self::Ref => "ref",
+            Self::Fn => "fn",
+        }
+    }
+}
+impl SearchForm {
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_type(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.r#type)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "type", self.r#type);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "type", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_type`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_type_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.r#type))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "type", self.r#type);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "type", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const TYPE_SELECTOR_DESC: &'static str = "By::Css(\"select.type\")";
+    /// Click on the web element.
+    pub async fn click_type(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_type(driver).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "type", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "type"))),
+        }
+    }
+    /// Send special keys or key combinations to the web element.
+    ///
+    /// Accepts a `thirtyfour::Key`, a sequence such as `Key::Control + "a"`, or plain text.
+    pub async fn send_key_type(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        keys: impl Into<thirtyfour::TypingData> + std::fmt::Debug,
+    ) -> std::result::Result<(), anyhow::Error> {
+        let keys: thirtyfour::TypingData = keys.into();
+        match self.query_type(driver).await {
+            Some(element) => {
+                element
+                    .send_keys(keys)
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to send keys to {}: {}", "type", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "type"))),
+        }
+    }
+    /// Like [`Self::click_type`], waiting up to `timeout_secs` for the element instead.
+    pub async fn click_type_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_type_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "type", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "type"))),
+        }
+    }
+    /// Like [`Self::send_key_type`], waiting up to `timeout_secs` for the element instead.
+    pub async fn send_key_type_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        keys: impl Into<thirtyfour::TypingData> + std::fmt::Debug,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        let keys: thirtyfour::TypingData = keys.into();
+        match self.query_type_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .send_keys(keys)
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to send keys to {}: {}", "type", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "type"))),
+        }
+    }
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_ref(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.r#ref)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "ref", self.r#ref);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "ref", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_ref`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_ref_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.r#ref))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "ref", self.r#ref);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "ref", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const REF_SELECTOR_DESC: &'static str = "By::Css(\"input.ref\")";
+    /// Click on the web element.
+    pub async fn click_ref(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_ref(driver).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "ref", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "ref"))),
+        }
+    }
+    /// Send special keys or key combinations to the web element.
+    ///
+    /// Accepts a `thirtyfour::Key`, a sequence such as `Key::Control + "a"`, or plain text.
+    pub async fn send_key_ref(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        keys: impl Into<thirtyfour::TypingData> + std::fmt::Debug,
+    ) -> std::result::Result<(), anyhow::Error> {
+        let keys: thirtyfour::TypingData = keys.into();
+        match self.query_ref(driver).await {
+            Some(element) => {
+                element
+                    .send_keys(keys)
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to send keys to {}: {}", "ref", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "ref"))),
+        }
+    }
+    /// Like [`Self::click_ref`], waiting up to `timeout_secs` for the element instead.
+    pub async fn click_ref_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_ref_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "ref", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "ref"))),
+        }
+    }
+    /// Like [`Self::send_key_ref`], waiting up to `timeout_secs` for the element instead.
+    pub async fn send_key_ref_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        keys: impl Into<thirtyfour::TypingData> + std::fmt::Debug,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        let keys: thirtyfour::TypingData = keys.into();
+        match self.query_ref_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .send_keys(keys)
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to send keys to {}: {}", "ref", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "ref"))),
+        }
+    }
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_fn(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.r#fn)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "fn", self.r#fn);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "fn", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_fn`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_fn_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.r#fn))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "fn", self.r#fn);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "fn", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const FN_SELECTOR_DESC: &'static str = "By::Css(\"button.fn\")";
+    /// Click on the web element.
+    pub async fn click_fn(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_fn(driver).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "fn", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "fn"))),
+        }
+    }
+    /// Send special keys or key combinations to the web element.
+    ///
+    /// Accepts a `thirtyfour::Key`, a sequence such as `Key::Control + "a"`, or plain text.
+    pub async fn send_key_fn(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        keys: impl Into<thirtyfour::TypingData> + std::fmt::Debug,
+    ) -> std::result::Result<(), anyhow::Error> {
+        let keys: thirtyfour::TypingData = keys.into();
+        match self.query_fn(driver).await {
+            Some(element) => {
+                element
+                    .send_keys(keys)
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to send keys to {}: {}", "fn", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "fn"))),
+        }
+    }
+    /// Like [`Self::click_fn`], waiting up to `timeout_secs` for the element instead.
+    pub async fn click_fn_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_fn_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "fn", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "fn"))),
+        }
+    }
+    /// Like [`Self::send_key_fn`], waiting up to `timeout_secs` for the element instead.
+    pub async fn send_key_fn_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        keys: impl Into<thirtyfour::TypingData> + std::fmt::Debug,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        let keys: thirtyfour::TypingData = keys.into();
+        match self.query_fn_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .send_keys(keys)
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to send keys to {}: {}", "fn", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "fn"))),
+        }
+    }
+    /// Create the page object from the selectors declared for its fields.
+    pub fn new() -> Self {
+        Self {
+            r#type: thirtyfour::By::Css("select.type"),
+            r#ref: thirtyfour::By::Css("input.ref"),
+            r#fn: thirtyfour::By::Css("button.fn"),
+        }
+    }
+    /// Query every field not marked `lazy` concurrently, returning the elements found.
+    pub async fn resolve_all(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::collections::HashMap<SearchFormField, thirtyfour::WebElement> {
+        use std::task::Poll;
+        let mut queries: Vec<_> = [
+            SearchFormField::Type,
+            SearchFormField::Ref,
+            SearchFormField::Fn,
+        ]
+            .into_iter()
+            .map(|field| Some(
+                Box::pin(async move {
+                    (
+                        field,
+                        match field {
+                            SearchFormField::Type => self.query_type(driver).await,
+                            SearchFormField::Ref => self.query_ref(driver).await,
+                            SearchFormField::Fn => self.query_fn(driver).await,
+                        },
+                    )
+                }),
+            ))
+            .collect();
+        let mut elements = std::collections::HashMap::new();
+        std::future::poll_fn(|cx| {
+                let mut done = true;
+                for slot in queries.iter_mut() {
+                    if let Some(query) = slot {
+                        match std::future::Future::poll(query.as_mut(), cx) {
+                            Poll::Ready((field, element)) => {
+                                if let Some(element) = element {
+                                    elements.insert(field, element);
+                                }
+                                *slot = None;
+                            }
+                            Poll::Pending => done = false,
+                        }
+                    }
+                }
+                if done { Poll::Ready(()) } else { Poll::Pending }
+            })
+            .await;
+        elements
+    }
+    /// Query every field concurrently, including `lazy` ones, returning the elements found.
+    pub async fn resolve_all_include_lazy(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::collections::HashMap<SearchFormField, thirtyfour::WebElement> {
+        use std::task::Poll;
+        let mut queries: Vec<_> = [
+            SearchFormField::Type,
+            SearchFormField::Ref,
+            SearchFormField::Fn,
+        ]
+            .into_iter()
+            .map(|field| Some(
+                Box::pin(async move {
+                    (
+                        field,
+                        match field {
+                            SearchFormField::Type => self.query_type(driver).await,
+                            SearchFormField::Ref => self.query_ref(driver).await,
+                            SearchFormField::Fn => self.query_fn(driver).await,
+                        },
+                    )
+                }),
+            ))
+            .collect();
+        let mut elements = std::collections::HashMap::new();
+        std::future::poll_fn(|cx| {
+                let mut done = true;
+                for slot in queries.iter_mut() {
+                    if let Some(query) = slot {
+                        match std::future::Future::poll(query.as_mut(), cx) {
+                            Poll::Ready((field, element)) => {
+                                if let Some(element) = element {
+                                    elements.insert(field, element);
+                                }
+                                *slot = None;
+                            }
+                            Poll::Pending => done = false,
+                        }
+                    }
+                }
+                if done { Poll::Ready(()) } else { Poll::Pending }
+            })
+            .await;
+        elements
+    }
+    /// Wait until any of `fields` is visible, returning the first one found.
+    pub async fn wait_for_any(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        fields: &[SearchFormField],
+        timeout_secs: u64,
+    ) -> std::result::Result<(SearchFormField, thirtyfour::WebElement), anyhow::Error> {
+        use std::time::{Duration, Instant};
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        loop {
+            for field in fields.iter().copied() {
+                if let Some(element) = match field {
+                    SearchFormField::Type => {
+                        self.query_type_with_timeout(driver, 0).await
+                    }
+                    SearchFormField::Ref => self.query_ref_with_timeout(driver, 0).await,
+                    SearchFormField::Fn => self.query_fn_with_timeout(driver, 0).await,
+                } {
+                    if element.is_displayed().await.unwrap_or(false) {
+                        return Ok((field, element));
+                    }
+                }
+            }
+            if Instant::now() >= deadline {
+                let names: Vec<&str> = fields.iter().map(|field| field.name()).collect();
+                return Err(
+                    anyhow::Error::msg(
+                        format!(
+                            "Timed out waiting for any of [{}] to be visible", names
+                            .join(", ")
+                        ),
+                    ),
+                );
+            }
+            tokio::time::sleep(Duration::from_millis(500u64)).await;
+        }
+    }
+    /// Wait until all of `fields` are visible, returning their elements in order.
+    pub async fn wait_for_all(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        fields: &[SearchFormField],
+        timeout_secs: u64,
+    ) -> std::result::Result<Vec<thirtyfour::WebElement>, anyhow::Error> {
+        use std::time::{Duration, Instant};
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let mut elements = Vec::with_capacity(fields.len());
+        for field in fields.iter().copied() {
+            let element = loop {
+                if let Some(element) = match field {
+                    SearchFormField::Type => {
+                        self.query_type_with_timeout(driver, 0).await
+                    }
+                    SearchFormField::Ref => self.query_ref_with_timeout(driver, 0).await,
+                    SearchFormField::Fn => self.query_fn_with_timeout(driver, 0).await,
+                } {
+                    if element.is_displayed().await.unwrap_or(false) {
+                        break element;
+                    }
+                }
+                if Instant::now() >= deadline {
+                    return Err(
+                        anyhow::Error::msg(
+                            format!(
+                                "Timed out waiting for {} to be visible", field.name()
+                            ),
+                        ),
+                    );
+                }
+                tokio::time::sleep(Duration::from_millis(500u64)).await;
+            };
+            elements.push(element);
+        }
+        Ok(elements)
+    }
+    /// Read the text of each of `fields` concurrently.
+    pub async fn get_texts_of(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        fields: &[SearchFormField],
+    ) -> std::result::Result<
+        std::collections::HashMap<SearchFormField, String>,
+        anyhow::Error,
+    > {
+        use std::task::Poll;
+        let mut reads: Vec<_> = fields
+            .iter()
+            .copied()
+            .map(|field| {
+                Some(
+                    Box::pin(async move {
+                        let element = match field {
+                            SearchFormField::Type => self.query_type(driver).await,
+                            SearchFormField::Ref => self.query_ref(driver).await,
+                            SearchFormField::Fn => self.query_fn(driver).await,
+                        };
+                        let text = match element {
+                            Some(element) => {
+                                element
+                                    .text()
+                                    .await
+                                    .map_err(|e| anyhow::Error::msg(
+                                        format!("Failed to get text from {}: {}", field.name(), e),
+                                    ))
+                            }
+                            None => {
+                                Err(
+                                    anyhow::Error::msg(
+                                        format!("Element {} not found", field.name()),
+                                    ),
+                                )
+                            }
+                        };
+                        (field, text)
+                    }),
+                )
+            })
+            .collect();
+        let mut texts = std::collections::HashMap::with_capacity(fields.len());
+        let mut failure = None;
+        std::future::poll_fn(|cx| {
+                let mut done = true;
+                for slot in reads.iter_mut() {
+                    if let Some(read) = slot {
+                        match std::future::Future::poll(read.as_mut(), cx) {
+                            Poll::Ready((field, Ok(text))) => {
+                                texts.insert(field, text);
+                                *slot = None;
+                            }
+                            Poll::Ready((_, Err(e))) => {
+                                failure.get_or_insert(e);
+                                *slot = None;
+                            }
+                            Poll::Pending => done = false,
+                        }
+                    }
+                }
+                if done { Poll::Ready(()) } else { Poll::Pending }
+            })
+            .await;
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(texts),
+        }
+    }
+    /// Map the name of each locator field to its selector, e.g. for reporting which
+    /// locators a page uses.
+    ///
+    /// `resolver`, `skip` and `component` fields have no selector of their own and are left out.
+    pub fn selector_map(
+        &self,
+    ) -> std::collections::HashMap<&'static str, thirtyfour::By> {
+        std::collections::HashMap::from([
+            ("type", thirtyfour::By::clone(&self.r#type)),
+            ("ref", thirtyfour::By::clone(&self.r#ref)),
+            ("fn", thirtyfour::By::clone(&self.r#fn)),
+        ])
+    }
+    /// Get the locator of a field.
+    pub fn locator(&self, field: SearchFormField) -> &thirtyfour::By {
+        match field {
+            SearchFormField::Type => &self.r#type,
+            SearchFormField::Ref => &self.r#ref,
+            SearchFormField::Fn => &self.r#fn,
+        }
+    }
+}
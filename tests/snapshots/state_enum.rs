@@ -0,0 +1,283 @@
+/// The `Cart` state of [`Checkout`], with its locators and generated actions.
+struct CheckoutCart {
+    item: By,
+    total: By,
+}
+impl Default for CheckoutCart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl CheckoutCart {
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_item(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.item)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "item", self.item);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "item", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_item`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_item_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.item))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "item", self.item);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "item", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const ITEM_SELECTOR_DESC: &'static str = "By::Css(\".cart-item\")";
+    /// Click on the web element.
+    pub async fn click_item(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_item(driver).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "item", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "item"))),
+        }
+    }
+    /// Like [`Self::click_item`], waiting up to `timeout_secs` for the element instead.
+    pub async fn click_item_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> std::result::Result<(), anyhow::Error> {
+        match self.query_item_with_timeout(driver, timeout_secs).await {
+            Some(element) => {
+                element
+                    .click()
+                    .await
+                    .map_err(|e| anyhow::Error::msg(
+                        format!("Failed to click {}: {}", "item", e),
+                    ))?;
+                Ok(())
+            }
+            None => Err(anyhow::Error::msg(format!("Element {} not found", "item"))),
+        }
+    }
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_total(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.total)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "total", self.total);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "total", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_total`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_total_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.total))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "total", self.total);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "total", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const TOTAL_SELECTOR_DESC: &'static str = "By::Css(\".total\")";
+    /// Create the page object from the selectors declared for its fields.
+    pub fn new() -> Self {
+        Self {
+            item: thirtyfour::By::Css(".cart-item"),
+            total: thirtyfour::By::Css(".total"),
+        }
+    }
+    /// Map the name of each locator field to its selector, e.g. for reporting which
+    /// locators a page uses.
+    ///
+    /// `resolver`, `skip` and `component` fields have no selector of their own and are left out.
+    pub fn selector_map(
+        &self,
+    ) -> std::collections::HashMap<&'static str, thirtyfour::By> {
+        std::collections::HashMap::from([
+            ("item", thirtyfour::By::clone(&self.item)),
+            ("total", thirtyfour::By::clone(&self.total)),
+        ])
+    }
+}
+impl From<CheckoutCart> for Checkout {
+    fn from(state: CheckoutCart) -> Self {
+        let CheckoutCart { item, total } = state;
+        Self::Cart { item, total }
+    }
+}
+/// The `Done` state of [`Checkout`], with its locators and generated actions.
+struct CheckoutDone {
+    thanks: By,
+}
+impl Default for CheckoutDone {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl CheckoutDone {
+    /// Query the web element from the DOM.
+    ///
+    /// Returns `Some(WebElement)` if found, `None` otherwise.
+    pub async fn query_thanks(
+        &self,
+        driver: &thirtyfour::WebDriver,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver.query(thirtyfour::By::clone(&self.thanks)).first_opt().await {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "thanks", self.thanks);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "thanks", e);
+                None
+            }
+        }
+    }
+    /// Like [`Self::query_thanks`], waiting up to `timeout_secs` for the element to appear.
+    pub async fn query_thanks_with_timeout(
+        &self,
+        driver: &thirtyfour::WebDriver,
+        timeout_secs: u64,
+    ) -> Option<thirtyfour::WebElement> {
+        match driver
+            .query(thirtyfour::By::clone(&self.thanks))
+            .wait(
+                std::time::Duration::from_secs(timeout_secs),
+                std::time::Duration::from_millis(500u64),
+            )
+            .first_opt()
+            .await
+        {
+            Ok(Some(element)) => Some(element),
+            Ok(None) => {
+                log::debug!("Element {} not found ({:?})", "thanks", self.thanks);
+                None
+            }
+            Err(e) => {
+                log::error!("Error querying element {}: {}", "thanks", e);
+                None
+            }
+        }
+    }
+    /// The inline selector of this field, rendered like its `By` constructor.
+    pub const THANKS_SELECTOR_DESC: &'static str = "By::Css(\".thanks\")";
+    /// Create the page object from the selectors declared for its fields.
+    pub fn new() -> Self {
+        Self {
+            thanks: thirtyfour::By::Css(".thanks"),
+        }
+    }
+    /// Map the name of each locator field to its selector, e.g. for reporting which
+    /// locators a page uses.
+    ///
+    /// `resolver`, `skip` and `component` fields have no selector of their own and are left out.
+    pub fn selector_map(
+        &self,
+    ) -> std::collections::HashMap<&'static str, thirtyfour::By> {
+        std::collections::HashMap::from([
+            ("thanks", thirtyfour::By::clone(&self.thanks)),
+        ])
+    }
+}
+impl From<CheckoutDone> for Checkout {
+    fn from(state: CheckoutDone) -> Self {
+        let CheckoutDone { thanks } = state;
+        Self::Done { thanks }
+    }
+}
+impl Checkout {
+    /// Find which state the page is in by probing the anchor element of each state, in
+    /// declaration order, without waiting.
+    ///
+    /// Returns the first state whose anchor is present, built with its selectors, or `None`
+    /// if no anchor is.
+    pub async fn detect_state(driver: &thirtyfour::WebDriver) -> Option<Self> {
+        let state = CheckoutCart::new();
+        if state.query_total_with_timeout(driver, 0).await.is_some() {
+            return Some(Self::from(state));
+        }
+        let state = CheckoutDone::new();
+        if state.query_thanks_with_timeout(driver, 0).await.is_some() {
+            return Some(Self::from(state));
+        }
+        None
+    }
+    /// The fields of the `Cart` state as a [`CheckoutCart`], to run its actions, or `None` in another state.
+    pub fn into_cart(self) -> Option<CheckoutCart> {
+        match self {
+            Self::Cart { item, total } => Some(CheckoutCart { item, total }),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+    /// The fields of the `Done` state as a [`CheckoutDone`], to run its actions, or `None` in another state.
+    pub fn into_done(self) -> Option<CheckoutDone> {
+        match self {
+            Self::Done { thanks } => Some(CheckoutDone { thanks }),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
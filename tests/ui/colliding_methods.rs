@@ -0,0 +1,14 @@
+use impl_thirtyfour_actions::ImplThirtyfourActions;
+
+struct By;
+
+// `wait_for_value` on `total` and `wait_for` on `value_total` both generate `wait_for_value_total`
+#[derive(ImplThirtyfourActions)]
+struct CartPage {
+    #[thirtyfour_actions(css = "#total", methods(wait_for_value))]
+    total: By,
+    #[thirtyfour_actions(css = "#value-total", methods(wait_for))]
+    value_total: By,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use impl_thirtyfour_actions::ImplThirtyfourActions;
+
+struct By;
+
+// Renaming `submit` to `value_total` makes it collide with the other field's methods
+#[derive(ImplThirtyfourActions)]
+struct CartPage {
+    #[thirtyfour_actions(css = "#total", methods(wait_for_value))]
+    total: By,
+    #[thirtyfour_actions(rename = "value_total", css = "#submit", methods(wait_for))]
+    submit: By,
+}
+
+fn main() {}